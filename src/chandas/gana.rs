@@ -1,4 +1,17 @@
-use crate::chandas::{Maatra,Kaala};
+use crate::chandas::{Kaala, Maatra};
+
+/// The eight trika ganas of Piṅgala by their pattern of laghu (false) and
+/// guru (true) syllables, with their names in Latin and Devanagari.
+const TRIKA: [([bool; 3], &str, &str); 8] = [
+    ([false, true, true], "ya", "य"),
+    ([true, true, true], "ma", "म"),
+    ([true, true, false], "ta", "त"),
+    ([true, false, true], "ra", "र"),
+    ([false, true, false], "ja", "ज"),
+    ([true, false, false], "bha", "भ"),
+    ([false, false, false], "na", "न"),
+    ([false, false, true], "sa", "स"),
+];
 
 /// `Gana` represents a grouping of Mātrās forming a word or syllabic unit.
 #[derive(Debug, PartialEq, Eq)]
@@ -19,10 +32,37 @@ impl Gana {
 
     /// Returns the total Kaala count of the `Gana`.
     pub fn total_kaala(&self) -> u32 {
-        self.maatras.iter().map(|m| match m.length {
-            Kaala::One => 1,
-            Kaala::Two => 2,
-            Kaala::Three => 3,
-        }).sum()
+        self.maatras.iter().map(|m| u32::from(m.length)).sum()
+    }
+
+    /// Divides a line into trika ganas of three syllables, leaving the
+    /// syllables that do not fill a gana as single-syllable groups.
+    pub fn divide(maatras: &[Maatra]) -> Vec<Gana> {
+        let trikas = maatras.len() / 3 * 3;
+        maatras[..trikas].chunks(3)
+            .chain(maatras[trikas..].chunks(1))
+            .map(|chunk| Gana::new(chunk.to_vec()))
+            .collect()
+    }
+
+    /// Returns the name of the gana: `ya`, `ma`, `ta`, `ra`, `ja`, `bha`,
+    /// `na` or `sa` for three syllables, `la` or `ga` for one. A pluta
+    /// syllable counts as guru.
+    pub fn name(&self) -> Option<&'static str> {
+        self.lookup().map(|(name, _)| name)
+    }
+
+    /// Returns the name of the gana as its Devanagari letter (`य`, `ल`, `ग`, ...).
+    pub fn letter(&self) -> Option<&'static str> {
+        self.lookup().map(|(_, letter)| letter)
+    }
+
+    fn lookup(&self) -> Option<(&'static str, &'static str)> {
+        let pattern: Vec<bool> = self.maatras.iter().map(|m| m.length != Kaala::One).collect();
+        match pattern[..] {
+            [false] => Some(("la", "ल")),
+            [true] => Some(("ga", "ग")),
+            _ => TRIKA.iter().find(|(trika, _, _)| trika[..] == pattern[..]).map(|&(_, name, letter)| (name, letter)),
+        }
     }
 }
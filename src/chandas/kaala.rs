@@ -1,3 +1,7 @@
+use std::iter::Sum;
+use std::ops::Add;
+use crate::shiksha::{Varna, Matra};
+
 /// `Kaala` represents a unit of metrical time.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Kaala {
@@ -5,3 +9,65 @@ pub enum Kaala {
     Two,   // Guru (2 Kaala units)
     Three, // Pluta (3 Kaala units)
 }
+
+/// A vowel lasts as many Kaalas as its Matra: hrasva one, diirgha two, pluta three.
+impl From<Matra> for Kaala {
+    fn from(matra: Matra) -> Self {
+        match matra {
+            Matra::Hrasva => Kaala::One,
+            Matra::Diirgha => Kaala::Two,
+            Matra::Pluta => Kaala::Three,
+        }
+    }
+}
+
+/// The number of morae (mātrās) in a Kaala.
+impl From<Kaala> for u32 {
+    fn from(kaala: Kaala) -> Self {
+        match kaala {
+            Kaala::One => 1,
+            Kaala::Two => 2,
+            Kaala::Three => 3,
+        }
+    }
+}
+
+/// A duration counted in half morae. The Shiksha gives a consonant half the
+/// time of a short vowel (`vyañjanam ardhamātrikam`), so durations of
+/// phonemes are only whole in halves.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Hash)]
+pub struct ArdhaMaatra(pub u32);
+
+impl ArdhaMaatra {
+    /// Returns the duration in morae.
+    pub fn maatras(self) -> f32 {
+        self.0 as f32 / 2.0
+    }
+}
+
+impl From<Kaala> for ArdhaMaatra {
+    fn from(kaala: Kaala) -> Self {
+        ArdhaMaatra(2 * u32::from(kaala))
+    }
+}
+
+/// Vowels last as long as their Matra; consonants and ayogavahas half a mora.
+impl From<&Varna> for ArdhaMaatra {
+    fn from(varna: &Varna) -> Self {
+        varna.matra.map_or(ArdhaMaatra(1), |matra| Kaala::from(matra).into())
+    }
+}
+
+impl Add for ArdhaMaatra {
+    type Output = ArdhaMaatra;
+
+    fn add(self, other: ArdhaMaatra) -> ArdhaMaatra {
+        ArdhaMaatra(self.0 + other.0)
+    }
+}
+
+impl Sum for ArdhaMaatra {
+    fn sum<I: Iterator<Item = ArdhaMaatra>>(iter: I) -> Self {
+        iter.fold(ArdhaMaatra::default(), Add::add)
+    }
+}
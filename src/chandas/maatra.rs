@@ -1,4 +1,5 @@
 use crate::chandas::{Kaala};
+use crate::shiksha::Matra;
 
 /// `Maatra` represents a single metrical unit (Laghu, Guru, or Pluta).
 #[derive(Clone,Debug, PartialEq, Eq)]
@@ -22,3 +23,16 @@ impl Maatra {
         Maatra { length: Kaala::Three }
     }
 }
+
+impl From<Kaala> for Maatra {
+    fn from(length: Kaala) -> Self {
+        Maatra { length }
+    }
+}
+
+/// The weight of an open syllable, which is that of its vowel.
+impl From<Matra> for Maatra {
+    fn from(matra: Matra) -> Self {
+        Kaala::from(matra).into()
+    }
+}
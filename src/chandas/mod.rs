@@ -1,8 +1,18 @@
-pub mod maatra; 
-pub mod gana;  
+pub mod maatra;
 pub mod kaala;
+#[cfg(feature = "chandas")]
+pub mod gana;
+#[cfg(feature = "chandas")]
+pub mod vibhaga;
+#[cfg(feature = "chandas")]
+pub mod prastara;
 
 // Re-export for easier access in other modules
 pub use maatra::Maatra;
+pub use kaala::{Kaala, ArdhaMaatra};
+#[cfg(feature = "chandas")]
 pub use gana::Gana;
-pub use kaala::Kaala;
+#[cfg(feature = "chandas")]
+pub use vibhaga::{syllabify, Syllable, VibhagaOptions};
+#[cfg(feature = "chandas")]
+pub use prastara::{prastara, MarkStyle, PrastaraOptions};
@@ -0,0 +1,210 @@
+use crate::shiksha::{lipi, TransliterationScheme, Vaakya};
+use crate::chandas::{syllabify, Gana, Kaala, Maatra, Syllable, VibhagaOptions};
+
+const RESET: &str = "\x1b[0m";
+
+/// The marks written under each syllable.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MarkStyle {
+    #[default]
+    Latin,      // U for laghu, — for guru
+    Devanagari, // ल for laghu, ग for guru
+}
+
+impl MarkStyle {
+    /// Returns the mark for a syllable weight. Pluta is the guru mark followed by 3.
+    pub fn mark(self, kaala: Kaala) -> &'static str {
+        match (self, kaala) {
+            (MarkStyle::Latin, Kaala::One) => "U",
+            (MarkStyle::Latin, Kaala::Two) => "—",
+            (MarkStyle::Latin, Kaala::Three) => "—3",
+            (MarkStyle::Devanagari, Kaala::One) => "ल",
+            (MarkStyle::Devanagari, Kaala::Two) => "ग",
+            (MarkStyle::Devanagari, Kaala::Three) => "ग३",
+        }
+    }
+}
+
+/// Options for printing the scansion of a line of verse.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PrastaraOptions {
+    pub scheme: TransliterationScheme, // Script the syllables are written in
+    pub marks: MarkStyle,              // Marks written under the syllables
+    pub color: bool,                   // Color by weight with ANSI escapes, for terminals
+    pub pada_length: Option<usize>,    // Syllables in a pāda (verse quarter); the whole line is one pāda if None
+    pub yati: Vec<usize>,              // Syllables into a pāda after which a caesura (yati) falls
+    pub vibhaga: VibhagaOptions,       // How the line is divided into syllables
+}
+
+impl Default for PrastaraOptions {
+    fn default() -> Self {
+        PrastaraOptions {
+            scheme: TransliterationScheme::Iast,
+            marks: MarkStyle::Latin,
+            color: false,
+            pada_length: None,
+            yati: Vec::new(),
+            vibhaga: VibhagaOptions::default(),
+        }
+    }
+}
+
+/// A column of the printed scansion.
+enum Cell {
+    Syllable(usize), // Index of the syllable within the pāda
+    Yati,
+}
+
+/// Prints the scansion of a line: each pāda as its syllables, the weight
+/// marks aligned under them followed by the pāda's total of mātrās, and the
+/// trika ganas bracketed under the marks. Yati is marked with `|`.
+///
+/// ```
+/// use pss::{Lexer, parse};
+/// use pss::chandas::{prastara, PrastaraOptions};
+///
+/// let vaakyas = parse(&Lexer::new("kazcit kAntAvirahaguruNA").tokenize());
+/// let printed = prastara(&vaakyas[0], &PrastaraOptions { yati: vec![4], ..PrastaraOptions::default() });
+/// assert_eq!(printed, "\
+/// kaś cit kān tā | vi ra ha gu ru ṇā
+///  —   —   —  —  | U  U  U  U  U  —  = 15
+/// [   ma    ] [  bha   ] [  na  ] ga");
+/// ```
+pub fn prastara(vaakya: &Vaakya, options: &PrastaraOptions) -> String {
+    let syllables = syllabify(vaakya, &options.vibhaga);
+    let padas: Vec<&[Syllable]> = match options.pada_length {
+        Some(length) if length > 0 => syllables.chunks(length).collect(),
+        _ => vec![&syllables[..]],
+    };
+
+    padas.iter().filter(|pada| !pada.is_empty()).map(|pada| render_pada(pada, options)).collect::<Vec<_>>().join("\n")
+}
+
+fn render_pada(syllables: &[Syllable], options: &PrastaraOptions) -> String {
+    let texts: Vec<String> = syllables.iter().map(|s| lipi::render(&s.varnas, options.scheme)).collect();
+    let marks: Vec<&str> = syllables.iter().map(|s| options.marks.mark(s.maatra.length)).collect();
+
+    let mut cells = Vec::new();
+    for i in 0..syllables.len() {
+        if i > 0 && options.yati.contains(&i) {
+            cells.push(Cell::Yati);
+        }
+        cells.push(Cell::Syllable(i));
+    }
+    let widths: Vec<usize> = cells.iter().map(|cell| match cell {
+        Cell::Syllable(i) => width(&texts[*i]).max(width(marks[*i])),
+        Cell::Yati => 1,
+    }).collect();
+
+    // The syllables and the marks under them
+    let row = |content: &[&str]| -> String {
+        cells.iter().zip(&widths).map(|(cell, &w)| match cell {
+            Cell::Syllable(i) => paint(&center(content[*i], w), weight_color(syllables[*i].maatra.length), options.color),
+            Cell::Yati => paint("|", "\x1b[31m", options.color),
+        }).collect::<Vec<_>>().join(" ")
+    };
+    let text_row = row(&texts.iter().map(String::as_str).collect::<Vec<_>>());
+    let maatras: u32 = syllables.iter().map(|s| u32::from(s.maatra.length)).sum();
+    let mark_row = format!("{}  = {}", row(&marks).trim_end(), maatras);
+
+    // The ganas, each bracketed under the columns of its syllables
+    let cell_of = |syllable: usize| cells.iter().position(|c| matches!(c, Cell::Syllable(i) if *i == syllable)).unwrap_or(0);
+    let ganas = Gana::divide(&syllables.iter().map(|s| s.maatra.clone()).collect::<Vec<Maatra>>());
+    let mut gana_row = Vec::new();
+    let (mut cell, mut syllable) = (0, 0);
+    for gana in &ganas {
+        let (first, last) = (cell_of(syllable), cell_of(syllable + gana.maatras.len() - 1));
+        gana_row.extend(widths[cell..first].iter().map(|&w| " ".repeat(w)));
+
+        let w = widths[first..=last].iter().sum::<usize>() + last - first;
+        let name = match options.marks {
+            MarkStyle::Latin => gana.name(),
+            MarkStyle::Devanagari => gana.letter(),
+        }.unwrap_or("");
+        let label = if gana.maatras.len() == 3 { bracket(name, w) } else { center(name, w) };
+        gana_row.push(paint(&label, "\x1b[2m", options.color));
+
+        cell = last + 1;
+        syllable += gana.maatras.len();
+    }
+
+    [text_row, mark_row, gana_row.join(" ")].iter().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n")
+}
+
+fn weight_color(kaala: Kaala) -> &'static str {
+    match kaala {
+        Kaala::One => "\x1b[36m",     // Cyan
+        Kaala::Two => "\x1b[1;33m",   // Bold yellow
+        Kaala::Three => "\x1b[1;35m", // Bold magenta
+    }
+}
+
+fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if enabled { format!("{}{}{}", color, text, RESET) } else { text.to_string() }
+}
+
+/// Centers text in a column of the given width.
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(self::width(text));
+    format!("{}{}{}", " ".repeat(padding / 2), text, " ".repeat(padding - padding / 2))
+}
+
+/// Brackets a gana name across a column of the given width.
+fn bracket(name: &str, width: usize) -> String {
+    format!("[{}]", center(name, width.saturating_sub(2)))
+}
+
+/// The number of terminal columns text takes: combining marks, such as
+/// diacritics, Devanagari vowel signs and viramas, take none.
+fn width(text: &str) -> usize {
+    text.chars().filter(|&c| !matches!(c,
+        '\u{0300}'..='\u{036F}' | '\u{0900}'..='\u{0903}' | '\u{093A}'..='\u{094F}' | '\u{0951}'..='\u{0957}' |
+        '\u{0962}'..='\u{0963}' | '\u{1CD0}'..='\u{1CF4}' | '\u{1CF7}'..='\u{1CFF}' | '\u{A8E0}'..='\u{A8F1}' |
+        '\u{200C}' | '\u{200D}') || c == 'ऽ').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{self, Lexer};
+
+    fn print(line: &str, options: &PrastaraOptions) -> String {
+        let vaakyas = niruktam::parse(&Lexer::with_scheme(line, TransliterationScheme::HarvardKyoto).tokenize());
+        prastara(&vaakyas[0], options)
+    }
+
+    #[test]
+    fn ganas() {
+        let names = |pattern: &str| -> Vec<&str> {
+            let maatras: Vec<Maatra> = pattern.chars().map(|c| if c == 'G' { Maatra::guru() } else { Maatra::laghu() }).collect();
+            Gana::divide(&maatras).iter().filter_map(Gana::name).collect()
+        };
+        // Mandakranta: ma bha na ta ta ga ga
+        assert_eq!(names("GGGGLLLLLGGLGGLGG"), ["ma", "bha", "na", "ta", "ta", "ga", "ga"]);
+        assert_eq!(names("LGGGLGLGLLLLLLG"), ["ya", "ra", "ja", "na", "sa"]);
+    }
+
+    #[test]
+    fn padas_in_devanagari() {
+        let options = PrastaraOptions {
+            scheme: TransliterationScheme::HarvardKyoto,
+            marks: MarkStyle::Devanagari,
+            pada_length: Some(8),
+            ..PrastaraOptions::default()
+        };
+        assert_eq!(print("dharmakSetre kurukSetre samavetA yuyutsavaH", &options), "\
+dhar mak Set re ku ruk Set re
+ ग    ग   ग  ग  ल   ग   ग  ग  = 15
+[    म     ] [   र   ]  ग  ग
+sa ma ve tA yu yut sa vaH
+ल  ल  ग  ग  ल   ग  ल   ग  = 12
+[  स   ] [   र   ] ल   ग");
+    }
+
+    #[test]
+    fn color() {
+        let options = PrastaraOptions { color: true, ..PrastaraOptions::default() };
+        let printed = print("rAma", &options);
+        assert!(printed.starts_with("\x1b[1;33mrā\x1b[0m \x1b[36mma\x1b[0m"), "{printed:?}");
+    }
+}
@@ -0,0 +1,154 @@
+use crate::shiksha::{Varna, Vaakya};
+use crate::chandas::Maatra;
+
+/// Options for dividing a line of verse into metrical syllables.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct VibhagaOptions {
+    pub padanta_guru: bool, // Count the last syllable of a line as guru whatever its weight (pādānta-guru)
+}
+
+/// A metrical syllable: a vowel with the consonants before it that it takes
+/// as onset and the consonants after it that close it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Syllable {
+    pub varnas: Vec<Varna>, // Onset, vowel and coda
+    pub pada: usize,        // Index of the Pada the vowel belongs to
+    pub maatra: Maatra,     // Weight, counting the coda taken from the next Pada
+    pub padanta: bool,      // Last syllable of the line
+}
+
+/// Divides a line of verse into syllables for scansion.
+///
+/// Aksharas from the lexer end at their vowel, so the consonants closing a
+/// syllable sit in the next Akshara, often in the next Pada (`rāmas tu`).
+/// Prosody reads the whole line as one stream: of the consonants between two
+/// vowels only the last opens the next syllable, and the rest close the
+/// previous one and make it guru. Anusvara and visarga always close their
+/// syllable, and consonants after the last vowel close the last syllable.
+///
+/// ```
+/// use pss::{Lexer, parse};
+/// use pss::chandas::{syllabify, Maatra, VibhagaOptions};
+///
+/// let vaakyas = parse(&Lexer::new("rAmas tu").tokenize());
+/// let syllables = syllabify(&vaakyas[0], &VibhagaOptions::default());
+/// let guru: Vec<bool> = syllables.iter().map(|s| s.maatra == Maatra::guru()).collect();
+/// assert_eq!(guru, [true, true, false]);
+/// ```
+pub fn syllabify(vaakya: &Vaakya, options: &VibhagaOptions) -> Vec<Syllable> {
+    // The line as one stream of Varnas, each with the index of its Pada
+    let (padas, varnas): (Vec<usize>, Vec<Varna>) = vaakya.padas.iter().enumerate()
+        .flat_map(|(i, pada)| pada.aksharas.iter().flat_map(move |a| a.varnas.iter().map(move |v| (i, *v))))
+        .unzip();
+    let nuclei: Vec<usize> = (0..varnas.len()).filter(|&i| varnas[i].is_vowel()).collect();
+
+    let mut syllables = Vec::new();
+    for (n, &nucleus) in nuclei.iter().enumerate() {
+        // The syllable starts where the previous one ended, or at the beginning of the line
+        let start = syllables.iter().map(|s: &Syllable| s.varnas.len()).sum();
+        let end = match nuclei.get(n + 1) {
+            Some(&next) => onset_start(&varnas[nucleus + 1..next]) + nucleus + 1,
+            None => varnas.len(),
+        };
+
+        syllables.push(Syllable {
+            varnas: varnas[start..end].to_vec(),
+            pada: padas[nucleus],
+            maatra: weight(varnas[nucleus], end > nucleus + 1),
+            padanta: n + 1 == nuclei.len(),
+        });
+    }
+
+    if options.padanta_guru {
+        if let Some(last) = syllables.last_mut().filter(|s| s.maatra == Maatra::laghu()) {
+            last.maatra = Maatra::guru();
+        }
+    }
+
+    syllables
+}
+
+/// Returns the index within `between` (the Varnas between two vowels) at
+/// which the next syllable's onset starts.
+fn onset_start(between: &[Varna]) -> usize {
+    match between.last() {
+        Some(last) if last.is_consonant() => between.len() - 1,
+        _ => between.len(), // Nothing, or only an anusvara or visarga, stands before the vowel
+    }
+}
+
+/// A syllable weighs as much as its vowel, except that a consonant or
+/// ayogavaha closing it makes a short syllable guru.
+fn weight(vowel: Varna, closed: bool) -> Maatra {
+    match vowel.matra.map(Maatra::from) {
+        Some(maatra) if maatra == Maatra::laghu() && closed => Maatra::guru(),
+        Some(maatra) => maatra,
+        None => Maatra::laghu(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chandas::Kaala;
+    use crate::niruktam::{self, Lexer};
+    use crate::shiksha::TransliterationScheme;
+
+    /// Scans a line written in Harvard-Kyoto as a string of L (laghu), G (guru) and P (pluta).
+    fn scan(line: &str, options: &VibhagaOptions) -> String {
+        scan_in(line, TransliterationScheme::HarvardKyoto, options)
+    }
+
+    fn scan_in(line: &str, scheme: TransliterationScheme, options: &VibhagaOptions) -> String {
+        let tokens = Lexer::with_scheme(line, scheme).tokenize();
+        let vaakyas = niruktam::parse(&tokens);
+        syllabify(&vaakyas[0], options).iter().map(|s| match s.maatra.length {
+            Kaala::One => 'L',
+            Kaala::Two => 'G',
+            Kaala::Three => 'P',
+        }).collect()
+    }
+
+    #[test]
+    fn anushtubh() {
+        // Bhagavad Gita 1.1
+        assert_eq!(scan("dharmakSetre kurukSetre", &VibhagaOptions::default()), "GGGGLGGG");
+        assert_eq!(scan("samavetA yuyutsavaH", &VibhagaOptions::default()), "LLGGLGLG");
+    }
+
+    #[test]
+    fn consonants_cross_word_boundaries() {
+        // Ramayana 1.2.15: the m of `tvam` opens the first syllable of `agamaH`
+        assert_eq!(scan("mA niSAda pratiSThAM tvam agamaH zAzvatIH samAH", &VibhagaOptions::default()), "GLGGLGGLLLGGLGLG");
+        // The coda of `rAmas` comes from the same word, that of `tat` from the next
+        assert_eq!(scan("rAmas tu", &VibhagaOptions::default()), "GGL");
+        assert_eq!(scan("tat atra", &VibhagaOptions::default()), "LGL");
+    }
+
+    #[test]
+    fn mandakranta() {
+        // Meghaduta 1: ma bha na ta ta ga ga
+        assert_eq!(scan("kazcit kAntAvirahaguruNA svAdhikArAt pramattaH", &VibhagaOptions::default()), "GGGGLLLLLGGLGGLGG");
+    }
+
+    #[test]
+    fn ayogavahas_close_a_syllable() {
+        assert_eq!(scan("saMskRtam", &VibhagaOptions::default()), "GLG");
+        assert_eq!(scan("namaH", &VibhagaOptions::default()), "LG");
+        assert_eq!(scan("taZ kaH", &VibhagaOptions::default()), "GG");
+        assert_eq!(scan("puruSaV pavitraH", &VibhagaOptions::default()), "LLGLGG");
+    }
+
+    #[test]
+    fn pluta_vowels() {
+        assert_eq!(scan("o3m", &VibhagaOptions::default()), "P");
+        assert_eq!(scan_in("ओ३म्", TransliterationScheme::Devanagari, &VibhagaOptions::default()), "P");
+        assert_eq!(scan("devadatta3", &VibhagaOptions::default()), "GLGP");
+    }
+
+    #[test]
+    fn padanta_guru() {
+        assert_eq!(scan("rAma", &VibhagaOptions::default()), "GL");
+        assert_eq!(scan("rAma", &VibhagaOptions { padanta_guru: true }), "GG");
+    }
+}
@@ -0,0 +1,550 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use pss::{niruktam, Lexer, Token, TransliterationScheme};
+use pss::chandas::Kaala;
+use pss::shiksha::lipi;
+
+pub const USAGE: &str = "\
+Usage:
+  pss                             start the interactive REPL
+  pss lex [OPTIONS] [FILE...]     list the Aksharas of the input with their positions and weights
+  pss translit [OPTIONS] [FILE...] transliterate the input
+  pss scan [OPTIONS] [FILE...]    scan each line into laghu and guru syllables
+  pss sandhi [OPTIONS] [A B]      join two words, or each pair of words on the input lines
+  pss split [OPTIONS] [WORD...]   split words, or each word on the input lines, at a sandhi
+  pss html [OPTIONS] [FILE...]    write the input as annotated HTML
+  pss latex [OPTIONS] [FILE...]   write the input as annotated LaTeX tables
+  pss conllu [OPTIONS] [FILE...]  write each Vaakya of the input as a CoNLL-U sentence, one Pada per row
+  pss help                        show this help
+
+FILE - or no FILE reads standard input.
+
+Options:
+  -f, --from SCHEME      read input in SCHEME, or auto (the default) or lenient
+  -t, --to SCHEME        write Sanskrit in SCHEME (default iast, devanagari for html and latex)
+  -o, --output FILE      write to FILE instead of standard output
+      --format FORMAT    plain (the default), tsv, or json (one object per line)
+      --padanta-guru     count the last syllable of a line as guru when scanning
+      --reading SCHEME   annotate exported Aksharas in SCHEME, or none (default iast)
+      --accents          mark the Swaras in exported text
+
+Schemes: hk, devanagari, iast, slp1, itrans, velthuis.
+
+Exit status: 0 on success, 1 if the input had problems (reported on standard
+error), 2 on a usage error, 3 if a file could not be read or written.";
+
+/// How input is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Auto,                          // Detect the scheme of each script run
+    Scheme(TransliterationScheme), // Read everything in one scheme
+    Lenient,                       // Read casual romanization
+}
+
+impl Input {
+    pub fn name(self) -> &'static str {
+        match self {
+            Input::Auto => "auto",
+            Input::Scheme(scheme) => scheme.name(),
+            Input::Lenient => "lenient",
+        }
+    }
+
+    /// Reads `auto`, `lenient` or the name of a scheme.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Input::Auto),
+            "lenient" => Ok(Input::Lenient),
+            _ => name.parse().map(Input::Scheme).map_err(|err: pss::shiksha::ParseNameError| err.to_string()),
+        }
+    }
+
+    /// Lexes text as this input setting reads it.
+    pub fn lex(self, text: &str) -> niruktam::Lexed {
+        match self {
+            Input::Auto => Lexer::new(text).tokenize_with_diagnostics(),
+            Input::Scheme(scheme) => Lexer::with_scheme(text, scheme).tokenize_with_diagnostics(),
+            Input::Lenient => {
+                let lexing = Lexer::lenient(text).tokenize_lenient();
+                niruktam::Lexed { tokens: lexing.tokens, diagnostics: lexing.diagnostics }
+            }
+        }
+    }
+}
+
+/// The Varnas of all Aksharas in a token stream.
+#[cfg(feature = "vyakaran")]
+pub fn varnas(tokens: &[Token]) -> Vec<pss::Varna> {
+    tokens.iter().flat_map(|token| match token {
+        Token::Akshara(akshara, _) => akshara.varnas.clone(),
+        Token::Unknown(..) => Vec::new(),
+    }).collect()
+}
+
+/// Marks a weight as L (laghu), G (guru) or P (pluta).
+pub fn mark(kaala: Kaala) -> char {
+    match kaala {
+        Kaala::One => 'L',
+        Kaala::Two => 'G',
+        Kaala::Three => 'P',
+    }
+}
+
+/// Reads the two words of a sandhi, written `A + B` or `A B`.
+#[cfg(feature = "vyakaran")]
+pub fn word_pair(text: &str) -> Option<(&str, &str)> {
+    let (first, second) = text.split_once('+').or_else(|| text.trim().split_once(char::is_whitespace))?;
+    let (first, second) = (first.trim(), second.trim());
+    (!first.is_empty() && !second.is_empty() && !second.contains(char::is_whitespace)).then_some((first, second))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Lex,
+    Translit,
+    Scan,
+    Sandhi,
+    Split,
+    Html,
+    Latex,
+    Conllu,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Plain,
+    Tsv,
+    Json,
+}
+
+/// A parsed command line.
+#[derive(Debug)]
+struct Options {
+    command: Command,
+    input: Input,
+    output: TransliterationScheme,
+    format: Format,
+    destination: Option<String>,            // Output file; standard output if none
+    padanta_guru: bool,                     // Scan the last syllable of a line as guru
+    reading: Option<TransliterationScheme>, // Transliteration annotating exported text
+    accents: bool,                          // Mark Swaras in exported text
+    arguments: Vec<String>,                 // Files, or words for sandhi and split
+}
+
+/// Why the command could not run.
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),         // The command line is wrong
+    Io(String, io::Error), // A file could not be read or written
+}
+
+impl CliError {
+    /// The exit status for the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Io(..) => 3,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Io(path, err) => write!(f, "{}: {}", path, err),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// A value in an output record.
+enum Field {
+    Text(String),
+    Number(usize),
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Field::Text(text) => f.write_str(text),
+            Field::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// One record of output, with its plain-text form and its named fields for TSV and JSON.
+struct Row {
+    plain: String,
+    fields: Vec<(&'static str, Field)>,
+}
+
+/// Runs a subcommand and returns the exit status.
+pub fn run(args: &[String]) -> i32 {
+    if matches!(args.first().map(String::as_str), Some("help" | "-h" | "--help")) {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    match parse_args(args).and_then(|options| execute(&options)) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(err) => {
+            eprintln!("pss: {}", err);
+            if let CliError::Usage(_) = err {
+                eprintln!("Try 'pss help' for more information.");
+            }
+            err.exit_code()
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, CliError> {
+    let (command, rest) = args.split_first().ok_or_else(|| CliError::Usage("no command given".to_string()))?;
+    let command = match command.as_str() {
+        "lex" => Command::Lex,
+        "translit" => Command::Translit,
+        "scan" => Command::Scan,
+        "sandhi" => Command::Sandhi,
+        "split" => Command::Split,
+        "html" => Command::Html,
+        "latex" => Command::Latex,
+        "conllu" => Command::Conllu,
+        _ => return Err(CliError::Usage(format!("unknown command {:?}", command))),
+    };
+
+    let mut options = Options {
+        command,
+        input: Input::Auto,
+        output: TransliterationScheme::Iast,
+        format: Format::Plain,
+        destination: None,
+        padanta_guru: false,
+        reading: Some(TransliterationScheme::Iast),
+        accents: false,
+        arguments: Vec::new(),
+    };
+    if matches!(command, Command::Html | Command::Latex) {
+        options.output = TransliterationScheme::Devanagari;
+    }
+
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        let mut value = || rest.next().cloned().ok_or_else(|| CliError::Usage(format!("{} needs a value", arg)));
+        match arg.as_str() {
+            "-f" | "--from" => options.input = Input::parse(&value()?).map_err(CliError::Usage)?,
+            "-t" | "--to" => options.output = value()?.parse().map_err(|err| CliError::Usage(format!("{}", err)))?,
+            "-o" | "--output" => options.destination = Some(value()?),
+            "--format" => options.format = match value()?.as_str() {
+                "plain" => Format::Plain,
+                "tsv" => Format::Tsv,
+                "json" if cfg!(feature = "serialization") => Format::Json,
+                "json" => return Err(CliError::Usage("JSON output needs the serialization feature".to_string())),
+                other => return Err(CliError::Usage(format!("unknown format {:?}", other))),
+            },
+            "--padanta-guru" => options.padanta_guru = true,
+            "--reading" => options.reading = match value()?.as_str() {
+                "none" => None,
+                name => Some(name.parse().map_err(|err| CliError::Usage(format!("{}", err)))?),
+            },
+            "--accents" => options.accents = true,
+            "-" => options.arguments.push(arg.clone()),
+            _ if arg.starts_with('-') => return Err(CliError::Usage(format!("unknown option {}", arg))),
+            _ => options.arguments.push(arg.clone()),
+        }
+    }
+
+    if command == Command::Sandhi && !matches!(options.arguments.len(), 0 | 2) {
+        return Err(CliError::Usage("sandhi takes two words, or none to read pairs from standard input".to_string()));
+    }
+    Ok(options)
+}
+
+/// Runs the command, writing its output. Returns false if the input had problems.
+fn execute(options: &Options) -> Result<bool, CliError> {
+    let (rows, clean) = match options.command {
+        Command::Sandhi if options.arguments.len() == 2 => sandhi(options, &options.arguments.join(" "))?,
+        Command::Split if !options.arguments.is_empty() => split(options, &options.arguments.join("\n"))?,
+        Command::Sandhi | Command::Split => {
+            let text = read_source("-")?;
+            match options.command {
+                Command::Sandhi => sandhi(options, &text)?,
+                _ => split(options, &text)?,
+            }
+        }
+        Command::Lex | Command::Translit | Command::Scan | Command::Html | Command::Latex | Command::Conllu => {
+            let sources = if options.arguments.is_empty() { vec!["-".to_string()] } else { options.arguments.clone() };
+            let mut rows = Vec::new();
+            let mut clean = true;
+            for source in &sources {
+                let text = read_source(source)?;
+                let lexed = options.input.lex(&text);
+                for diagnostic in &lexed.diagnostics {
+                    eprintln!("{}:{}", source_name(source), diagnostic);
+                }
+                clean &= lexed.diagnostics.is_empty();
+                rows.extend(match options.command {
+                    Command::Lex => lex(options, &text, &lexed.tokens),
+                    Command::Translit => translit(options, &text, &lexed.tokens),
+                    Command::Scan => scan(options, &lexed.tokens)?,
+                    _ => vec![export(options, &lexed.tokens)],
+                });
+            }
+            (rows, clean)
+        }
+    };
+
+    write_rows(options, &rows)?;
+    Ok(clean)
+}
+
+fn lex(options: &Options, text: &str, tokens: &[Token]) -> Vec<Row> {
+    tokens.iter().filter_map(|token| match token {
+        Token::Akshara(akshara, span) => {
+            let written = lipi::render(&akshara.varnas, options.output);
+            let weight = mark(akshara.maatra.length);
+            Some(Row {
+                plain: format!("{}:{}\t{}\t{}", span.line, span.column, written, weight),
+                fields: vec![
+                    ("line", Field::Number(span.line)),
+                    ("column", Field::Number(span.column)),
+                    ("start", Field::Number(span.start)),
+                    ("end", Field::Number(span.end)),
+                    ("source", Field::Text(span.slice(text).to_string())),
+                    ("akshara", Field::Text(written)),
+                    ("weight", Field::Text(weight.to_string())),
+                ],
+            })
+        }
+        Token::Unknown(..) => None,
+    }).collect()
+}
+
+fn translit(options: &Options, text: &str, tokens: &[Token]) -> Vec<Row> {
+    let rendered = niruktam::render(tokens, options.output);
+    text.lines().zip(rendered.lines()).enumerate().map(|(i, (input, output))| Row {
+        plain: output.to_string(),
+        fields: vec![
+            ("line", Field::Number(i + 1)),
+            ("input", Field::Text(input.to_string())),
+            ("output", Field::Text(output.to_string())),
+        ],
+    }).collect()
+}
+
+/// Writes the parsed text as HTML, LaTeX or CoNLL-U, as one record.
+fn export(options: &Options, tokens: &[Token]) -> Row {
+    use pss::shiksha::{to_conllu, to_html, to_latex, ConlluSentence, ExportOptions};
+
+    let export_options = ExportOptions { script: options.output, reading: options.reading, accents: options.accents, scansion: true };
+    let vaakyas = niruktam::parse(tokens);
+    let (name, document) = match options.command {
+        Command::Html => ("html", to_html(&vaakyas, &export_options)),
+        Command::Conllu => ("conllu", to_conllu(&vaakyas.iter().map(ConlluSentence::from_vaakya).collect::<Vec<_>>(), options.output)),
+        _ => ("latex", to_latex(&vaakyas, &export_options)),
+    };
+    Row { plain: document.trim_end().to_string(), fields: vec![(name, Field::Text(document))] }
+}
+
+#[cfg(feature = "chandas")]
+fn scan(options: &Options, tokens: &[Token]) -> Result<Vec<Row>, CliError> {
+    use pss::chandas::{syllabify, VibhagaOptions};
+
+    let syllabify_options = VibhagaOptions { padanta_guru: options.padanta_guru };
+    let mut rows = Vec::new();
+
+    // Each input line is scanned on its own, even without a danda at its end
+    for line_tokens in tokens.chunk_by(|a, b| a.span().line == b.span().line) {
+        let line = line_tokens[0].span().line;
+        for vaakya in niruktam::parse(line_tokens) {
+            let syllables = syllabify(&vaakya, &syllabify_options);
+            let text = syllables.iter().map(|s| lipi::render(&s.varnas, options.output)).collect::<Vec<_>>().join(".");
+            let pattern: String = syllables.iter().map(|s| mark(s.maatra.length)).collect();
+            let maatras: u32 = syllables.iter().map(|s| u32::from(s.maatra.length)).sum();
+            rows.push(Row {
+                plain: format!("{}\t{}\t{}", text, pattern, maatras),
+                fields: vec![
+                    ("line", Field::Number(line)),
+                    ("syllables", Field::Text(text)),
+                    ("pattern", Field::Text(pattern)),
+                    ("count", Field::Number(syllables.len())),
+                    ("maatras", Field::Number(maatras as usize)),
+                ],
+            });
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(not(feature = "chandas"))]
+fn scan(_options: &Options, _tokens: &[Token]) -> Result<Vec<Row>, CliError> {
+    Err(CliError::Usage("scan needs the chandas feature".to_string()))
+}
+
+/// Joins the word pair on each line of `text`.
+#[cfg(feature = "vyakaran")]
+fn sandhi(options: &Options, text: &str) -> Result<(Vec<Row>, bool), CliError> {
+    let mut rows = Vec::new();
+    let mut clean = true;
+
+    for (i, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let Some((first, second)) = word_pair(line) else {
+            eprintln!("{}:{}: expected two words, found {:?}", source_name("-"), i + 1, line);
+            clean = false;
+            continue;
+        };
+        let joined = pss::vyakaran::join(&varnas(&options.input.lex(first).tokens), &varnas(&options.input.lex(second).tokens));
+        let written = joined.words().iter().map(|word| lipi::render(word, options.output)).collect::<Vec<_>>().join(" ");
+        let rule = joined.rule.unwrap_or("");
+        rows.push(Row {
+            plain: if rule.is_empty() { written.clone() } else { format!("{}\t{}", written, rule) },
+            fields: vec![
+                ("first", Field::Text(first.to_string())),
+                ("second", Field::Text(second.to_string())),
+                ("result", Field::Text(written)),
+                ("rule", Field::Text(rule.to_string())),
+            ],
+        });
+    }
+
+    Ok((rows, clean))
+}
+
+/// Lists the splits of each word in `text`, one word per line.
+#[cfg(feature = "vyakaran")]
+fn split(options: &Options, text: &str) -> Result<(Vec<Row>, bool), CliError> {
+    let mut rows = Vec::new();
+    let mut clean = true;
+
+    for word in text.split_whitespace() {
+        let splits = pss::vyakaran::split(&varnas(&options.input.lex(word).tokens));
+        if splits.is_empty() {
+            eprintln!("{}: no split found", word);
+            clean = false;
+        }
+        for s in splits {
+            let (first, second) = (lipi::render(&s.first, options.output), lipi::render(&s.second, options.output));
+            rows.push(Row {
+                plain: format!("{}\t{} + {}\t{}", word, first, second, s.rule),
+                fields: vec![
+                    ("word", Field::Text(word.to_string())),
+                    ("first", Field::Text(first)),
+                    ("second", Field::Text(second)),
+                    ("rule", Field::Text(s.rule.to_string())),
+                ],
+            });
+        }
+    }
+
+    Ok((rows, clean))
+}
+
+#[cfg(not(feature = "vyakaran"))]
+fn sandhi(_options: &Options, _text: &str) -> Result<(Vec<Row>, bool), CliError> {
+    Err(CliError::Usage("sandhi needs the vyakaran feature".to_string()))
+}
+
+#[cfg(not(feature = "vyakaran"))]
+fn split(_options: &Options, _text: &str) -> Result<(Vec<Row>, bool), CliError> {
+    Err(CliError::Usage("split needs the vyakaran feature".to_string()))
+}
+
+/// Reads a file, or standard input for `-`.
+fn read_source(source: &str) -> Result<String, CliError> {
+    if source == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map_err(|err| CliError::Io(source_name(source).to_string(), err))?;
+        Ok(text)
+    } else {
+        fs::read_to_string(source).map_err(|err| CliError::Io(source.to_string(), err))
+    }
+}
+
+fn source_name(source: &str) -> &str {
+    if source == "-" { "<stdin>" } else { source }
+}
+
+fn write_rows(options: &Options, rows: &[Row]) -> Result<(), CliError> {
+    let name = options.destination.as_deref().unwrap_or("<stdout>");
+    let io_error = |err| CliError::Io(name.to_string(), err);
+
+    let mut out: Box<dyn Write> = match &options.destination {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path).map_err(io_error)?)),
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+
+    if options.format == Format::Tsv {
+        if let Some(row) = rows.first() {
+            writeln!(out, "{}", row.fields.iter().map(|(name, _)| *name).collect::<Vec<_>>().join("\t")).map_err(io_error)?;
+        }
+    }
+    for row in rows {
+        let line = match options.format {
+            Format::Plain => row.plain.clone(),
+            Format::Tsv => row.fields.iter().map(|(_, value)| tsv_field(&value.to_string())).collect::<Vec<_>>().join("\t"),
+            Format::Json => json_object(&row.fields)?,
+        };
+        writeln!(out, "{}", line).map_err(io_error)?;
+    }
+    out.flush().map_err(io_error)
+}
+
+/// Keeps a TSV field on one line and in one column.
+fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(feature = "serialization")]
+fn json_object(fields: &[(&'static str, Field)]) -> Result<String, CliError> {
+    let object: serde_json::Map<String, serde_json::Value> = fields.iter().map(|(name, value)| {
+        let value = match value {
+            Field::Text(text) => serde_json::Value::from(text.as_str()),
+            Field::Number(n) => serde_json::Value::from(*n),
+        };
+        (name.to_string(), value)
+    }).collect();
+    Ok(serde_json::Value::Object(object).to_string())
+}
+
+#[cfg(not(feature = "serialization"))]
+fn json_object(_fields: &[(&'static str, Field)]) -> Result<String, CliError> {
+    Err(CliError::Usage("JSON output needs the serialization feature".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn options() {
+        let options = parse_args(&args("translit --from hk -t devanagari --format tsv -o out.tsv a.txt -")).unwrap();
+        assert_eq!(options.command, Command::Translit);
+        assert_eq!(options.input, Input::Scheme(TransliterationScheme::HarvardKyoto));
+        assert_eq!(options.output, TransliterationScheme::Devanagari);
+        assert_eq!(options.format, Format::Tsv);
+        assert_eq!(options.destination.as_deref(), Some("out.tsv"));
+        assert_eq!(options.arguments, ["a.txt", "-"]);
+    }
+
+    #[test]
+    fn usage_errors() {
+        for line in ["", "parse", "lex --from klingon", "lex --to", "lex --format xml", "lex --verbose", "sandhi deva"] {
+            assert_eq!(parse_args(&args(line)).map(|_| ()).unwrap_err().exit_code(), 2, "{line:?}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "vyakaran")]
+    fn word_pairs() {
+        assert_eq!(word_pair("deva + indra"), Some(("deva", "indra")));
+        assert_eq!(word_pair(" tat zrutvA "), Some(("tat", "zrutvA")));
+        assert_eq!(word_pair("rAmaH"), None);
+        assert_eq!(word_pair("a b c"), None);
+    }
+}
@@ -0,0 +1,27 @@
+//! Paniniya Shiksha: Sanskrit phonetics, transliteration and prosody.
+//!
+//! - [`shiksha`]: the Varna inventory, Aksharas, Padas and Vaakyas, scripts
+//!   and IPA transcription.
+//! - [`niruktam`]: the lexer, reading Devanagari and the romanizations into
+//!   Aksharas, and the parser grouping them into Padas and Vaakyas.
+//! - [`chandas`]: syllable weight and, with the `chandas` feature, scansion.
+//! - [`vyakaran`]: Paninian grammar, with the `vyakaran` feature.
+//!
+//! Optional features, all on by default: `serialization` (JSON documents),
+//! `chandas` (scansion), `vyakaran` (grammar) and `tei` (TEI XML verse).
+//!
+//! ```
+//! use pss::{Lexer, TransliterationScheme};
+//!
+//! let tokens = Lexer::new("धर्मक्षेत्रे कुरुक्षेत्रे").tokenize();
+//! assert_eq!(pss::niruktam::render(&tokens, TransliterationScheme::Iast), "dharmakṣetre kurukṣetre");
+//! ```
+
+pub mod shiksha;
+pub mod niruktam;
+pub mod chandas;
+#[cfg(feature = "vyakaran")]
+pub mod vyakaran;
+
+pub use shiksha::{Varna, Akshara, Pada, Vaakya, TransliterationScheme};
+pub use niruktam::{Lexer, Token, parse};
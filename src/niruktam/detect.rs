@@ -0,0 +1,342 @@
+use crate::shiksha::TransliterationScheme;
+use crate::shiksha::lipi::{Glyph, GlyphMap};
+
+/// Romanized schemes considered by the detector, in order of preference on a tie.
+const ROMAN_SCHEMES: [TransliterationScheme; 5] = [
+    TransliterationScheme::HarvardKyoto,
+    TransliterationScheme::Iast,
+    TransliterationScheme::Itrans,
+    TransliterationScheme::Velthuis,
+    TransliterationScheme::Slp1,
+];
+
+/// Spellings that are characteristic of one scheme, with the weight of each occurrence.
+/// Negative weights mark spellings the scheme would not produce.
+const MARKERS: &[(TransliterationScheme, &str, f32)] = &[
+    // Harvard-Kyoto
+    (TransliterationScheme::HarvardKyoto, "z", 1.0),
+    (TransliterationScheme::HarvardKyoto, "lR", 1.0),
+    // IAST diacritics are also unlexable in every other scheme
+    (TransliterationScheme::Iast, "ā", 1.0),
+    (TransliterationScheme::Iast, "ī", 1.0),
+    (TransliterationScheme::Iast, "ū", 1.0),
+    (TransliterationScheme::Iast, "ṛ", 1.0),
+    (TransliterationScheme::Iast, "ṃ", 1.0),
+    (TransliterationScheme::Iast, "ḥ", 1.0),
+    (TransliterationScheme::Iast, "ś", 1.0),
+    (TransliterationScheme::Iast, "ṣ", 1.0),
+    (TransliterationScheme::Iast, "ṇ", 1.0),
+    // ITRANS
+    (TransliterationScheme::Itrans, "sh", 1.0),
+    (TransliterationScheme::Itrans, "Sh", 1.0),
+    (TransliterationScheme::Itrans, "RRi", 2.0),
+    (TransliterationScheme::Itrans, "R^i", 2.0),
+    (TransliterationScheme::Itrans, "~N", 2.0),
+    (TransliterationScheme::Itrans, "~n", 0.5),
+    (TransliterationScheme::Itrans, "Ch", 1.0),
+    (TransliterationScheme::Itrans, "chh", 1.0),
+    (TransliterationScheme::Itrans, "GY", 2.0),
+    (TransliterationScheme::Itrans, ".n", 1.0),
+    (TransliterationScheme::Itrans, "aa", 0.5),
+    (TransliterationScheme::Itrans, "ii", 0.5),
+    (TransliterationScheme::Itrans, "uu", 0.5),
+    // Velthuis
+    (TransliterationScheme::Velthuis, ".t", 2.0),
+    (TransliterationScheme::Velthuis, ".d", 2.0),
+    (TransliterationScheme::Velthuis, ".s", 2.0),
+    (TransliterationScheme::Velthuis, ".r", 2.0),
+    (TransliterationScheme::Velthuis, ".l", 2.0),
+    (TransliterationScheme::Velthuis, "\"s", 2.0),
+    (TransliterationScheme::Velthuis, "\"n", 2.0),
+    (TransliterationScheme::Velthuis, ".m", 1.0),
+    (TransliterationScheme::Velthuis, ".h", 1.0),
+    (TransliterationScheme::Velthuis, "~n", 0.5),
+    (TransliterationScheme::Velthuis, "aa", 0.5),
+    (TransliterationScheme::Velthuis, "ii", 0.5),
+    (TransliterationScheme::Velthuis, "uu", 0.5),
+    // SLP1 writes aspirates and long diphthongs as single capitals
+    (TransliterationScheme::Slp1, "K", 1.0),
+    (TransliterationScheme::Slp1, "C", 1.0),
+    (TransliterationScheme::Slp1, "P", 1.0),
+    (TransliterationScheme::Slp1, "B", 1.0),
+    (TransliterationScheme::Slp1, "Q", 1.0),
+    (TransliterationScheme::Slp1, "W", 1.0),
+    (TransliterationScheme::Slp1, "Y", 1.0),
+    (TransliterationScheme::Slp1, "E", 1.0),
+    (TransliterationScheme::Slp1, "O", 1.0),
+    (TransliterationScheme::Slp1, "f", 1.0),
+    (TransliterationScheme::Slp1, "F", 1.0),
+    (TransliterationScheme::Slp1, "q", 1.0),
+    (TransliterationScheme::Slp1, "kh", -1.0),
+    (TransliterationScheme::Slp1, "gh", -1.0),
+    (TransliterationScheme::Slp1, "ch", -1.0),
+    (TransliterationScheme::Slp1, "jh", -1.0),
+    (TransliterationScheme::Slp1, "th", -1.0),
+    (TransliterationScheme::Slp1, "dh", -1.0),
+    (TransliterationScheme::Slp1, "ph", -1.0),
+    (TransliterationScheme::Slp1, "bh", -1.0),
+];
+
+/// Score given to Harvard-Kyoto, the native scheme of this crate, before any evidence.
+const HK_PRIOR: f32 = 0.5;
+
+/// Score lost for every letter a scheme cannot read.
+const UNKNOWN_PENALTY: f32 = 2.0;
+
+/// Confidence below which the scheme of a romanized run is reported as doubtful.
+pub(crate) const LOW_CONFIDENCE: f32 = 0.5;
+
+/// The likely scheme of a piece of input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub scheme: TransliterationScheme,
+    pub confidence: f32, // Between 0 and 1
+}
+
+/// A stretch of input written in a single script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run<'a> {
+    pub text: &'a str,
+    pub offset: usize, // Byte offset of the run in the input
+    pub scheme: TransliterationScheme,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Devanagari,
+    Roman,
+    Neutral, // Whitespace, digits and punctuation belong to whichever run they are in
+}
+
+/// Inspects the input and returns the scheme it is most likely written in.
+///
+/// ```
+/// use pss::{niruktam, TransliterationScheme};
+///
+/// assert_eq!(niruktam::detect("kṛṣṇaḥ").scheme, TransliterationScheme::Iast);
+/// assert_eq!(niruktam::detect("कृष्णः").scheme, TransliterationScheme::Devanagari);
+/// ```
+pub fn detect(input: &str) -> Detection {
+    let devanagari = input.chars().filter(|&c| script_of(c) == Script::Devanagari).count();
+    let roman = input.chars().filter(|&c| script_of(c) == Script::Roman).count();
+    let letters = (devanagari + roman).max(1) as f32;
+
+    if devanagari > roman {
+        Detection { scheme: TransliterationScheme::Devanagari, confidence: devanagari as f32 / letters }
+    } else {
+        let detection = detect_roman(input);
+        Detection { confidence: detection.confidence * roman as f32 / letters, ..detection }
+    }
+}
+
+/// Splits the input into runs of one script, each with its own detected scheme.
+/// Romanized text also starts a new run on every line, so that lines in
+/// different schemes are told apart. Runs too short to be conclusive fall back
+/// to the scheme of all romanized text in the input.
+///
+/// ```
+/// use pss::{niruktam, TransliterationScheme};
+///
+/// let runs = niruktam::segment("rAmaH vanaM gacchati\nrāmaḥ vanaṃ gacchati");
+/// assert_eq!(runs[0].scheme, TransliterationScheme::HarvardKyoto);
+/// assert_eq!(runs[1].scheme, TransliterationScheme::Iast);
+/// ```
+pub fn segment(input: &str) -> Vec<Run<'_>> {
+    let mut bounds: Vec<(usize, Script)> = Vec::new();
+    let mut line_break = false;
+
+    for (i, c) in input.char_indices() {
+        let script = script_of(c);
+        if script == Script::Neutral {
+            line_break |= c == '\n';
+            continue;
+        }
+        match bounds.last() {
+            Some(&(_, current)) if current == script && !(line_break && script == Script::Roman) => {}
+            Some(_) => bounds.push((i, script)),
+            None => bounds.push((0, script)),
+        }
+        line_break = false;
+    }
+    if bounds.is_empty() {
+        bounds.push((0, Script::Neutral));
+    }
+
+    let texts: Vec<(usize, &str, Script)> = bounds.iter().enumerate().map(|(n, &(start, script))| {
+        let end = bounds.get(n + 1).map_or(input.len(), |&(next, _)| next);
+        (start, &input[start..end], script)
+    }).collect();
+
+    let roman_text: String = texts.iter().filter(|t| t.2 != Script::Devanagari).map(|t| t.1).collect::<Vec<_>>().join(" ");
+    let overall = detect_roman(&roman_text);
+    let roman_runs = texts.iter().filter(|t| t.2 != Script::Devanagari).count();
+
+    texts.into_iter().map(|(offset, text, script)| {
+        let detection = match script {
+            Script::Devanagari => Detection { scheme: TransliterationScheme::Devanagari, confidence: 1.0 },
+            _ if roman_runs == 1 => overall,
+            _ => {
+                let own = detect_roman(text);
+                let conclusive = own.confidence >= LOW_CONFIDENCE || own.confidence > overall.confidence;
+                if own.scheme == overall.scheme || conclusive { own } else { overall }
+            }
+        };
+        Run { text, offset, scheme: detection.scheme, confidence: detection.confidence }
+    }).collect()
+}
+
+/// Scores every romanized scheme by its characteristic spellings and by how
+/// much of the input it can read, and normalizes the scores into a confidence.
+/// Schemes that read the input into the same letters as the winner count
+/// towards its confidence rather than against it.
+fn detect_roman(input: &str) -> Detection {
+    let scores: Vec<f32> = ROMAN_SCHEMES.iter().map(|&scheme| {
+        let prior = if scheme == TransliterationScheme::HarvardKyoto { HK_PRIOR } else { 0.0 };
+        let evidence: f32 = MARKERS.iter()
+            .filter(|(s, ..)| *s == scheme)
+            .map(|(_, pattern, weight)| input.matches(pattern).count() as f32 * weight)
+            .sum();
+        let unknown = unreadable(input, scheme) + misplaced(input, scheme).len();
+        prior + evidence - UNKNOWN_PENALTY * unknown as f32
+    }).collect();
+    let readings: Vec<Vec<Option<Glyph>>> = ROMAN_SCHEMES.iter().map(|&scheme| reading(input, scheme)).collect();
+
+    let best = scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let total: f32 = scores.iter().map(|s| (s - best).exp()).sum();
+    let winner = scores.iter().position(|&s| s == best).unwrap_or(0);
+    let agreeing: f32 = scores.iter().zip(&readings)
+        .filter(|(_, reading)| **reading == readings[winner])
+        .map(|(s, _)| (s - best).exp())
+        .sum();
+
+    Detection { scheme: ROMAN_SCHEMES[winner], confidence: agreeing / total }
+}
+
+/// Reads the input with the keys of the scheme; `None` stands for a character it has no key for.
+fn reading(input: &str, scheme: TransliterationScheme) -> Vec<Option<Glyph>> {
+    let glyphs = GlyphMap::for_scheme(scheme);
+    let mut rest = input;
+    let mut read = Vec::new();
+
+    while let Some(c) = rest.chars().next() {
+        let (glyph, consumed) = match glyphs.longest_match(rest) {
+            Some((glyph, len)) => (Some(glyph), len),
+            None => (None, c.len_utf8()),
+        };
+        read.push(glyph);
+        rest = &rest[consumed..];
+    }
+
+    read
+}
+
+/// Counts the letters of the input that the scheme has no key for.
+fn unreadable(input: &str, scheme: TransliterationScheme) -> usize {
+    let glyphs = GlyphMap::for_scheme(scheme);
+    let mut rest = input;
+    let mut count = 0;
+
+    while let Some(c) = rest.chars().next() {
+        let consumed = match glyphs.longest_match(rest) {
+            Some((_, len)) => len,
+            None => {
+                if script_of(c) == Script::Roman {
+                    count += 1;
+                }
+                c.len_utf8()
+            }
+        };
+        rest = &rest[consumed..];
+    }
+
+    count
+}
+
+/// Finds the letters the scheme can read but would not write where they stand,
+/// with their byte offsets. IAST capitalizes only the first letter of a word,
+/// so an `A`, `H` or `M` after a small letter belongs to another scheme.
+pub(crate) fn misplaced(input: &str, scheme: TransliterationScheme) -> Vec<(usize, char)> {
+    if scheme != TransliterationScheme::Iast {
+        return Vec::new();
+    }
+    let mut previous = ' ';
+    input.char_indices().filter(|&(_, c)| {
+        let after_small = previous.is_lowercase();
+        previous = c;
+        c.is_uppercase() && after_small
+    }).collect()
+}
+
+fn script_of(c: char) -> Script {
+    match c {
+        '\u{0964}'..='\u{096F}' => Script::Neutral, // Dandas and Devanagari digits
+        '\u{0900}'..='\u{097F}' | '\u{A8E0}'..='\u{A8FF}' | '\u{1CD0}'..='\u{1CFF}' => Script::Devanagari,
+        '\u{0300}'..='\u{036F}' => Script::Roman, // Combining diacritics of decomposed IAST
+        _ if c.is_alphabetic() => Script::Roman,
+        _ => Script::Neutral,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{render, Lexer};
+
+    #[test]
+    fn schemes() {
+        let samples = [
+            ("kRSNaH zaraNaM", TransliterationScheme::HarvardKyoto),
+            ("kṛṣṇaḥ śaraṇaṃ", TransliterationScheme::Iast),
+            ("kfzRaH SaraRaM Bavati", TransliterationScheme::Slp1),
+            ("kRRiShNaH sharaNaM", TransliterationScheme::Itrans),
+            ("k.r.s.na.h \"sara.na.m", TransliterationScheme::Velthuis),
+            ("कृष्णः शरणं", TransliterationScheme::Devanagari),
+        ];
+        for (text, scheme) in samples {
+            let detection = detect(text);
+            assert_eq!(detection.scheme, scheme, "{text}");
+            assert!(detection.confidence > 0.5, "{text}: {}", detection.confidence);
+        }
+    }
+
+    #[test]
+    fn doubled_vowels() {
+        assert_eq!(detect("raama").scheme, TransliterationScheme::HarvardKyoto);
+        assert_eq!(render(&Lexer::new("raama").tokenize(), TransliterationScheme::Devanagari), "राम");
+    }
+
+    #[test]
+    fn mixed_scripts() {
+        let runs = segment("धर्मक्षेत्रे kurukSetre samavetA");
+        let found: Vec<(&str, usize, TransliterationScheme)> = runs.iter().map(|r| (r.text, r.offset, r.scheme)).collect();
+        assert_eq!(found, [
+            ("धर्मक्षेत्रे ", 0, TransliterationScheme::Devanagari),
+            ("kurukSetre samavetA", 37, TransliterationScheme::HarvardKyoto),
+        ]);
+        assert_eq!(render(&Lexer::new("धर्म dharma").tokenize(), TransliterationScheme::Iast), "dharma dharma");
+    }
+
+    #[test]
+    fn mixed_schemes() {
+        let runs = segment("rAmaH vanaM gacchati\nrāmaḥ vanaṃ gacchati");
+        let found: Vec<(&str, usize, TransliterationScheme)> = runs.iter().map(|r| (r.text, r.offset, r.scheme)).collect();
+        assert_eq!(found, [
+            ("rAmaH vanaM gacchati\n", 0, TransliterationScheme::HarvardKyoto),
+            ("rāmaḥ vanaṃ gacchati", 21, TransliterationScheme::Iast),
+        ]);
+        assert!(runs.iter().all(|r| r.confidence >= LOW_CONFIDENCE), "{runs:?}");
+
+        let tokens = Lexer::new("rAmaH vanaM gacchati\nrāmaḥ vanaṃ gacchati").tokenize();
+        assert_eq!(render(&tokens, TransliterationScheme::Iast), "rāmaḥ vanaṃ gacchati\nrāmaḥ vanaṃ gacchati");
+    }
+
+    #[test]
+    fn agreeing_schemes() {
+        // Every scheme but SLP1 reads `dharma` alike, so the choice among them does not matter
+        assert!(detect("dharma").confidence >= LOW_CONFIDENCE);
+        assert!(detect("Rta").confidence < LOW_CONFIDENCE);
+        assert_eq!(misplaced("Rāmaḥ vanAm", TransliterationScheme::Iast), [(12, 'A')]);
+        assert!(misplaced("RĀMA", TransliterationScheme::Iast).is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+use crate::niruktam::span::Span;
+
+/// What the lexer found wrong with a stretch of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LexErrorKind {
+    UnknownCharacter,   // A letter or mark that is not in the input scheme
+    InconsistentAccent, // One Akshara is marked with different Swaras
+    OrphanVowelSign,    // A vowel sign, virama or accent mark with nothing to attach to
+    IllegalCluster,     // Anusvara or visarga without a vowel, or too many consonants in a row
+    UncertainScheme,    // Romanized text that could be in more than one scheme
+    MisplacedLetter,    // A letter the detected scheme would not write there (`A` inside an IAST word)
+}
+
+impl fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            LexErrorKind::UnknownCharacter => "unknown character",
+            LexErrorKind::InconsistentAccent => "inconsistent accent",
+            LexErrorKind::OrphanVowelSign => "sign without a letter to attach to",
+            LexErrorKind::IllegalCluster => "illegal cluster",
+            LexErrorKind::UncertainScheme => "scheme uncertain",
+            LexErrorKind::MisplacedLetter => "letter out of place in the detected scheme",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// A problem the lexer recovered from. The input around it is still tokenized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub span: Span,         // Where in the input the problem is
+    pub input: String,      // The offending input
+    pub kind: LexErrorKind, // What is wrong with it
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {} {:?}", self.span.line, self.span.column, self.kind, self.input)
+    }
+}
+
+impl std::error::Error for LexError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::Lexer;
+    use crate::shiksha::TransliterationScheme;
+
+    fn diagnostics(text: &str, scheme: TransliterationScheme) -> Vec<(LexErrorKind, String)> {
+        Lexer::with_scheme(text, scheme).tokenize_with_diagnostics().diagnostics.into_iter().map(|d| (d.kind, d.input)).collect()
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(diagnostics("rAmaq ca, 1", TransliterationScheme::HarvardKyoto), [(LexErrorKind::UnknownCharacter, "q".to_string())]);
+        assert_eq!(diagnostics("अ॒॑", TransliterationScheme::Devanagari), [(LexErrorKind::InconsistentAccent, "\u{0951}".to_string())]);
+        assert_eq!(diagnostics("ा ॒", TransliterationScheme::Devanagari), [
+            (LexErrorKind::OrphanVowelSign, "\u{093E}".to_string()),
+            (LexErrorKind::OrphanVowelSign, "\u{0952}".to_string()),
+        ]);
+        assert_eq!(diagnostics("Ma kkkkkkA", TransliterationScheme::HarvardKyoto), [
+            (LexErrorKind::IllegalCluster, "M".to_string()),
+            (LexErrorKind::IllegalCluster, "k".to_string()),
+        ]);
+        assert!(diagnostics("रामः ॥ १ ॥", TransliterationScheme::Devanagari).is_empty());
+    }
+
+    #[test]
+    fn detected_schemes() {
+        let detected = |text| -> Vec<(LexErrorKind, String)> {
+            Lexer::new(text).tokenize_with_diagnostics().diagnostics.into_iter().map(|d| (d.kind, d.input)).collect()
+        };
+        assert_eq!(detected(" Rta "), [(LexErrorKind::UncertainScheme, "Rta".to_string())]);
+        assert_eq!(detected("rāmaḥ vanAm"), [(LexErrorKind::MisplacedLetter, "A".to_string())]);
+        assert!(detected("rAmaH vanaM gacchati\nrāmaḥ vanaṃ gacchati").is_empty());
+        assert!(detected("धर्मक्षेत्रे kurukSetre samavetA").is_empty());
+    }
+
+    #[test]
+    fn recovery_and_display() {
+        let lexed = Lexer::with_scheme("ka\nxqa", TransliterationScheme::HarvardKyoto).tokenize_with_diagnostics();
+        let shown: Vec<String> = lexed.diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(shown, ["2:1: unknown character \"x\"", "2:2: unknown character \"q\""]);
+        assert_eq!(crate::niruktam::render(&lexed.tokens, TransliterationScheme::Iast), "ka\nxqa");
+    }
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+use crate::shiksha::{Varna, VarnaMap, TransliterationScheme};
+use crate::shiksha::lipi::{self, Glyph, GlyphMap};
+use crate::niruktam::niruktam::{Lexed, Scanner, Token, render};
+use crate::niruktam::diagnostic::LexError;
+use crate::niruktam::span::Span;
+
+/// Informal spellings and the Varnas (by Harvard-Kyoto name) they stand for.
+/// Keys are matched exactly first and then in lowercase, so that only the
+/// capitals with an established meaning (A, T, Sh, H, M …) are significant.
+const SPELLINGS: &[(&str, &str)] = &[
+    // Swaras (Vowels)
+    ("a", "a"), ("aa", "A"), ("A", "A"),
+    ("i", "i"), ("ii", "I"), ("ee", "I"), ("I", "I"),
+    ("u", "u"), ("uu", "U"), ("oo", "U"), ("U", "U"),
+    ("R", "R"), ("Ri", "R"), ("RR", "RR"),
+    ("e", "e"), ("ai", "ai"), ("o", "o"), ("au", "au"), ("ou", "au"),
+
+    // Vyanjanas (Consonants)
+    ("k", "k"), ("kh", "kh"), ("g", "g"), ("gh", "gh"), ("q", "k"),
+    ("c", "c"), ("ch", "c"), ("chh", "ch"), ("Ch", "ch"), ("j", "j"), ("jh", "jh"), ("z", "j"),
+    ("T", "T"), ("Th", "Th"), ("D", "D"), ("Dh", "Dh"), ("N", "N"),
+    ("t", "t"), ("th", "th"), ("d", "d"), ("dh", "dh"), ("n", "n"),
+    ("p", "p"), ("ph", "ph"), ("f", "ph"), ("b", "b"), ("bh", "bh"), ("m", "m"),
+    ("y", "y"), ("r", "r"), ("l", "l"), ("v", "v"), ("w", "v"),
+    ("sh", "z"), ("Sh", "S"), ("S", "S"), ("shh", "S"), ("s", "s"), ("h", "h"),
+    ("ksh", "k S"), ("x", "k S"), ("jn", "j J"), ("jny", "j J"), ("dny", "j J"),
+
+    // Anusvara and Visarga
+    ("M", "M"), ("H", "H"),
+];
+
+const LONGEST_SPELLING: usize = 3;
+
+/// A reading the lenient lexer had to guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub offset: usize,                 // Byte offset of the spelling in the input
+    pub spelling: String,              // The spelling as written
+    pub chosen: Vec<Varna>,            // The Varnas it was read as
+    pub alternatives: Vec<Vec<Varna>>, // Other plausible readings
+}
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternatives: Vec<String> = self.alternatives.iter().map(|a| iast(a)).collect();
+        write!(f, "'{}' at {} read as {}, could be {}", self.spelling, self.offset, iast(&self.chosen), alternatives.join(" or "))
+    }
+}
+
+/// Tokens read from casual romanization, with the guesses made along the way.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LenientLexing {
+    pub tokens: Vec<Token>,
+    pub choices: Vec<Choice>,
+    pub diagnostics: Vec<LexError>,
+}
+
+impl LenientLexing {
+    /// Suggests the canonical spelling of the input in the given scheme.
+    pub fn suggest(&self, scheme: TransliterationScheme) -> String {
+        render(&self.tokens, scheme)
+    }
+}
+
+/// One spelling of the input and what it was read as.
+struct Unit<'a> {
+    offset: usize,
+    text: &'a str,
+    varnas: Vec<Varna>, // Empty for characters that are not letters
+}
+
+impl Unit<'_> {
+    fn is_vowel(&self) -> bool {
+        self.varnas.first().is_some_and(|v| v.is_vowel())
+    }
+
+    fn is_consonant(&self) -> bool {
+        self.varnas.first().is_some_and(|v| v.is_consonant())
+    }
+
+    fn starts_with(&self, names: &[&str]) -> bool {
+        self.varnas.first().is_some_and(|v| names.contains(&v.hk))
+    }
+}
+
+/// Reads casual romanization, found at byte `offset` of the input, into tokens.
+pub fn read(input: &str, offset: usize) -> LenientLexing {
+    let mut units = spell_out(input, offset);
+    let choices = disambiguate(input, offset, &mut units);
+
+    let mut lexed = Lexed::default();
+    let mut scanner = Scanner::new(TransliterationScheme::HarvardKyoto, input, offset, &mut lexed);
+    for unit in &units {
+        if unit.varnas.is_empty() {
+            for (i, c) in unit.text.char_indices() {
+                let start = unit.offset + i;
+                scanner.unknown(c, Span::new(start, start + c.len_utf8()));
+            }
+        }
+        for &varna in &unit.varnas {
+            scanner.read(Glyph::Varna(varna), Span::new(unit.offset, unit.offset + unit.text.len()));
+        }
+    }
+    scanner.finish();
+
+    LenientLexing { tokens: lexed.tokens, choices, diagnostics: lexed.diagnostics }
+}
+
+/// Splits the input into the longest known spellings.
+/// IAST letters are read as such, since casual text often mixes them in.
+fn spell_out(input: &str, base: usize) -> Vec<Unit<'_>> {
+    let iast = GlyphMap::for_scheme(TransliterationScheme::Iast);
+    let mut units = Vec::new();
+    let mut position = 0;
+
+    while let Some(c) = input[position..].chars().next() {
+        let rest = &input[position..];
+        let ends: Vec<usize> = rest.char_indices().take(LONGEST_SPELLING).map(|(i, c)| i + c.len_utf8()).collect();
+        let found = ends.iter().rev().find_map(|&end| lookup(&rest[..end]).map(|varnas| (end, varnas)))
+            .or_else(|| match iast.longest_match(rest)? {
+                (Glyph::Varna(v), len) => Some((len, vec![v])),
+                (Glyph::Conjunct(first, second), len) => Some((len, vec![first, second])),
+                _ => None,
+            });
+
+        let (len, varnas) = found.unwrap_or((c.len_utf8(), Vec::new()));
+        units.push(Unit { offset: base + position, text: &rest[..len], varnas });
+        position += len;
+    }
+
+    units
+}
+
+fn lookup(spelling: &str) -> Option<Vec<Varna>> {
+    let exact = SPELLINGS.iter().find(|(key, _)| *key == spelling);
+    let lower = spelling.to_lowercase();
+    let found = exact.or_else(|| SPELLINGS.iter().find(|(key, _)| *key == lower))?;
+    Some(found.1.split(' ').map(varna).collect())
+}
+
+/// Applies the contextual readings of casual spelling and records each guess.
+fn disambiguate<'a>(input: &'a str, base: usize, units: &mut Vec<Unit<'a>>) -> Vec<Choice> {
+    let mut choices = Vec::new();
+    let mut i = 0;
+
+    while i < units.len() {
+        let previous_consonant = i == 0 || !units[i - 1].is_vowel();
+        let next = units.get(i + 1);
+        let next_consonant = next.is_some_and(|u| u.is_consonant());
+        let word_end = next.is_none_or(|u| u.varnas.is_empty());
+        let unit = &units[i];
+
+        // The reading chosen, the alternatives, and whether the next unit is absorbed
+        // Letters written with IAST diacritics are never ambiguous
+        let names: Vec<&str> = if unit.text.is_ascii() { unit.varnas.iter().map(|v| v.hk).collect() } else { Vec::new() };
+        let reading: Option<(&str, &[&str], bool)> = match names.as_slice() {
+            // A bare r between consonants is the vowel ṛ (`amrtasya`)
+            ["r"] if previous_consonant && next_consonant => Some(("R", &["r"], false)),
+            // ri after a consonant and before another is usually ṛ (`krishna`)
+            ["r"] if previous_consonant && next.is_some_and(|u| u.text == "i")
+                && units.get(i + 2).is_some_and(|u| u.is_consonant()) => Some(("R", &["r i"], true)),
+            // sh is ś, except after ṛ or before a retroflex where it is ṣ
+            ["z"] if i > 0 && units[i - 1].starts_with(&["R", "RR"]) => Some(("S", &["z"], false)),
+            ["z"] if next.is_some_and(|u| u.starts_with(&["T", "Th", "D", "Dh", "N"])) => Some(("S", &["z"], false)),
+            ["z"] => Some(("z", &["S"], false)),
+            ["S"] if unit.text != "S" => Some(("S", &["z"], false)),
+            // A nasal takes the place of the stop that follows it
+            ["n"] if next.is_some_and(|u| u.starts_with(&["k", "kh", "g", "gh"])) => Some(("G", &["n", "M"], false)),
+            ["n"] if next.is_some_and(|u| u.starts_with(&["c", "ch", "j", "jh"])) => Some(("J", &["n", "M"], false)),
+            ["n"] if next.is_some_and(|u| u.starts_with(&["T", "Th", "D", "Dh"])) => Some(("N", &["n", "M"], false)),
+            // n directly after ṣ is retroflex (`krishna`)
+            ["n"] if i > 0 && units[i - 1].starts_with(&["S"]) => Some(("N", &["n"], false)),
+            // A word-final h after a vowel is the visarga (`namah`)
+            ["h"] if !previous_consonant && word_end => Some(("H", &["h"], false)),
+            _ => None,
+        };
+
+        if let Some((chosen, alternatives, absorbs_next)) = reading {
+            if absorbs_next {
+                let absorbed = units.remove(i + 1);
+                let start = units[i].offset - base;
+                units[i].text = &input[start..absorbed.offset - base + absorbed.text.len()];
+            }
+            let unit = &mut units[i];
+            unit.varnas = chosen.split(' ').map(varna).collect();
+            choices.push(Choice {
+                offset: unit.offset,
+                spelling: unit.text.to_string(),
+                chosen: unit.varnas.clone(),
+                alternatives: alternatives.iter().map(|a| a.split(' ').map(varna).collect()).collect(),
+            });
+        }
+        i += 1;
+    }
+
+    choices
+}
+
+fn varna(hk: &str) -> Varna {
+    VarnaMap::get(hk).unwrap_or_else(|| panic!("no Varna spelled {hk:?} in the inventory"))
+}
+
+fn iast(varnas: &[Varna]) -> String {
+    lipi::render(varnas, TransliterationScheme::Iast)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lenient(text: &str) -> LenientLexing {
+        read(text, 0)
+    }
+
+    fn choices(lexing: &LenientLexing) -> Vec<(String, String)> {
+        lexing.choices.iter().map(|c| (c.spelling.clone(), iast(&c.chosen))).collect()
+    }
+
+    #[test]
+    fn vowel_r() {
+        let lexing = lenient("amrtasya krishna");
+        assert_eq!(lexing.suggest(TransliterationScheme::Iast), "amṛtasya kṛṣṇa");
+        assert_eq!(choices(&lexing), [
+            ("r".to_string(), "ṛ".to_string()),
+            ("ri".to_string(), "ṛ".to_string()),
+            ("sh".to_string(), "ṣ".to_string()),
+            ("n".to_string(), "ṇ".to_string()),
+        ]);
+        assert_eq!(lexing.choices[1].alternatives, [vec![varna("r"), varna("i")]]);
+    }
+
+    #[test]
+    fn sibilants_and_nasals() {
+        assert_eq!(lenient("shiva").suggest(TransliterationScheme::Iast), "śiva");
+        assert_eq!(lenient("vishva").suggest(TransliterationScheme::Iast), "viśva");
+        assert_eq!(lenient("ganga").suggest(TransliterationScheme::Iast), "gaṅga");
+        assert_eq!(lenient("sanjaya").suggest(TransliterationScheme::Iast), "sañjaya");
+        assert_eq!(lenient("kantha").suggest(TransliterationScheme::Iast), "kantha");
+        assert_eq!(lenient("kaNTha").suggest(TransliterationScheme::Iast), "kaṇṭha");
+    }
+
+    #[test]
+    fn spellings() {
+        assert_eq!(lenient("seetaa raamah").suggest(TransliterationScheme::Iast), "sītā rāmaḥ");
+        assert_eq!(lenient("gauri poorNa").suggest(TransliterationScheme::Iast), "gauri pūrṇa");
+        assert_eq!(lenient("kṛshna").suggest(TransliterationScheme::Iast), "kṛṣṇa");
+        assert_eq!(lenient("jnaana").suggest(TransliterationScheme::Iast), "jñāna");
+        let lexing = lenient("namah");
+        assert_eq!(choices(&lexing), [("h".to_string(), "ḥ".to_string())]);
+        assert_eq!(lexing.choices[0].to_string(), "'h' at 4 read as ḥ, could be h");
+    }
+}
@@ -1,4 +1,16 @@
-pub mod niruktam;
+#[allow(clippy::module_inception)]
+mod niruktam;
+mod detect;
+mod loose;
+mod span;
+mod stream;
+mod parser;
+mod diagnostic;
 
-
-pub use niruktam::Lexer;
+pub use niruktam::{Lexer, Lexed, Token, render};
+pub use detect::{detect, segment, Detection, Run};
+pub use loose::{Choice, LenientLexing};
+pub use span::Span;
+pub use stream::StreamLexer;
+pub use parser::parse;
+pub use diagnostic::{LexError, LexErrorKind};
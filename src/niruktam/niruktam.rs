@@ -1,83 +1,440 @@
-use crate::shiksha::{Varna, VarnaMap, Akshara};
+use crate::shiksha::{Varna, VarnaMap, Akshara, Swara, TransliterationScheme};
+use crate::shiksha::lipi::{self, Glyph, GlyphMap};
+use crate::niruktam::detect;
+use crate::niruktam::loose::{self, LenientLexing};
+use crate::niruktam::span::{Cursor, Span};
+use crate::niruktam::diagnostic::{LexError, LexErrorKind};
 
 /// Represents a tokenized Sanskrit phoneme.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Token {
-    Akshara(Akshara), // Recognized Sanskrit syllable (one or more Varnas)
-    Unknown(char),    // Unrecognized character
+    Akshara(Akshara, Span), // Recognized Sanskrit syllable (one or more Varnas)
+    Unknown(char, Span),    // Unrecognized character
+}
+
+impl Token {
+    /// Returns where in the input the token was read from.
+    pub fn span(&self) -> Span {
+        match self {
+            Token::Akshara(_, span) | Token::Unknown(_, span) => *span,
+        }
+    }
+
+    pub(crate) fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Token::Akshara(_, span) | Token::Unknown(_, span) => span,
+        }
+    }
+}
+
+/// How the lexer reads its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    Auto,                           // Detect the scheme of each script run
+    Scheme(TransliterationScheme),  // Read everything in one known scheme
+    Lenient,                        // Read casual romanization
 }
 
 pub struct Lexer<'a> {
     input: &'a str,
+    mode: Mode,
 }
 
 impl<'a> Lexer<'a> {
-    /// Creates a new lexer instance.
+    /// Creates a new lexer instance that detects the input scheme automatically.
     pub fn new(input: &'a str) -> Self {
-        Lexer { input }
+        Lexer { input, mode: Mode::Auto }
+    }
+
+    /// Creates a new lexer instance for input in a known scheme.
+    pub fn with_scheme(input: &'a str, scheme: TransliterationScheme) -> Self {
+        Lexer { input, mode: Mode::Scheme(scheme) }
+    }
+
+    /// Creates a new lexer instance for informal romanization
+    /// (`shrnvantu`, `ee` for ī, `sh` for both ś and ṣ).
+    pub fn lenient(input: &'a str) -> Self {
+        Lexer { input, mode: Mode::Lenient }
     }
 
     /// Tokenizes the input string into Sanskrit phonetic syllables (Aksharas).
+    /// In auto mode, mixed-script input is split into runs and each run is
+    /// lexed in its own detected scheme.
     pub fn tokenize(&self) -> Vec<Token> {
-        let varna_map = VarnaMap::get_map(); // Get Harvard-Kyoto mappings
-        let mut tokens = Vec::new();
-        let mut current_akshara = Vec::new(); // Collects Varnas for an Akshara
-        let chars: Vec<char> = self.input.chars().collect();
-        let mut i = 0;
-
-        while i < chars.len() {
-            let mut found = false;
-
-            // Try matching 3-character sequences (e.g., 'kSa', 'jJa')
-            if i + 2 < chars.len() {
-                let slice = chars[i..=i+2].iter().collect::<String>();
-                if let Some(&varna) = varna_map.get(slice.as_str()) {
-                    current_akshara.push(varna);
-                    i += 3;
-                    found = true;
+        self.tokenize_with_diagnostics().tokens
+    }
+
+    /// Tokenizes the input like `tokenize`, also returning every problem
+    /// the lexer recovered from along the way.
+    pub fn tokenize_with_diagnostics(&self) -> Lexed {
+        let mut lexed = Lexed::default();
+        lex(self.input, 0, self.mode, &mut lexed);
+        Cursor::default().locate(self.input, 0, &mut lexed.tokens, &mut lexed.diagnostics);
+        lexed
+    }
+
+    /// Returns the slice of the input that the `index`-th Akshara of `tokens` was read from.
+    pub fn akshara_source(&self, tokens: &[Token], index: usize) -> Option<&'a str> {
+        tokens.iter()
+            .filter(|t| matches!(t, Token::Akshara(..)))
+            .nth(index)
+            .map(|t| t.span().slice(self.input))
+    }
+
+    /// Tokenizes informal romanization, reporting the ambiguous spellings
+    /// and the reading chosen for each. A lexer made with `new` or
+    /// `with_scheme` reads its input exactly, so it reports no choices.
+    ///
+    /// ```
+    /// use pss::{Lexer, TransliterationScheme};
+    ///
+    /// let lexing = Lexer::lenient("krishna").tokenize_lenient();
+    /// assert_eq!(lexing.suggest(TransliterationScheme::Iast), "kṛṣṇa");
+    ///
+    /// let exact = Lexer::with_scheme("kRSNa", TransliterationScheme::HarvardKyoto).tokenize_lenient();
+    /// assert_eq!(exact.suggest(TransliterationScheme::Iast), "kṛṣṇa");
+    /// assert!(exact.choices.is_empty());
+    /// ```
+    pub fn tokenize_lenient(&self) -> LenientLexing {
+        if self.mode != Mode::Lenient {
+            let Lexed { tokens, diagnostics } = self.tokenize_with_diagnostics();
+            return LenientLexing { tokens, choices: Vec::new(), diagnostics };
+        }
+        let mut lexing = loose::read(self.input, 0);
+        Cursor::default().locate(self.input, 0, &mut lexing.tokens, &mut lexing.diagnostics);
+        lexing
+    }
+}
+
+/// Tokens together with the problems the lexer recovered from while reading them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Lexed {
+    pub tokens: Vec<Token>,
+    pub diagnostics: Vec<LexError>,
+}
+
+/// Lexes `text`, found at byte `offset` of the input, appending its tokens and diagnostics.
+/// Only byte offsets are set on the spans; `Cursor::locate` fills in the rest.
+pub(crate) fn lex(text: &str, offset: usize, mode: Mode, lexed: &mut Lexed) {
+    match mode {
+        Mode::Scheme(scheme) => Scanner::new(scheme, text, offset, lexed).scan(scheme),
+        Mode::Auto => {
+            for run in detect::segment(text) {
+                let base = offset + run.offset;
+                if run.scheme != TransliterationScheme::Devanagari {
+                    doubt(&run, base, lexed);
                 }
+                Scanner::new(run.scheme, run.text, base, lexed).scan(run.scheme);
             }
+        }
+        Mode::Lenient => {
+            let lexing = loose::read(text, offset);
+            lexed.tokens.extend(lexing.tokens);
+            lexed.diagnostics.extend(lexing.diagnostics);
+        }
+    }
+}
+
+/// Reports a romanized run whose scheme was guessed with low confidence, and
+/// the letters in it that its scheme would not write.
+fn doubt(run: &detect::Run, base: usize, lexed: &mut Lexed) {
+    let letters = run.text.trim();
+    let start = base + run.text.len() - run.text.trim_start().len();
+    if run.confidence < detect::LOW_CONFIDENCE && !letters.is_empty() {
+        let span = Span::new(start, start + letters.len());
+        lexed.diagnostics.push(LexError { span, input: letters.to_string(), kind: LexErrorKind::UncertainScheme });
+    }
+    for (i, c) in detect::misplaced(run.text, run.scheme) {
+        let span = Span::new(base + i, base + i + c.len_utf8());
+        lexed.diagnostics.push(LexError { span, input: c.to_string(), kind: LexErrorKind::MisplacedLetter });
+    }
+}
 
-            // Try matching 2-character sequences (e.g., 'kh', 'gh', 'Th')
-            if !found && i + 1 < chars.len() {
-                let slice = chars[i..=i+1].iter().collect::<String>();
-                if let Some(&varna) = varna_map.get(slice.as_str()) {
-                    current_akshara.push(varna);
-                    i += 2;
-                    found = true;
+/// Groups the Varnas read from one run of input into Aksharas.
+pub(crate) struct Scanner<'t> {
+    abugida: bool, // Consonants carry an inherent `a` (Devanagari)
+    text: &'t str, // The run being read
+    base: usize,   // Byte offset of the run in the input
+    lexed: &'t mut Lexed,
+    current_akshara: Vec<Varna>, // Collects Varnas for an Akshara
+    current_span: Span,          // Bytes the current Akshara was read from
+    inherent_a: bool,            // A Devanagari consonant is still waiting for its vowel
+    previous: Option<Varna>,     // The last Varna read, if no unknown character came since
+    consonants: usize,           // Consonants read since the last vowel
+    first_token: usize,          // Index of the first token of the run in `lexed`
+    accented: bool,              // The run has Vedic accent marks
+}
+
+/// Numerals that prolong the vowel written before them (`o3m`, `ओ३म्`).
+const PLUTA_MARKS: &[char] = &['3', '३'];
+
+/// More consonants in a row than this cannot be pronounced (`kārtsnyam` has five).
+const LONGEST_CLUSTER: usize = 5;
+
+impl<'t> Scanner<'t> {
+    /// Creates a scanner for `text`, found at byte `base` of the input, writing into `lexed`.
+    pub(crate) fn new(scheme: TransliterationScheme, text: &'t str, base: usize, lexed: &'t mut Lexed) -> Self {
+        let abugida = matches!(scheme, TransliterationScheme::Devanagari | TransliterationScheme::Unicode);
+        let first_token = lexed.tokens.len();
+        Scanner {
+            abugida,
+            text,
+            base,
+            lexed,
+            current_akshara: Vec::new(),
+            current_span: Span::default(),
+            inherent_a: false,
+            previous: None,
+            consonants: 0,
+            first_token,
+            accented: false,
+        }
+    }
+
+    /// Reads the whole run with the input table of `scheme`.
+    fn scan(mut self, scheme: TransliterationScheme) {
+        let glyphs = GlyphMap::for_scheme(scheme);
+        let text = self.text;
+        let mut position = 0;
+
+        while let Some(c) = text[position..].chars().next() {
+            let start = self.base + position;
+            let consumed = match glyphs.longest_match(&text[position..]) {
+                Some((glyph, len)) => {
+                    self.read(glyph, Span::new(start, start + len));
+                    len
+                }
+                None => {
+                    self.unknown(c, Span::new(start, start + c.len_utf8()));
+                    c.len_utf8()
+                }
+            };
+            position += consumed;
+        }
+
+        self.finish();
+    }
+
+    /// Reads one glyph found at `span` of the input.
+    pub(crate) fn read(&mut self, glyph: Glyph, span: Span) {
+        if matches!(glyph, Glyph::Varna(_) | Glyph::Conjunct(..) | Glyph::Accent(_)) {
+            self.settle_inherent_a();
+        }
+        if !self.current_akshara.is_empty() || !matches!(glyph, Glyph::Virama | Glyph::Ignored | Glyph::Accent(_)) {
+            self.extend_span(span);
+        }
+
+        match glyph {
+            Glyph::Varna(varna) => {
+                if varna.is_ayogavaha() && !self.previous.is_some_and(|v| v.is_vowel()) {
+                    self.report(LexErrorKind::IllegalCluster, span);
                 }
+                self.push(varna, span);
+                // Only Devanagari consonants carry an inherent vowel; romanized ones are bare
+                self.inherent_a = varna.is_consonant() && self.abugida;
+            }
+            Glyph::Conjunct(first, second) => {
+                self.push(first, span);
+                self.push(second, span);
             }
+            Glyph::VowelSign(vowel) => {
+                // The sign is still read as its vowel, so the text around it scans normally
+                if !self.inherent_a {
+                    self.report(LexErrorKind::OrphanVowelSign, span);
+                }
+                self.inherent_a = false;
+                self.push(vowel, span);
+            }
+            Glyph::Virama => {
+                if !self.inherent_a {
+                    self.report(LexErrorKind::OrphanVowelSign, span);
+                }
+                self.inherent_a = false;
+            }
+            Glyph::Accent(swara) => self.accent(swara, span),
+            Glyph::Ignored => {}
+        }
+    }
 
-            // Try matching single character (fallback)
-            if !found {
-                let slice = chars[i].to_string();
-                if let Some(&varna) = varna_map.get(slice.as_str()) {
-                    current_akshara.push(varna);
-                } else {
-                    // If we encounter an unknown character, push the last Akshara and reset
-                    if !current_akshara.is_empty() {
-                        tokens.push(Token::Akshara(Akshara::new(current_akshara.clone()).unwrap()));
-                        current_akshara.clear();
+    /// If we encounter an unknown character, push the last Akshara and reset
+    pub(crate) fn unknown(&mut self, c: char, span: Span) {
+        self.settle_inherent_a();
+        self.flush();
+        if PLUTA_MARKS.contains(&c) && self.prolong(span) {
+            return;
+        }
+        // Spaces and punctuation are expected between words; only letters and marks are errors
+        if (c.is_alphabetic() && c != 'ऽ') || is_mark(c) {
+            self.report(LexErrorKind::UnknownCharacter, span);
+        }
+        self.previous = None;
+        self.consonants = 0;
+        self.lexed.tokens.push(Token::Unknown(c, span)); // Handle unknown characters
+    }
+
+    /// Push any remaining Akshara at the end. In a run with accent marks the
+    /// unmarked vowels are udaatta (the pracaya after a svarita is written alike).
+    pub(crate) fn finish(mut self) {
+        self.settle_inherent_a();
+        self.flush();
+        if self.accented {
+            for token in &mut self.lexed.tokens[self.first_token..] {
+                if let Token::Akshara(akshara, _) = token {
+                    if akshara.swara.is_none() && akshara.varnas.iter().any(|v| v.is_vowel()) {
+                        akshara.swara = Some(Swara::Udaatta);
                     }
-                    tokens.push(Token::Unknown(chars[i])); // Handle unknown characters
                 }
-                i += 1;
             }
+        }
+    }
+
+    /// Gives the Akshara just read the Swara of an accent mark written after it.
+    fn accent(&mut self, swara: Swara, span: Span) {
+        self.flush();
+        let marked = match self.lexed.tokens[self.first_token..].last_mut() {
+            Some(Token::Akshara(akshara, akshara_span)) if akshara.varnas.iter().any(|v| v.is_vowel()) => {
+                let consistent = akshara.swara.is_none_or(|s| s == swara);
+                akshara.swara = Some(swara);
+                akshara_span.end = span.end;
+                Some(consistent)
+            }
+            _ => None,
+        };
+        match marked {
+            Some(consistent) => {
+                self.accented = true;
+                if !consistent {
+                    self.report(LexErrorKind::InconsistentAccent, span);
+                }
+            }
+            None => self.report(LexErrorKind::OrphanVowelSign, span),
+        }
+    }
+
+    /// Supplies the inherent `a` of a Devanagari consonant not followed by a sign or virama.
+    fn settle_inherent_a(&mut self) {
+        if self.inherent_a {
+            self.inherent_a = false;
+            let span = self.current_span;
+            self.push(VarnaMap::get("a").expect("inventory contains the vowel a"), span);
+        }
+    }
+
+    fn extend_span(&mut self, span: Span) {
+        if self.current_akshara.is_empty() {
+            self.current_span = span;
+        } else {
+            self.current_span.end = span.end;
+        }
+    }
+
+    fn push(&mut self, varna: Varna, span: Span) {
+        // An ayogavaha closes the syllable of the vowel before it
+        if varna.is_ayogavaha() && self.current_akshara.is_empty() && self.previous.is_some_and(|v| v.is_vowel())
+            && self.amend_last(span, |varnas| varnas.push(varna)) {
+            self.previous = Some(varna);
+            return;
+        }
+
+        self.current_akshara.push(varna);
+        self.previous = Some(varna);
 
+        if varna.is_consonant() {
+            self.consonants += 1;
+            if self.consonants == LONGEST_CLUSTER + 1 {
+                self.report(LexErrorKind::IllegalCluster, span);
+            }
+        } else if varna.is_vowel() {
             // If the current Varna is a vowel, finalize the Akshara
-            if let Some(last_varna) = current_akshara.last() {
-                if last_varna.swara.is_some() {
-                    tokens.push(Token::Akshara(Akshara::new(current_akshara.clone()).unwrap()));
-                    current_akshara.clear();
+            self.consonants = 0;
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.current_akshara.is_empty() {
+            return;
+        }
+
+        let varnas = std::mem::take(&mut self.current_akshara);
+        match Akshara::new(varnas.clone()) {
+            Ok(akshara) => self.lexed.tokens.push(Token::Akshara(akshara, self.current_span)),
+            Err(_) => {
+                // Keep the Varnas, one Akshara each, so that nothing read is lost
+                self.report(LexErrorKind::InconsistentAccent, self.current_span);
+                for varna in varnas {
+                    if let Ok(akshara) = Akshara::new(vec![varna]) {
+                        self.lexed.tokens.push(Token::Akshara(akshara, self.current_span));
+                    }
                 }
             }
         }
+    }
 
-        // Push any remaining Akshara at the end
-        if !current_akshara.is_empty() {
-            tokens.push(Token::Akshara(Akshara::new(current_akshara).unwrap()));
+    /// Turns the vowel just read into its pluta form, for a 3 written right after it.
+    /// Returns false if there is no such vowel, and the 3 is just a numeral.
+    fn prolong(&mut self, span: Span) -> bool {
+        let Some(pluta) = self.previous.and_then(|v| v.pluta()) else {
+            return false;
+        };
+        let prolonged = self.amend_last(span, |varnas| {
+            if let Some(vowel) = varnas.last_mut() {
+                *vowel = pluta;
+            }
+        });
+        if prolonged {
+            self.previous = Some(pluta);
         }
+        prolonged
+    }
 
-        tokens
+    /// Changes the Varnas of the Akshara last pushed and extends it over `span`.
+    /// Returns false if the last token is not an Akshara.
+    fn amend_last(&mut self, span: Span, amend: impl FnOnce(&mut Vec<Varna>)) -> bool {
+        let Some(Token::Akshara(akshara, akshara_span)) = self.lexed.tokens.last_mut() else {
+            return false;
+        };
+        let mut varnas = akshara.varnas.clone();
+        amend(&mut varnas);
+        match Akshara::new(varnas) {
+            Ok(amended) => {
+                *akshara = amended;
+                akshara_span.end = span.end;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn report(&mut self, kind: LexErrorKind, span: Span) {
+        let input = self.text[span.start - self.base..span.end - self.base].to_string();
+        self.lexed.diagnostics.push(LexError { span, input, kind });
+    }
+}
+
+/// Returns true for combining marks: Latin diacritics, Devanagari signs and Vedic accents.
+fn is_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}' | '\u{0900}'..='\u{0903}' | '\u{093A}'..='\u{094F}' | '\u{0951}'..='\u{0957}' |
+        '\u{0962}'..='\u{0963}' | '\u{1CD0}'..='\u{1CFF}' | '\u{A8E0}'..='\u{A8F1}') && c != 'ऽ'
+}
+
+/// Renders a token stream in the given scheme, keeping unknown characters as they are.
+pub fn render(tokens: &[Token], scheme: TransliterationScheme) -> String {
+    let mut output = String::new();
+    let mut varnas = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Akshara(akshara, _) => varnas.extend_from_slice(&akshara.varnas),
+            Token::Unknown(c, _) => {
+                output.push_str(&lipi::render(&varnas, scheme));
+                varnas.clear();
+                output.push(*c);
+            }
+        }
     }
+    output.push_str(&lipi::render(&varnas, scheme));
+
+    output
 }
@@ -0,0 +1,188 @@
+use crate::shiksha::{Akshara, Pada, Vaakya, Danda};
+use crate::niruktam::niruktam::Token;
+
+/// Groups a flat token stream into Padas and Vaakyas.
+///
+/// Padas end at whitespace and punctuation; hyphens and avagrahas stay inside
+/// a Pada. Vaakyas end at a danda (`।`, `|`), a double danda (`॥`, `||`) or a
+/// blank line. A number between double dandas (`॥ १ ॥`) is the verse number
+/// of the Vaakya it follows.
+pub fn parse(tokens: &[Token]) -> Vec<Vaakya> {
+    Parser::new(tokens).parse()
+}
+
+struct Parser<'t> {
+    tokens: &'t [Token],
+    position: usize,
+    current_pada: Vec<Akshara>,
+    current_padas: Vec<Pada>,
+    vaakyas: Vec<Vaakya>,
+}
+
+impl<'t> Parser<'t> {
+    fn new(tokens: &'t [Token]) -> Self {
+        Parser { tokens, position: 0, current_pada: Vec::new(), current_padas: Vec::new(), vaakyas: Vec::new() }
+    }
+
+    fn parse(mut self) -> Vec<Vaakya> {
+        while let Some(token) = self.tokens.get(self.position) {
+            self.position += 1;
+
+            match token {
+                Token::Akshara(akshara, _) => self.current_pada.push(akshara.clone()),
+                Token::Unknown(c, _) if joins_pada(*c) => {}
+                Token::Unknown(c, _) => match self.danda(*c) {
+                    Some(danda) => {
+                        self.close_vaakya(Some(danda));
+                        self.read_verse_number(danda);
+                    }
+                    None if *c == '\n' && self.blank_line_follows() => self.close_vaakya(None),
+                    None => self.close_pada(),
+                },
+            }
+        }
+
+        self.close_vaakya(None);
+        self.vaakyas
+    }
+
+    /// Reads a danda at the current position, consuming the second bar of `||`.
+    fn danda(&mut self, c: char) -> Option<Danda> {
+        match c {
+            '।' => Some(Danda::Single),
+            '॥' => Some(Danda::Double),
+            '|' if self.peek_char() == Some('|') => {
+                self.position += 1;
+                Some(Danda::Double)
+            }
+            '|' => Some(Danda::Single),
+            _ => None,
+        }
+    }
+
+    /// Reads a verse number enclosed by dandas of the same kind (`॥ १ ॥`, `|| 1 ||`)
+    /// and assigns it to the last Vaakya. Dotted references (`१.२`) keep their last part.
+    fn read_verse_number(&mut self, opening: Danda) {
+        let start = self.position;
+        self.skip_spaces();
+
+        let mut digits = String::new();
+        while let Some(c) = self.peek_char() {
+            match c.to_digit(10).or_else(|| devanagari_digit(c)) {
+                Some(d) => digits.push(char::from_digit(d, 10).unwrap_or('0')),
+                None if c == '.' && !digits.is_empty() => digits.clear(),
+                None => break,
+            }
+            self.position += 1;
+        }
+        self.skip_spaces();
+
+        let closing = self.peek_char().and_then(|c| {
+            self.position += 1;
+            self.danda(c)
+        });
+
+        match (digits.parse().ok(), closing) {
+            (Some(number), Some(closing)) if closing == opening => {
+                if let Some(vaakya) = self.vaakyas.last_mut() {
+                    vaakya.number = Some(number);
+                }
+            }
+            _ => self.position = start, // Not a verse number; read the tokens normally
+        }
+    }
+
+    fn close_pada(&mut self) {
+        if !self.current_pada.is_empty() {
+            self.current_padas.push(Pada::new(std::mem::take(&mut self.current_pada)));
+        }
+    }
+
+    fn close_vaakya(&mut self, danda: Option<Danda>) {
+        self.close_pada();
+        if !self.current_padas.is_empty() {
+            let mut vaakya = Vaakya::new(std::mem::take(&mut self.current_padas));
+            vaakya.danda = danda;
+            self.vaakyas.push(vaakya);
+        }
+    }
+
+    /// Returns true if only whitespace separates the current position from another newline.
+    fn blank_line_follows(&self) -> bool {
+        self.tokens[self.position..].iter()
+            .map_while(|t| match t {
+                Token::Unknown(c, _) if c.is_whitespace() => Some(*c),
+                _ => None,
+            })
+            .any(|c| c == '\n')
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek_char().is_some_and(|c| c.is_whitespace() && c != '\n') {
+            self.position += 1;
+        }
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        match self.tokens.get(self.position) {
+            Some(Token::Unknown(c, _)) => Some(*c),
+            _ => None,
+        }
+    }
+}
+
+/// Characters that are written inside a Pada: compound hyphens and avagrahas.
+fn joins_pada(c: char) -> bool {
+    matches!(c, '-' | '\'' | '’' | 'ऽ')
+}
+
+fn devanagari_digit(c: char) -> Option<u32> {
+    ('०'..='९').contains(&c).then(|| c as u32 - '०' as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::Lexer;
+    use crate::shiksha::TransliterationScheme;
+
+    fn vaakyas(text: &str) -> Vec<(Vec<String>, Option<Danda>, Option<u32>)> {
+        parse(&Lexer::new(text).tokenize()).into_iter().map(|vaakya| {
+            let padas = vaakya.padas.iter().map(|p| p.aksharas.iter().map(|a| a.transliterate()).collect()).collect();
+            (padas, vaakya.danda, vaakya.number)
+        }).collect()
+    }
+
+    fn words(hk: &[&str]) -> Vec<String> {
+        hk.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn dandas_and_verse_numbers() {
+        assert_eq!(vaakyas("धर्मक्षेत्रे कुरुक्षेत्रे ।\nसमवेता युयुत्सवः ॥ १.१ ॥\nकिम् ॥"), [
+            (words(&["dharmakSetre", "kurukSetre"]), Some(Danda::Single), None),
+            (words(&["samavetA", "yuyutsavaH"]), Some(Danda::Double), Some(1)),
+            (words(&["kim"]), Some(Danda::Double), None),
+        ]);
+        assert_eq!(vaakyas("rAmaH | vanam || 12 ||"), [
+            (words(&["rAmaH"]), Some(Danda::Single), None),
+            (words(&["vanam"]), Some(Danda::Double), Some(12)),
+        ]);
+    }
+
+    #[test]
+    fn numbers_need_matching_dandas() {
+        // A number between unlike dandas is not a verse number; it is left unread
+        assert_eq!(vaakyas("rAmaH || 3 |"), [(words(&["rAmaH"]), Some(Danda::Double), None)]);
+    }
+
+    #[test]
+    fn padas_and_blank_lines() {
+        assert_eq!(vaakyas("rAma-lakSmaNau so'pi\n\nvanam"), [
+            (words(&["rAmalakSmaNau", "sopi"]), None, None),
+            (words(&["vanam"]), None, None),
+        ]);
+        assert_eq!(vaakyas("rAmaH\nvanam").len(), 1);
+        assert!(parse(&Lexer::with_scheme(" । ", TransliterationScheme::Devanagari).tokenize()).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::niruktam::niruktam::Token;
+use crate::niruktam::diagnostic::LexError;
+
+/// Location of a token in the lexer input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub start: usize,      // Byte offset of the first byte
+    pub end: usize,        // Byte offset just past the last byte
+    pub char_start: usize, // Offset of the first character, in characters
+    pub char_end: usize,   // Offset just past the last character, in characters
+    pub line: usize,       // Line of the first character, from 1
+    pub column: usize,     // Column of the first character, in characters from 1
+}
+
+impl Span {
+    /// Creates a span covering a byte range; character positions are filled in by `locate`.
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end, ..Span::default() }
+    }
+
+    /// Returns the part of the input the span covers.
+    pub fn slice<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.start..self.end]
+    }
+}
+
+/// Tracks character, line and column positions while walking forward through text.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Cursor {
+    byte: usize,
+    char: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor { byte: 0, char: 0, line: 1, column: 1 }
+    }
+}
+
+impl Cursor {
+    /// Moves forward up to the byte offset `to`, through `text` found at byte `base` of the input.
+    fn advance(&mut self, text: &str, base: usize, to: usize) {
+        let from = self.byte.max(base);
+        let to = to.max(from);
+        for c in text[from - base..to - base].chars() {
+            self.char += 1;
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.byte = to;
+    }
+
+    /// Fills in the character, line and column positions of the spans of the
+    /// tokens and diagnostics, which must lie within `text` found at byte `base`
+    /// of the input. Tokens must be in input order, as the lexer produces them.
+    pub(crate) fn locate(&mut self, text: &str, base: usize, tokens: &mut [Token], diagnostics: &mut [LexError]) {
+        // Diagnostics are found in input order too, except that an Akshara is only
+        // checked once complete; sort them and walk them with a cursor of their own
+        diagnostics.sort_by_key(|d| d.span.start);
+        let mut cursor = *self;
+        cursor.fill(text, base, diagnostics.iter_mut().map(|d| &mut d.span));
+
+        self.fill(text, base, tokens.iter_mut().map(Token::span_mut));
+    }
+
+    fn fill<'s>(&mut self, text: &str, base: usize, spans: impl Iterator<Item = &'s mut Span>) {
+        for span in spans {
+            self.advance(text, base, span.start);
+            span.char_start = self.char;
+            span.line = self.line;
+            span.column = self.column;
+            self.advance(text, base, span.end);
+            span.char_end = self.char;
+        }
+        self.advance(text, base, base + text.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::niruktam::{Lexer, Token};
+    use crate::shiksha::TransliterationScheme;
+
+    #[test]
+    fn positions() {
+        let input = "रामः\nkRSNa";
+        let tokens = Lexer::new(input).tokenize();
+        let spans: Vec<(&str, usize, usize, usize, usize, usize, usize)> = tokens.iter().map(|t| {
+            let s = t.span();
+            (s.slice(input), s.start, s.end, s.char_start, s.char_end, s.line, s.column)
+        }).collect();
+        assert_eq!(spans, [
+            ("रा", 0, 6, 0, 2, 1, 1),
+            ("मः", 6, 12, 2, 4, 1, 3),
+            ("\n", 12, 13, 4, 5, 1, 5),
+            ("kR", 13, 15, 5, 7, 2, 1),
+            ("SNa", 15, 18, 7, 10, 2, 3),
+        ]);
+    }
+
+    #[test]
+    fn akshara_source() {
+        let input = "kṛṣṇaḥ";
+        let lexer = Lexer::with_scheme(input, TransliterationScheme::Iast);
+        let tokens = lexer.tokenize();
+        assert_eq!(lexer.akshara_source(&tokens, 1), Some("ṣṇaḥ"));
+        assert_eq!(lexer.akshara_source(&tokens, 2), None);
+        assert!(matches!(tokens[1], Token::Akshara(_, span) if span.char_start == 2 && span.char_end == 6));
+    }
+}
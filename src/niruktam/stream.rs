@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+use crate::shiksha::TransliterationScheme;
+use crate::niruktam::niruktam::{lex, Lexed, Mode, Token};
+use crate::niruktam::span::Cursor;
+use crate::niruktam::diagnostic::LexError;
+
+/// Lexes Sanskrit text from any reader one line at a time, yielding tokens as
+/// they are read. Memory use is bounded by the longest line, not the input.
+///
+/// Spans are offsets into the whole stream. With a fixed scheme the tokens are
+/// the same as `Lexer::tokenize` would give for the whole text; in auto mode
+/// the scheme is detected line by line.
+pub struct StreamLexer<R> {
+    reader: R,
+    mode: Mode,
+    line: String,               // Reused buffer for the current line
+    offset: usize,              // Byte offset of the next line in the stream
+    cursor: Cursor,
+    pending: VecDeque<Token>,   // Tokens of the current line not yet yielded
+    scratch: Lexed,             // Reused buffers the lexer writes a line's tokens into
+    diagnostics: Vec<LexError>, // Problems recovered from so far, not yet taken
+    done: bool,
+}
+
+impl<R: BufRead> StreamLexer<R> {
+    /// Creates a streaming lexer that detects the scheme of each line.
+    pub fn new(reader: R) -> Self {
+        Self::with_mode(reader, Mode::Auto)
+    }
+
+    /// Creates a streaming lexer for input in a known scheme.
+    pub fn with_scheme(reader: R, scheme: TransliterationScheme) -> Self {
+        Self::with_mode(reader, Mode::Scheme(scheme))
+    }
+
+    /// Creates a streaming lexer for informal romanization.
+    pub fn lenient(reader: R) -> Self {
+        Self::with_mode(reader, Mode::Lenient)
+    }
+
+    fn with_mode(reader: R, mode: Mode) -> Self {
+        StreamLexer {
+            reader,
+            mode,
+            line: String::new(),
+            offset: 0,
+            cursor: Cursor::default(),
+            pending: VecDeque::new(),
+            scratch: Lexed::default(),
+            diagnostics: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Returns the problems the lexer recovered from in the lines read so far,
+    /// leaving none behind. Lines are read ahead only as far as tokens are yielded.
+    pub fn take_diagnostics(&mut self) -> Vec<LexError> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Reads and lexes the next line. Returns false at the end of the input.
+    fn fill(&mut self) -> io::Result<bool> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line)? == 0 {
+            return Ok(false);
+        }
+
+        lex(&self.line, self.offset, self.mode, &mut self.scratch);
+        self.cursor.locate(&self.line, self.offset, &mut self.scratch.tokens, &mut self.scratch.diagnostics);
+        self.pending.extend(self.scratch.tokens.drain(..));
+        self.diagnostics.append(&mut self.scratch.diagnostics);
+        self.offset += self.line.len();
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> Iterator for StreamLexer<R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            match self.fill() {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::Lexer;
+
+    #[test]
+    fn matches_tokenize() {
+        let text = "धर्मक्षेत्रे कुरुक्षेत्रे ।\nसमवेता युयुत्सवः ॥ १ ॥\n\nओ३म् x";
+        let whole = Lexer::with_scheme(text, TransliterationScheme::Devanagari).tokenize_with_diagnostics();
+
+        let mut stream = StreamLexer::with_scheme(text.as_bytes(), TransliterationScheme::Devanagari);
+        let tokens: Vec<Token> = stream.by_ref().collect::<io::Result<_>>().unwrap();
+        assert_eq!(tokens, whole.tokens);
+        assert_eq!(stream.take_diagnostics(), whole.diagnostics);
+        assert!(stream.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn detects_each_line() {
+        let stream = StreamLexer::new("रामः\nrAmaH\n".as_bytes());
+        let tokens: Vec<Token> = stream.collect::<io::Result<_>>().unwrap();
+        let text = crate::niruktam::render(&tokens, TransliterationScheme::Iast);
+        assert_eq!(text, "rāmaḥ\nrāmaḥ\n");
+        assert_eq!(tokens.last().map(|t| (t.span().start, t.span().line)), Some((18, 2)));
+    }
+}
@@ -0,0 +1,458 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use pss::{niruktam, Token, TransliterationScheme};
+use pss::shiksha::{IpaOptions, Varna};
+use crate::cli::{mark, Input};
+#[cfg(feature = "vyakaran")]
+use crate::cli::{varnas, word_pair};
+
+const HELP: &str = "\
+Commands (settings last for the session):
+  :scheme NAME      read input as hk, devanagari, iast, slp1, itrans, velthuis, auto or lenient
+  :out NAME         write Sanskrit in hk, devanagari, iast, slp1, itrans or velthuis
+  :tokens [TEXT]    show the lexer tokens and diagnostics
+  :translit [TEXT]  transliterate into the output scheme
+  :weights [TEXT]   show each Akshara with its weight
+  :scan [TEXT]      print the scansion of each line with weights and ganas
+  :phon [TEXT]      transcribe into IPA
+  :pron NAME        transcribe in the standard, hindi, marathi or madhyandina pronunciation
+  :accents on|off   mark the accent of each syllable in the transcription
+  :json [TEXT]      show the JSON document of the parsed text
+  :padanta on|off   count the last syllable of a line as guru when scanning
+  :marks STYLE      mark syllables with latin (U and —) or devanagari (ल and ग) marks
+  :color on|off     color the scansion by weight
+  :pada N|off       scan in pādas of N syllables, or the whole line as one
+  :yati N,N|off     mark a caesura after these syllables of each pāda
+  :sandhi A + B     join two words by sandhi
+  :split WORD       list the ways a word splits into two joined by sandhi
+  :show             show the current settings
+  :help             show this help
+  exit, :quit       leave the REPL
+A view command without TEXT makes that view the default for plain input.";
+
+/// What the REPL shows for a line of Sanskrit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Tokens,
+    Translit,
+    Weights,
+    Scan,
+    Phon,
+    Json,
+}
+
+impl View {
+    fn name(self) -> &'static str {
+        match self {
+            View::Tokens => "tokens",
+            View::Translit => "translit",
+            View::Weights => "weights",
+            View::Scan => "scan",
+            View::Phon => "phon",
+            View::Json => "json",
+        }
+    }
+}
+
+/// The settings of a REPL session.
+#[derive(Debug, Clone)]
+struct Settings {
+    input: Input,                  // How input is read
+    output: TransliterationScheme, // How Sanskrit is written back
+    view: View,                    // What plain input shows
+    padanta_guru: bool,            // Scan the last syllable of a line as guru
+    devanagari_marks: bool,        // Mark weights with ल and ग rather than U and —
+    color: bool,                   // Color the scansion with ANSI escapes
+    pada_length: Option<usize>,    // Syllables in a pāda, if lines are divided
+    yati: Vec<usize>,              // Syllables into a pāda after which a caesura falls
+    ipa: IpaOptions,               // How the phon view transcribes
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            input: Input::Auto,
+            output: TransliterationScheme::Iast,
+            view: View::Tokens,
+            padanta_guru: false,
+            devanagari_marks: false,
+            color: io::stdout().is_terminal(),
+            pada_length: None,
+            yati: Vec::new(),
+            ipa: IpaOptions::default(),
+        }
+    }
+}
+
+/// Sets or shows an on/off setting.
+fn switch(name: &str, setting: &mut bool, value: &str) -> String {
+    match value {
+        "on" => *setting = true,
+        "off" => *setting = false,
+        "" => {}
+        _ => return format!("Error: expected on or off, not {:?}", value),
+    }
+    format!("{}: {}", name, on_off(*setting))
+}
+
+fn on_off(setting: bool) -> &'static str {
+    if setting { "on" } else { "off" }
+}
+
+/// What the session does after reading a line.
+enum Reply {
+    Print(String),
+    Quit,
+}
+
+/// A REPL session: the settings chosen so far.
+#[derive(Default)]
+struct Session {
+    settings: Settings,
+}
+
+impl Session {
+    /// Evaluates one line: a `:command` or Sanskrit text shown in the current view.
+    fn eval(&mut self, line: &str) -> Reply {
+        let line = line.trim();
+        if line.eq_ignore_ascii_case("exit") {
+            return Reply::Quit;
+        }
+        let Some(command) = line.strip_prefix(':') else {
+            return Reply::Print(self.show(self.settings.view, line));
+        };
+
+        let (name, argument) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        let argument = argument.trim();
+        let view = match name {
+            "tokens" => Some(View::Tokens),
+            "translit" => Some(View::Translit),
+            "weights" => Some(View::Weights),
+            "scan" => Some(View::Scan),
+            "phon" => Some(View::Phon),
+            "json" => Some(View::Json),
+            _ => None,
+        };
+
+        let reply = match (name, view) {
+            (_, Some(view)) if argument.is_empty() => {
+                self.settings.view = view;
+                format!("View: {}", view.name())
+            }
+            (_, Some(view)) => self.show(view, argument),
+            ("scheme", _) => self.set_input(argument),
+            ("out", _) => self.set_output(argument),
+            ("padanta", _) => switch("Padanta guru", &mut self.settings.padanta_guru, argument),
+            ("color", _) => switch("Color", &mut self.settings.color, argument),
+            ("pron", _) => self.set_pronunciation(argument),
+            ("accents", _) => switch("Accents", &mut self.settings.ipa.accents, argument),
+            ("marks", _) => self.set_marks(argument),
+            ("pada", _) => self.set_pada(argument),
+            ("yati", _) => self.set_yati(argument),
+            ("sandhi", _) => self.sandhi(argument),
+            ("split", _) => self.split(argument),
+            ("show", _) => self.describe(),
+            ("help", _) => HELP.to_string(),
+            ("quit" | "q", _) => return Reply::Quit,
+            _ => format!("Error: unknown command :{}; type :help for the list", name),
+        };
+        Reply::Print(reply)
+    }
+
+    fn set_input(&mut self, name: &str) -> String {
+        if !name.is_empty() {
+            match Input::parse(name) {
+                Ok(input) => self.settings.input = input,
+                Err(err) => return format!("Error: {}", err),
+            }
+        }
+        format!("Input: {}", self.settings.input.name())
+    }
+
+    fn set_output(&mut self, name: &str) -> String {
+        if !name.is_empty() {
+            match name.parse() {
+                Ok(scheme) => self.settings.output = scheme,
+                Err(err) => return format!("Error: {}", err),
+            }
+        }
+        format!("Output: {}", self.settings.output)
+    }
+
+    fn set_pronunciation(&mut self, name: &str) -> String {
+        if !name.is_empty() {
+            match name.parse() {
+                Ok(pronunciation) => self.settings.ipa.pronunciation = pronunciation,
+                Err(err) => return format!("Error: {}", err),
+            }
+        }
+        format!("Pronunciation: {}", self.settings.ipa.pronunciation)
+    }
+
+    fn set_marks(&mut self, style: &str) -> String {
+        match style {
+            "latin" => self.settings.devanagari_marks = false,
+            "devanagari" => self.settings.devanagari_marks = true,
+            "" => {}
+            _ => return format!("Error: expected latin or devanagari, not {:?}", style),
+        }
+        format!("Marks: {}", if self.settings.devanagari_marks { "devanagari" } else { "latin" })
+    }
+
+    fn set_pada(&mut self, length: &str) -> String {
+        match length {
+            "" => {}
+            "off" => self.settings.pada_length = None,
+            _ => match length.parse() {
+                Ok(n) if n > 0 => self.settings.pada_length = Some(n),
+                _ => return format!("Error: expected a number of syllables or off, not {:?}", length),
+            },
+        }
+        format!("Pada: {}", self.settings.pada_length.map_or("off".to_string(), |n| format!("{} syllables", n)))
+    }
+
+    fn set_yati(&mut self, positions: &str) -> String {
+        match positions {
+            "" => {}
+            "off" => self.settings.yati.clear(),
+            _ => match positions.split(',').map(|p| p.trim().parse()).collect() {
+                Ok(yati) => self.settings.yati = yati,
+                Err(_) => return format!("Error: expected syllable counts such as 4,10, not {:?}", positions),
+            },
+        }
+        format!("Yati: {}", self.yati_name())
+    }
+
+    fn yati_name(&self) -> String {
+        if self.settings.yati.is_empty() {
+            return "off".to_string();
+        }
+        self.settings.yati.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",")
+    }
+
+    fn describe(&self) -> String {
+        let s = &self.settings;
+        format!(
+            "Input: {}\nOutput: {}\nView: {}\nPadanta guru: {}\nMarks: {}\nColor: {}\nPada: {}\nYati: {}\nPronunciation: {}\nAccents: {}",
+            s.input.name(), s.output, s.view.name(), on_off(s.padanta_guru),
+            if s.devanagari_marks { "devanagari" } else { "latin" }, on_off(s.color),
+            s.pada_length.map_or("off".to_string(), |n| format!("{} syllables", n)), self.yati_name(),
+            s.ipa.pronunciation, on_off(s.ipa.accents),
+        )
+    }
+
+    /// Shows text in the given view.
+    fn show(&self, view: View, text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+
+        let lexed = self.settings.input.lex(text);
+        let mut lines = Vec::new();
+        if view == View::Tokens && self.settings.input == Input::Auto {
+            let detection = niruktam::detect(text);
+            lines.push(format!("Scheme: {:?} (confidence {:.2})", detection.scheme, detection.confidence));
+        }
+
+        match view {
+            View::Tokens => lines.push(format!("Lexed Output: {:?}", lexed.tokens)),
+            View::Translit => lines.push(niruktam::render(&lexed.tokens, self.settings.output)),
+            View::Weights => lines.push(self.weights(&lexed.tokens)),
+            View::Scan => lines.extend(self.scan(&lexed.tokens)),
+            View::Phon => lines.extend(niruktam::parse(&lexed.tokens).iter().map(|vaakya| {
+                vaakya.padas.iter().map(|p| p.ipa(&self.settings.ipa)).collect::<Vec<_>>().join(" ")
+            })),
+            View::Json => lines.push(json(&lexed.tokens)),
+        }
+
+        lines.extend(lexed.diagnostics.iter().map(|d| format!("Warning: {}", d)));
+        lines.join("\n")
+    }
+
+    /// Each Akshara in the output scheme, followed by its weight.
+    fn weights(&self, tokens: &[Token]) -> String {
+        tokens.iter().filter_map(|token| match token {
+            Token::Akshara(akshara, _) => Some(format!("{}({})", self.render(&akshara.varnas), mark(akshara.maatra.length))),
+            Token::Unknown(..) => None,
+        }).collect::<Vec<_>>().join(" ")
+    }
+
+    #[cfg(feature = "chandas")]
+    fn scan(&self, tokens: &[Token]) -> Vec<String> {
+        use pss::chandas::{prastara, MarkStyle, PrastaraOptions, VibhagaOptions};
+
+        let options = PrastaraOptions {
+            scheme: self.settings.output,
+            marks: if self.settings.devanagari_marks { MarkStyle::Devanagari } else { MarkStyle::Latin },
+            color: self.settings.color,
+            pada_length: self.settings.pada_length,
+            yati: self.settings.yati.clone(),
+            vibhaga: VibhagaOptions { padanta_guru: self.settings.padanta_guru },
+        };
+        niruktam::parse(tokens).iter().map(|vaakya| prastara(vaakya, &options)).collect()
+    }
+
+    #[cfg(not(feature = "chandas"))]
+    fn scan(&self, _tokens: &[Token]) -> Vec<String> {
+        vec!["Error: scansion needs the chandas feature".to_string()]
+    }
+
+    #[cfg(feature = "vyakaran")]
+    fn sandhi(&self, argument: &str) -> String {
+        let Some((first, second)) = word_pair(argument) else {
+            return "Error: expected :sandhi A + B".to_string();
+        };
+        let joined = pss::vyakaran::join(&self.varnas(first), &self.varnas(second));
+        let written = joined.words().iter().map(|word| self.render(word)).collect::<Vec<_>>().join(" ");
+        match joined.rule {
+            Some(rule) => format!("{}  [{}]", written, rule),
+            None => format!("{}  [no sandhi]", written),
+        }
+    }
+
+    #[cfg(feature = "vyakaran")]
+    fn split(&self, word: &str) -> String {
+        let splits = pss::vyakaran::split(&self.varnas(word));
+        if splits.is_empty() {
+            return "No split found".to_string();
+        }
+        splits.iter()
+            .map(|s| format!("{} + {}  [{}]", self.render(&s.first), self.render(&s.second), s.rule))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[cfg(not(feature = "vyakaran"))]
+    fn sandhi(&self, _argument: &str) -> String {
+        "Error: sandhi needs the vyakaran feature".to_string()
+    }
+
+    #[cfg(not(feature = "vyakaran"))]
+    fn split(&self, _word: &str) -> String {
+        "Error: sandhi needs the vyakaran feature".to_string()
+    }
+
+    /// The Varnas of a word, read as the session reads input.
+    #[cfg(feature = "vyakaran")]
+    fn varnas(&self, word: &str) -> Vec<Varna> {
+        varnas(&self.settings.input.lex(word).tokens)
+    }
+
+    fn render(&self, varnas: &[Varna]) -> String {
+        pss::shiksha::lipi::render(varnas, self.settings.output)
+    }
+}
+
+#[cfg(feature = "serialization")]
+fn json(tokens: &[Token]) -> String {
+    pss::shiksha::to_json(&niruktam::parse(tokens))
+}
+
+#[cfg(not(feature = "serialization"))]
+fn json(_tokens: &[Token]) -> String {
+    "Error: JSON output needs the serialization feature".to_string()
+}
+
+/// Runs the Read-Eval-Print Loop (REPL) until `exit` or the end of input.
+pub fn run() {
+    let mut session = Session::default();
+    let stdin = io::stdin();
+
+    loop {
+        print!("==> ");
+        io::stdout().flush().unwrap(); // Ensure prompt appears before input
+
+        let mut input = String::new();
+        match stdin.lock().read_line(&mut input) {
+            Ok(0) => break, // Exit if no input
+            Ok(_) => match session.eval(&input) {
+                Reply::Print(output) if output.is_empty() => {}
+                Reply::Print(output) => println!("{}", output),
+                Reply::Quit => break,
+            },
+            Err(err) => {
+                eprintln!("Error reading input: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        let mut session = Session::default();
+        session.settings.color = false;
+        session
+    }
+
+    fn reply(session: &mut Session, line: &str) -> String {
+        match session.eval(line) {
+            Reply::Print(output) => output,
+            Reply::Quit => panic!("{line:?} quit the session"),
+        }
+    }
+
+    #[test]
+    fn settings_last_for_the_session() {
+        let mut session = session();
+        assert_eq!(reply(&mut session, ":scheme hk"), "Input: hk");
+        assert_eq!(reply(&mut session, ":out devanagari"), "Output: devanagari");
+        assert_eq!(reply(&mut session, ":translit"), "View: translit");
+        assert_eq!(reply(&mut session, "rAmaH"), "रामः");
+        assert_eq!(reply(&mut session, "vanaM gacchati"), "वनं गच्छति");
+        assert_eq!(reply(&mut session, ":out iast"), "Output: iast");
+        assert_eq!(reply(&mut session, "rAmaH"), "rāmaḥ");
+        assert!(reply(&mut session, ":show").starts_with("Input: hk\nOutput: iast\nView: translit"));
+        assert!(matches!(session.eval("exit"), Reply::Quit));
+    }
+
+    #[test]
+    fn weights() {
+        let mut session = session();
+        reply(&mut session, ":scheme hk");
+        reply(&mut session, ":out hk");
+        assert_eq!(reply(&mut session, ":weights rAmaH"), "rA(G) maH(G)");
+        assert_eq!(reply(&mut session, ":weights hariH"), "ha(L) riH(G)");
+    }
+
+    #[test]
+    #[cfg(feature = "chandas")]
+    fn scan() {
+        let mut session = session();
+        reply(&mut session, ":scheme hk");
+        let scan = reply(&mut session, ":scan dharmakSetre kurukSetre samavetA yuyutsavaH");
+        let lines: Vec<&str> = scan.lines().collect();
+        assert_eq!(lines[0], "dhar mak ṣet re ku ruk ṣet re sa ma ve tā yu yut sa vaḥ");
+        assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>().join(" "), "— — — — U — — — U U — — U — U — = 27");
+        assert_eq!(lines[2], "[    ma    ] [  ra   ] [  ta   ] [  ya  ] [  ja   ] ga");
+    }
+
+    #[test]
+    #[cfg(feature = "vyakaran")]
+    fn sandhi_and_split() {
+        let mut session = session();
+        reply(&mut session, ":scheme hk");
+        reply(&mut session, ":out hk");
+        assert_eq!(reply(&mut session, ":sandhi deva + indra"), "devendra  [ādguṇaḥ (6.1.87)]");
+        assert_eq!(reply(&mut session, ":sandhi nadIH + rAjate"), "nadI rAjate  [ro ri (8.3.14)]");
+        assert_eq!(reply(&mut session, ":sandhi vana + gacchati"), "vanagacchati  [no sandhi]");
+        assert!(reply(&mut session, ":split devendra").lines().any(|line| line == "deva + indra  [ādguṇaḥ (6.1.87)]"));
+    }
+
+    #[test]
+    fn errors() {
+        let mut session = session();
+        for line in [
+            ":bogus", ":scheme klingon", ":out klingon", ":pada x", ":pada 0", ":yati a,b", ":pron bengali",
+            ":accents maybe", ":color maybe", ":padanta yes", ":marks cyrillic", ":sandhi deva",
+        ] {
+            let reply = reply(&mut session, line);
+            assert!(reply.starts_with("Error: "), "{line:?} gave {reply:?}");
+            assert_eq!(reply.lines().count(), 1, "{line:?}");
+        }
+        // The settings are left as they were
+        assert!(reply(&mut session, ":show").starts_with("Input: auto\nOutput: iast\nView: tokens"));
+    }
+}
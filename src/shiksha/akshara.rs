@@ -1,84 +1,155 @@
+use std::fmt;
 use crate::shiksha::{Varna, Swara, SamaSvara};
-use crate::chandas::Maatra;
+use crate::shiksha::ipa::{self, IpaOptions};
+use crate::chandas::{Maatra, ArdhaMaatra};
 
 /// Defines an Akshara (Syllable) as an array of Varnas
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Akshara {
     pub varnas: Vec<Varna>, // An Akshara consists of multiple Varnas
-    pub swara: Option<String>, // Unified Swara
-    pub sama_svara: Option<String>, // Unified Sama Svara
+    pub swara: Option<Swara>, // Swara read from accent marks in the text; None where the text has none
+    pub sama_svara: Option<SamaSvara>, // Unified Sama Svara
     pub maatra: Maatra, // Syllable duration/weight from chandas
 }
 
+/// Why a list of Varnas cannot form an Akshara.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AksharaError {
+    Empty,             // No Varnas at all
+    InconsistentSwara, // The vowels carry different Swaras or Sama Svaras
+}
+
+impl fmt::Display for AksharaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AksharaError::Empty => write!(f, "an Akshara needs at least one Varna"),
+            AksharaError::InconsistentSwara => write!(f, "the vowels of an Akshara must share one Swara"),
+        }
+    }
+}
+
+impl std::error::Error for AksharaError {}
+
 impl Akshara {
     /// Creates a new Akshara from a list of Varnas
-    pub fn new(varnas: Vec<Varna>) -> Option<Self> {
-        if varnas.is_empty() {
-            return None;
-        }
+    pub fn new(varnas: Vec<Varna>) -> Result<Self, AksharaError> {
+        Self::validate(&varnas)?;
 
-        // Extract first Swara and Sama Svara as Strings
-        let first_swara = varnas[0].swara.as_ref().map(|s| format!("{:?}", s));
-        let first_sama_svara = varnas[0].sama_svara.as_ref().map(|s| format!("{:?}", s));
-
-        // Check for consistency across all Varnas
-        let all_swara_match = varnas.iter().all(|v| v.swara.as_ref().map(|s| format!("{:?}", s)) == first_swara);
-        let all_sama_svara_match = varnas.iter().all(|v| v.sama_svara.as_ref().map(|s| format!("{:?}", s)) == first_sama_svara);
-
-        if all_swara_match && all_sama_svara_match {
-            // Determine syllable weight based on Varnas
-            let maatra = Self::determine_weight(&varnas);
-            
-            Some(Self {
-                varnas,
-                swara: first_swara,
-                sama_svara: first_sama_svara,
-                maatra,
-            })
-        } else {
-            None // Inconsistent Akshara
-        }
+        // Only vowels carry a Sama Svara; consonants take that of their vowel.
+        // The Swara of the inventory is fixed per vowel, so it is not assumed
+        // here: the lexer sets it from accent marks when the text has them
+        let first_vowel = varnas.iter().find(|v| v.swara.is_some());
+        let sama_svara = first_vowel.and_then(|v| v.sama_svara);
+
+        // Determine syllable weight based on Varnas
+        let maatra = Self::determine_weight(&varnas);
+
+        Ok(Self { varnas, swara: None, sama_svara, maatra })
+    }
+
+    /// Checks that the Varnas can form an Akshara: there is at least one,
+    /// and all vowels among them agree on Swara and Sama Svara.
+    pub fn validate(varnas: &[Varna]) -> Result<(), AksharaError> {
+        let first = varnas.first().ok_or(AksharaError::Empty)?;
+        let first_vowel = varnas.iter().find(|v| v.swara.is_some()).unwrap_or(first);
+
+        let consistent = varnas.iter()
+            .filter(|v| v.swara.is_some())
+            .all(|v| v.swara == first_vowel.swara && v.sama_svara == first_vowel.sama_svara);
+
+        if consistent { Ok(()) } else { Err(AksharaError::InconsistentSwara) }
     }
 
     /// Returns the transliteration of the Akshara using Harvard-Kyoto scheme
     pub fn transliterate(&self) -> String {
-        self.varnas.iter().map(|v| v.hk.clone()).collect::<String>()
+        self.varnas.iter().map(|v| v.hk).collect::<String>()
+    }
+
+    /// Returns the IPA transcription of the Akshara.
+    pub fn ipa(&self, options: &IpaOptions) -> String {
+        ipa::syllable(&self.varnas, self.swara, options)
+    }
+
+    /// Returns how long the Akshara takes to say, from the durations of its Varnas.
+    pub fn duration(&self) -> ArdhaMaatra {
+        self.varnas.iter().map(ArdhaMaatra::from).sum()
     }
 
     /// Determines whether an Akshara is Laghu or Guru based on its Varnas
     /// According to classical Sanskrit prosody rules:
-    /// 1. A syllable is Guru (heavy) if:
-    ///    - It contains a long vowel (diirgha)
+    /// 1. A syllable takes the Kaala of its vowel: hrasva laghu, diirgha guru, pluta pluta
+    /// 2. A syllable with a short vowel is still Guru (heavy) if:
     ///    - It contains a short vowel followed by a conjunct consonant
-    ///    - It contains anusvara or visarga
-    /// 2. A syllable is Laghu (light) if it contains a short vowel followed by at most one consonant
+    ///    - It contains anusvara, visarga, jihvamuliya or upadhmaniya
     fn determine_weight(varnas: &[Varna]) -> Maatra {
-        // Check if any Varna has Diirgha Matra
-        let has_long_vowel = varnas.iter().any(|v| {
-            v.matra.as_ref().map_or(false, |m| format!("{:?}", m) == "Diirgha")
-        });
-
-        if has_long_vowel {
-            return Maatra::guru();
-        }
-
         // Count consonants after the vowel
         let mut consonant_count = 0;
         let mut found_vowel = false;
 
         for varna in varnas {
-            if varna.matra.is_some() {
+            if varna.is_vowel() {
                 found_vowel = true;
             } else if found_vowel {
                 consonant_count += 1;
             }
         }
+        let closed = consonant_count > 1 || varnas.iter().any(|v| v.is_ayogavaha());
 
-        // If there are multiple consonants after the vowel, it's Guru
-        if consonant_count > 1 {
-            Maatra::guru()
-        } else {
-            Maatra::laghu()
+        match varnas.iter().find_map(|v| v.matra).map(Maatra::from) {
+            Some(maatra) if maatra == Maatra::laghu() && closed => Maatra::guru(),
+            Some(maatra) => maatra,
+            None if closed => Maatra::guru(),
+            None => Maatra::laghu(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chandas::Kaala;
+    use crate::niruktam::{Lexer, Token};
+    use crate::shiksha::TransliterationScheme;
+
+    fn aksharas(text: &str, scheme: TransliterationScheme) -> Vec<Akshara> {
+        Lexer::with_scheme(text, scheme).tokenize().into_iter().filter_map(|t| match t {
+            Token::Akshara(akshara, _) => Some(akshara),
+            Token::Unknown(..) => None,
+        }).collect()
+    }
+
+    fn weights(text: &str) -> Vec<(String, Kaala)> {
+        aksharas(text, TransliterationScheme::HarvardKyoto).iter().map(|a| (a.transliterate(), a.maatra.length)).collect()
+    }
+
+    #[test]
+    fn vowel_length() {
+        assert_eq!(weights("ka"), [("ka".to_string(), Kaala::One)]);
+        assert_eq!(weights("kA"), [("kA".to_string(), Kaala::Two)]);
+        assert_eq!(weights("ke"), [("ke".to_string(), Kaala::Two)]);
+    }
+
+    #[test]
+    fn ayogavahas_make_guru() {
+        assert_eq!(weights("saM"), [("saM".to_string(), Kaala::Two)]);
+        assert_eq!(weights("naH"), [("naH".to_string(), Kaala::Two)]);
+        assert_eq!(weights("aZ"), [("aZ".to_string(), Kaala::Two)]);
+        assert_eq!(weights("aV"), [("aV".to_string(), Kaala::Two)]);
+        assert_eq!(weights("rAmaH"), [("rA".to_string(), Kaala::Two), ("maH".to_string(), Kaala::Two)]);
+    }
+
+    #[test]
+    fn pluta_makes_three_kaalas() {
+        assert_eq!(weights("a3"), [("a3".to_string(), Kaala::Three)]);
+        assert_eq!(weights("hare3"), [("ha".to_string(), Kaala::One), ("re3".to_string(), Kaala::Three)]);
+        let om = aksharas("ओ३म्", TransliterationScheme::Devanagari);
+        assert_eq!(om[0].maatra, Maatra::pluta());
+    }
+
+    #[test]
+    fn verse_numbers_are_not_pluta() {
+        let tokens = Lexer::with_scheme("रामः ॥ ३ ॥", TransliterationScheme::Devanagari).tokenize();
+        assert!(tokens.iter().any(|t| matches!(t, Token::Unknown('३', _))));
+        assert_eq!(aksharas("रामः ॥ ३ ॥", TransliterationScheme::Devanagari).len(), 2);
+    }
+}
@@ -0,0 +1,359 @@
+use std::fmt;
+use crate::niruktam::{Lexer, Token};
+use crate::shiksha::{lipi, Pada, TransliterationScheme, Vaakya};
+
+/// A syntactic word: one row of CoNLL-U with a numeric ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConlluWord {
+    pub pada: Pada,                   // FORM
+    pub form: Option<String>,         // FORM as written, kept when it is not all Aksharas
+    pub lemma: Option<String>,        // LEMMA
+    pub upos: Option<String>,         // Universal part of speech
+    pub xpos: Option<String>,         // Language-specific part of speech
+    pub feats: Vec<(String, String)>, // Morphological features, as name and value
+    pub head: Option<usize>,          // ID of the head word, 0 for the root
+    pub deprel: Option<String>,       // Dependency relation to the head
+    pub deps: Option<String>,         // Enhanced dependencies, as written
+    pub misc: Option<String>,         // Anything else, as written
+}
+
+/// A Pada as written in the text. A Pada joined from several words by
+/// sandhi is a multiword token: a range row holding the surface form,
+/// followed by a row for each word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConlluToken {
+    pub surface: Pada,
+    pub form: Option<String>,   // The surface as written, kept when it is not all Aksharas
+    pub words: Vec<ConlluWord>, // The words the Pada is made of; one unless split
+}
+
+/// A sentence of CoNLL-U: a Vaakya and the annotation of its Padas.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConlluSentence {
+    pub id: Option<String>, // sent_id
+    pub tokens: Vec<ConlluToken>,
+}
+
+impl ConlluWord {
+    /// A word without annotation.
+    pub fn new(pada: Pada) -> Self {
+        ConlluWord { pada, form: None, lemma: None, upos: None, xpos: None, feats: Vec::new(), head: None, deprel: None, deps: None, misc: None }
+    }
+}
+
+impl ConlluToken {
+    /// A token of one word, the Pada itself, without annotation.
+    pub fn new(pada: Pada) -> Self {
+        ConlluToken { words: vec![ConlluWord::new(pada.clone())], surface: pada, form: None }
+    }
+
+    /// A token split by sandhi into the given words.
+    pub fn split(surface: Pada, words: Vec<Pada>) -> Self {
+        let words = words.into_iter().map(ConlluWord::new).collect();
+        ConlluToken { surface, form: None, words }
+    }
+}
+
+impl ConlluSentence {
+    /// A sentence of the Padas of a Vaakya, each a token of one word.
+    pub fn from_vaakya(vaakya: &Vaakya) -> Self {
+        ConlluSentence { id: None, tokens: vaakya.padas.iter().cloned().map(ConlluToken::new).collect() }
+    }
+
+    /// The Vaakya of the surface Padas.
+    pub fn vaakya(&self) -> Vaakya {
+        Vaakya::new(self.tokens.iter().map(|t| t.surface.clone()).collect())
+    }
+}
+
+/// Why a CoNLL-U file could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConlluError {
+    pub line: usize, // Line of the file, from 1
+    pub kind: ConlluErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConlluErrorKind {
+    Columns(usize),      // A row without 10 tab-separated columns; holds the count found
+    BadId(String),       // An ID that is not a number, range or empty node
+    OutOfOrder(String),  // An ID that does not follow the previous one
+    BadHead(String),     // A HEAD that is not a number
+    BadFeature(String),  // A feature without `=`
+    IncompleteRange,     // A multiword range not followed by all its words
+}
+
+impl fmt::Display for ConlluError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ConlluErrorKind::Columns(found) => write!(f, "expected 10 columns, found {}", found),
+            ConlluErrorKind::BadId(id) => write!(f, "invalid ID {:?}", id),
+            ConlluErrorKind::OutOfOrder(id) => write!(f, "ID {:?} is out of order", id),
+            ConlluErrorKind::BadHead(head) => write!(f, "invalid HEAD {:?}", head),
+            ConlluErrorKind::BadFeature(feature) => write!(f, "invalid feature {:?}", feature),
+            ConlluErrorKind::IncompleteRange => write!(f, "multiword token is missing some of its words"),
+        }
+    }
+}
+
+impl std::error::Error for ConlluError {}
+
+/// Writes sentences as CoNLL-U, with forms in the given scheme. Each
+/// sentence is preceded by its `sent_id`, if any, and its `text`; lemmas
+/// and the other annotation are written as they are.
+pub fn to_conllu(sentences: &[ConlluSentence], scheme: TransliterationScheme) -> String {
+    let mut output = String::new();
+
+    for sentence in sentences {
+        if let Some(id) = &sentence.id {
+            output.push_str(&format!("# sent_id = {}\n", id));
+        }
+        let text: Vec<String> = sentence.tokens.iter().map(|t| form(&t.surface, &t.form, scheme)).collect();
+        output.push_str(&format!("# text = {}\n", text.join(" ")));
+
+        let mut id = 1;
+        for token in &sentence.tokens {
+            if token.words.len() > 1 {
+                let last = id + token.words.len() - 1;
+                output.push_str(&format!("{}-{}\t{}\t_\t_\t_\t_\t_\t_\t_\t_\n", id, last, form(&token.surface, &token.form, scheme)));
+            }
+            for word in &token.words {
+                let feats = if word.feats.is_empty() {
+                    "_".to_string()
+                } else {
+                    let mut feats = word.feats.clone();
+                    feats.sort_by_key(|(name, _)| name.to_lowercase());
+                    feats.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join("|")
+                };
+                let columns = [
+                    id.to_string(),
+                    form(&word.pada, &word.form, scheme),
+                    column(&word.lemma),
+                    column(&word.upos),
+                    column(&word.xpos),
+                    feats,
+                    word.head.map_or("_".to_string(), |h| h.to_string()),
+                    column(&word.deprel),
+                    column(&word.deps),
+                    column(&word.misc),
+                ];
+                output.push_str(&columns.join("\t"));
+                output.push('\n');
+                id += 1;
+            }
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Reads CoNLL-U, lexing each form in the given scheme. A form with
+/// anything but Aksharas, such as punctuation, is also kept as written.
+/// Comments other than `sent_id` and empty nodes (IDs such as `3.1`) are
+/// skipped.
+pub fn from_conllu(text: &str, scheme: TransliterationScheme) -> Result<Vec<ConlluSentence>, ConlluError> {
+    let mut sentences = Vec::new();
+    let mut sentence = ConlluSentence::default();
+    let mut range: Option<(usize, (Pada, Option<String>))> = None; // Last ID and surface of the open multiword token
+    let mut words: Vec<ConlluWord> = Vec::new(); // Words read for the open multiword token
+    let mut next = 1;
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |kind| ConlluError { line: i + 1, kind };
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() {
+            if range.is_some() {
+                return Err(error(ConlluErrorKind::IncompleteRange));
+            }
+            if !sentence.tokens.is_empty() {
+                sentences.push(std::mem::take(&mut sentence));
+            }
+            next = 1;
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some((key, value)) = comment.split_once('=') {
+                if key.trim() == "sent_id" {
+                    sentence.id = Some(value.trim().to_string());
+                }
+            }
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() != 10 {
+            return Err(error(ConlluErrorKind::Columns(columns.len())));
+        }
+        let id = columns[0];
+
+        if id.contains('.') {
+            continue; // Empty node
+        }
+        if let Some((first, last)) = id.split_once('-') {
+            let (Ok(first), Ok(last)) = (first.parse::<usize>(), last.parse::<usize>()) else {
+                return Err(error(ConlluErrorKind::BadId(id.to_string())));
+            };
+            if first != next || last < first || range.is_some() {
+                return Err(error(ConlluErrorKind::OutOfOrder(id.to_string())));
+            }
+            range = Some((last, pada(columns[1], scheme)));
+            continue;
+        }
+
+        match id.parse::<usize>() {
+            Ok(n) if n == next => next += 1,
+            Ok(_) => return Err(error(ConlluErrorKind::OutOfOrder(id.to_string()))),
+            Err(_) => return Err(error(ConlluErrorKind::BadId(id.to_string()))),
+        }
+
+        let feats = match columns[5] {
+            "_" => Vec::new(),
+            feats => feats.split('|').map(|feature| match feature.split_once('=') {
+                Some((name, value)) => Ok((name.to_string(), value.to_string())),
+                None => Err(error(ConlluErrorKind::BadFeature(feature.to_string()))),
+            }).collect::<Result<_, _>>()?,
+        };
+        let head = match columns[6] {
+            "_" => None,
+            head => Some(head.parse().map_err(|_| error(ConlluErrorKind::BadHead(head.to_string())))?),
+        };
+        let (pada, form) = pada(columns[1], scheme);
+        let word = ConlluWord {
+            pada,
+            form,
+            lemma: value(columns[2]),
+            upos: value(columns[3]),
+            xpos: value(columns[4]),
+            feats,
+            head,
+            deprel: value(columns[7]),
+            deps: value(columns[8]),
+            misc: value(columns[9]),
+        };
+
+        match range.take() {
+            Some((last, surface)) => {
+                words.push(word);
+                if next > last {
+                    let (surface, form) = surface;
+                    sentence.tokens.push(ConlluToken { surface, form, words: std::mem::take(&mut words) });
+                } else {
+                    range = Some((last, surface));
+                }
+            }
+            None => sentence.tokens.push(ConlluToken { surface: word.pada.clone(), form: word.form.clone(), words: vec![word] }),
+        }
+    }
+
+    if range.is_some() {
+        return Err(ConlluError { line: text.lines().count(), kind: ConlluErrorKind::IncompleteRange });
+    }
+    if !sentence.tokens.is_empty() {
+        sentences.push(sentence);
+    }
+    Ok(sentences)
+}
+
+/// Writes a Pada as one form, or the form as written if it was kept.
+fn form(pada: &Pada, written: &Option<String>, scheme: TransliterationScheme) -> String {
+    if let Some(written) = written {
+        return written.clone();
+    }
+    let varnas: Vec<_> = pada.aksharas.iter().flat_map(|a| a.varnas.iter().copied()).collect();
+    lipi::render(&varnas, scheme)
+}
+
+/// Reads a form as one Pada, with the form itself if it is not all Aksharas.
+fn pada(form: &str, scheme: TransliterationScheme) -> (Pada, Option<String>) {
+    let mut written = None;
+    let aksharas = Lexer::with_scheme(form, scheme).tokenize().into_iter().filter_map(|token| match token {
+        Token::Akshara(akshara, _) => Some(akshara),
+        Token::Unknown(..) => {
+            written = Some(form.to_string());
+            None
+        }
+    }).collect();
+    (Pada::new(aksharas), written)
+}
+
+fn column(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "_".to_string())
+}
+
+fn value(column: &str) -> Option<String> {
+    (column != "_").then(|| column.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOLD: &str = "\
+# sent_id = bg-1.1a
+# text = dharmakṣetre kurukṣetre samavetā yuyutsavaḥ
+1\tdharmakṣetre\tdharmakṣetra\tNOUN\t_\tCase=Loc|Number=Sing\t2\tnmod\t_\t_
+2\tkurukṣetre\tkurukṣetra\tPROPN\t_\tCase=Loc|Number=Sing\t4\tobl\t_\t_
+3\tsamavetā\tsamaveta\tADJ\t_\tCase=Nom|Number=Plur\t4\tamod\t_\t_
+4\tyuyutsavaḥ\tyuyutsu\tNOUN\t_\tCase=Nom|Number=Plur\t0\troot\t_\t_
+
+# text = tacchrutvā
+1-2\ttacchrutvā\t_\t_\t_\t_\t_\t_\t_\t_
+1\ttat\ttad\tPRON\t_\t_\t2\tobj\t_\t_
+2\tśrutvā\tśru\tVERB\t_\tVerbForm=Conv\t0\troot\t_\t_
+
+";
+
+    #[test]
+    fn round_trip() {
+        let sentences = from_conllu(GOLD, TransliterationScheme::Iast).unwrap();
+        assert_eq!(sentences.len(), 2);
+        assert_eq!(sentences[0].id.as_deref(), Some("bg-1.1a"));
+        assert_eq!(sentences[0].tokens[3].words[0].head, Some(0));
+        assert_eq!(sentences[1].tokens.len(), 1);
+        assert_eq!(sentences[1].tokens[0].words.len(), 2);
+        assert_eq!(to_conllu(&sentences, TransliterationScheme::Iast), GOLD);
+    }
+
+    #[test]
+    fn punctuation_round_trip() {
+        let text = "\
+# text = rāmaḥ , vanam gacchati ।
+1\trāmaḥ\trāma\tPROPN\t_\t_\t4\tnsubj\t_\t_
+2\t,\t,\tPUNCT\t_\t_\t1\tpunct\t_\t_
+3\tvanam\tvana\tNOUN\t_\t_\t4\tobj\t_\t_
+4\tgacchati\tgam\tVERB\t_\t_\t0\troot\t_\t_
+5\t।\t।\tPUNCT\t_\t_\t4\tpunct\t_\t_
+
+";
+        let sentences = from_conllu(text, TransliterationScheme::Iast).unwrap();
+        assert_eq!(sentences[0].tokens[1].words[0].form.as_deref(), Some(","));
+        assert_eq!(sentences[0].tokens[4].form.as_deref(), Some("।"));
+        assert_eq!(sentences[0].tokens[0].form, None);
+        assert_eq!(to_conllu(&sentences, TransliterationScheme::Iast), text);
+    }
+
+    #[test]
+    fn from_a_vaakya() {
+        let vaakyas = crate::niruktam::parse(&Lexer::with_scheme("rAmo vanaM gacchati", TransliterationScheme::HarvardKyoto).tokenize());
+        let sentence = ConlluSentence::from_vaakya(&vaakyas[0]);
+        assert_eq!(sentence.vaakya(), vaakyas[0]);
+        assert_eq!(to_conllu(&[sentence], TransliterationScheme::HarvardKyoto), "\
+# text = rAmo vanaM gacchati
+1\trAmo\t_\t_\t_\t_\t_\t_\t_\t_
+2\tvanaM\t_\t_\t_\t_\t_\t_\t_\t_
+3\tgacchati\t_\t_\t_\t_\t_\t_\t_\t_
+
+");
+    }
+
+    #[test]
+    fn errors() {
+        let line = |text: &str| from_conllu(text, TransliterationScheme::Iast).unwrap_err();
+        assert_eq!(line("1\trāma\t_\n").kind, ConlluErrorKind::Columns(3));
+        assert_eq!(line("2\trāma\t_\t_\t_\t_\t_\t_\t_\t_\n").kind, ConlluErrorKind::OutOfOrder("2".to_string()));
+        assert_eq!(line("1-2\ttacchrutvā\t_\t_\t_\t_\t_\t_\t_\t_\n1\ttat\t_\t_\t_\t_\t_\t_\t_\t_\n\n").kind, ConlluErrorKind::IncompleteRange);
+    }
+}
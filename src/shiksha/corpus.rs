@@ -0,0 +1,382 @@
+use std::fmt;
+use std::io::{self, Write};
+use crate::shiksha::{Varna, VarnaMap, Akshara, Pada, Vaakya, Danda, Swara, SamaSvara};
+use crate::chandas::{Maatra, Kaala};
+
+/// Compact binary format for lexed corpora.
+///
+/// Every Varna is one byte, its index in `VarnaMap::inventory()`, so a text
+/// takes little more room than its Devanagari. The file is a header followed
+/// by four tables of fixed-size little-endian records, each starting at a
+/// multiple of four bytes, so a memory-mapped file can be read in place and
+/// any Vaakya, Pada or Akshara reached without reading those before it:
+///
+/// | Section  | Record                                                           |
+/// |----------|------------------------------------------------------------------|
+/// | Header   | magic `PSSC`, version u16, reserved u16, inventory size u16,     |
+/// |          | reserved u16, then Vaakya, Pada, Akshara and Varna counts (u32), |
+/// |          | reserved u32: 32 bytes                                           |
+/// | Vaakyas  | first Pada u32, verse number u32 (`u32::MAX` for none),          |
+/// |          | danda u8 (0 none, 1 single, 2 double), 3 bytes padding           |
+/// | Padas    | first Akshara u32                                                |
+/// | Aksharas | first Varna u32, accent u8, Kaalas u8 (1–3), 2 bytes padding     |
+/// | Varnas   | inventory index u8                                               |
+///
+/// A record's items run up to the first item of the next record. The accent
+/// byte holds the Swara in bits 0–1 (0 none, then udaatta, anudaatta,
+/// svarita) and the Sama Svara in bits 2–4 (0 none, then sa … ni). Every
+/// Akshara holds at least one Varna.
+pub const MAGIC: &[u8; 4] = b"PSSC";
+
+/// Version of the binary layout, raised on any incompatible change.
+pub const FORMAT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = 32;
+const VAAKYA_LEN: usize = 12;
+const PADA_LEN: usize = 4;
+const AKSHARA_LEN: usize = 8;
+const NO_NUMBER: u32 = u32::MAX;
+
+/// Why bytes could not be read as a corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorpusError {
+    BadMagic,                                            // Not a corpus file
+    UnsupportedVersion(u16),                             // Written with another layout version
+    InventoryMismatch { expected: usize, found: usize }, // Written against a different Varna inventory
+    Truncated,                                           // Shorter than its header says
+    Corrupt(&'static str),                               // A record of the named table points outside the next, holds nothing it must, or is followed by stray bytes
+}
+
+impl fmt::Display for CorpusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CorpusError::BadMagic => write!(f, "not a corpus file"),
+            CorpusError::UnsupportedVersion(version) => write!(f, "corpus version {} is not supported (expected {})", version, FORMAT_VERSION),
+            CorpusError::InventoryMismatch { expected, found } => write!(f, "corpus was written for {} Varnas, this inventory has {}", found, expected),
+            CorpusError::Truncated => write!(f, "corpus is shorter than its header says"),
+            CorpusError::Corrupt(table) => write!(f, "corpus {} table is corrupt", table),
+        }
+    }
+}
+
+impl std::error::Error for CorpusError {}
+
+/// Collects Vaakyas into the tables of a corpus and writes them out.
+#[derive(Debug, Default)]
+pub struct CorpusWriter {
+    vaakyas: Vec<u8>,
+    padas: Vec<u8>,
+    aksharas: Vec<u8>,
+    varnas: Vec<u8>,
+}
+
+impl CorpusWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a Vaakya to the end of the corpus.
+    pub fn push(&mut self, vaakya: &Vaakya) {
+        self.vaakyas.extend(count(&self.padas, PADA_LEN).to_le_bytes());
+        self.vaakyas.extend(vaakya.number.unwrap_or(NO_NUMBER).to_le_bytes());
+        self.vaakyas.extend([danda_code(vaakya.danda), 0, 0, 0]);
+
+        for pada in &vaakya.padas {
+            self.padas.extend(count(&self.aksharas, AKSHARA_LEN).to_le_bytes());
+            for akshara in &pada.aksharas {
+                self.aksharas.extend((self.varnas.len() as u32).to_le_bytes());
+                self.aksharas.extend([accent_code(akshara), u32::from(akshara.maatra.length) as u8, 0, 0]);
+                self.varnas.extend(akshara.varnas.iter().map(inventory_index));
+            }
+        }
+    }
+
+    /// Writes the header and tables.
+    pub fn write_to<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        out.write_all(&0u16.to_le_bytes())?;
+        out.write_all(&(VarnaMap::inventory().len() as u16).to_le_bytes())?;
+        out.write_all(&0u16.to_le_bytes())?;
+        for table in [(&self.vaakyas, VAAKYA_LEN), (&self.padas, PADA_LEN), (&self.aksharas, AKSHARA_LEN), (&self.varnas, 1)] {
+            out.write_all(&count(table.0, table.1).to_le_bytes())?;
+        }
+        out.write_all(&0u32.to_le_bytes())?;
+
+        for table in [&self.vaakyas, &self.padas, &self.aksharas, &self.varnas] {
+            out.write_all(table)?;
+        }
+        out.flush()
+    }
+
+    /// Returns the corpus as bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.vaakyas.len() + self.padas.len() + self.aksharas.len() + self.varnas.len());
+        self.write_to(&mut bytes).expect("writing to a Vec cannot fail");
+        bytes
+    }
+}
+
+/// Reads a corpus in place from its bytes, which may be a memory-mapped file.
+/// All records are checked when the corpus is opened, so reads never fail.
+#[derive(Debug, Clone, Copy)]
+pub struct CorpusReader<'a> {
+    vaakyas: &'a [u8],
+    padas: &'a [u8],
+    aksharas: &'a [u8],
+    varnas: &'a [u8],
+}
+
+impl<'a> CorpusReader<'a> {
+    /// Checks the header and tables of a corpus.
+    ///
+    /// ```
+    /// use pss::{Lexer, parse};
+    /// use pss::shiksha::{CorpusReader, write_corpus};
+    ///
+    /// let vaakyas = parse(&Lexer::new("रामो राजमणिः सदा विजयते ।").tokenize());
+    /// let mut bytes = Vec::new();
+    /// write_corpus(&vaakyas, &mut bytes)?;
+    ///
+    /// let corpus = CorpusReader::open(&bytes)?;
+    /// assert_eq!(corpus.vaakya(0), Some(vaakyas[0].clone()));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn open(bytes: &'a [u8]) -> Result<Self, CorpusError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(if bytes.starts_with(MAGIC) { CorpusError::Truncated } else { CorpusError::BadMagic });
+        }
+        if &bytes[0..4] != MAGIC {
+            return Err(CorpusError::BadMagic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != FORMAT_VERSION {
+            return Err(CorpusError::UnsupportedVersion(version));
+        }
+        let inventory = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        if inventory != VarnaMap::inventory().len() {
+            return Err(CorpusError::InventoryMismatch { expected: VarnaMap::inventory().len(), found: inventory });
+        }
+
+        let mut offset = HEADER_LEN;
+        let mut tables = [&bytes[..0]; 4];
+        for (i, record_len) in [VAAKYA_LEN, PADA_LEN, AKSHARA_LEN, 1].into_iter().enumerate() {
+            let len = read_u32(bytes, 12 + 4 * i) as usize * record_len;
+            tables[i] = bytes.get(offset..offset + len).ok_or(CorpusError::Truncated)?;
+            offset += len;
+        }
+        if offset != bytes.len() {
+            return Err(CorpusError::Corrupt("Varna"));
+        }
+
+        let reader = CorpusReader { vaakyas: tables[0], padas: tables[1], aksharas: tables[2], varnas: tables[3] };
+        reader.check()?;
+        Ok(reader)
+    }
+
+    /// Checks that every record points into the next table, in order.
+    fn check(&self) -> Result<(), CorpusError> {
+        if !ordered(self.vaakyas, VAAKYA_LEN, self.pada_count()) || self.vaakyas.chunks(VAAKYA_LEN).any(|r| r[8] > 2) {
+            return Err(CorpusError::Corrupt("Vaakya"));
+        }
+        if !ordered(self.padas, PADA_LEN, self.akshara_count()) {
+            return Err(CorpusError::Corrupt("Pada"));
+        }
+        if !ordered(self.aksharas, AKSHARA_LEN, self.varnas.len()) || !filled(self.aksharas, AKSHARA_LEN, self.varnas.len())
+            || self.aksharas.chunks(AKSHARA_LEN).any(|r| !(1..=3).contains(&r[5])) {
+            return Err(CorpusError::Corrupt("Akshara"));
+        }
+        if self.varnas.iter().any(|&i| i as usize >= VarnaMap::inventory().len()) {
+            return Err(CorpusError::Corrupt("Varna"));
+        }
+        Ok(())
+    }
+
+    pub fn vaakya_count(&self) -> usize {
+        count(self.vaakyas, VAAKYA_LEN) as usize
+    }
+
+    pub fn pada_count(&self) -> usize {
+        count(self.padas, PADA_LEN) as usize
+    }
+
+    pub fn akshara_count(&self) -> usize {
+        count(self.aksharas, AKSHARA_LEN) as usize
+    }
+
+    /// Reads the `index`-th Vaakya of the corpus.
+    pub fn vaakya(&self, index: usize) -> Option<Vaakya> {
+        let (start, end) = span(self.vaakyas, VAAKYA_LEN, index, self.pada_count())?;
+        let record = &self.vaakyas[index * VAAKYA_LEN..];
+
+        let mut vaakya = Vaakya::new((start..end).filter_map(|i| self.pada(i)).collect());
+        vaakya.number = Some(read_u32(record, 4)).filter(|&n| n != NO_NUMBER);
+        vaakya.danda = match record[8] {
+            1 => Some(Danda::Single),
+            2 => Some(Danda::Double),
+            _ => None,
+        };
+        Some(vaakya)
+    }
+
+    /// Reads the `index`-th Pada of the corpus, counting across Vaakyas.
+    pub fn pada(&self, index: usize) -> Option<Pada> {
+        let (start, end) = span(self.padas, PADA_LEN, index, self.akshara_count())?;
+        Some(Pada::new((start..end).filter_map(|i| self.akshara(i)).collect()))
+    }
+
+    /// Reads the `index`-th Akshara of the corpus, counting across Padas.
+    pub fn akshara(&self, index: usize) -> Option<Akshara> {
+        let (start, end) = span(self.aksharas, AKSHARA_LEN, index, self.varnas.len())?;
+        let record = &self.aksharas[index * AKSHARA_LEN..];
+        let (swara, sama_svara) = accents(record[4]);
+
+        let varnas = self.varnas[start..end].iter().map(|&i| VarnaMap::inventory()[i as usize]).collect();
+
+        // Records hold at least one Varna, checked on opening, and Varnas of the inventory always form an Akshara
+        let mut akshara = Akshara::new(varnas).ok()?;
+        akshara.swara = swara;
+        akshara.sama_svara = sama_svara;
+        akshara.maatra = Maatra::from(match record[5] {
+            1 => Kaala::One,
+            2 => Kaala::Two,
+            _ => Kaala::Three,
+        });
+        Some(akshara)
+    }
+
+    /// Reads every Vaakya in order.
+    pub fn vaakyas(&self) -> impl Iterator<Item = Vaakya> + '_ {
+        (0..self.vaakya_count()).filter_map(|i| self.vaakya(i))
+    }
+}
+
+/// Writes Vaakyas as a corpus.
+pub fn write_corpus<W: Write>(vaakyas: &[Vaakya], out: W) -> io::Result<()> {
+    let mut writer = CorpusWriter::new();
+    for vaakya in vaakyas {
+        writer.push(vaakya);
+    }
+    writer.write_to(out)
+}
+
+fn count(table: &[u8], record_len: usize) -> u32 {
+    (table.len() / record_len) as u32
+}
+
+/// Returns true if the records' first items start at 0, never decrease and stay within `items`.
+fn ordered(table: &[u8], record_len: usize, items: usize) -> bool {
+    let starts: Vec<usize> = table.chunks(record_len).map(|r| read_u32(r, 0) as usize).collect();
+    starts.first().is_none_or(|&first| first == 0)
+        && starts.windows(2).all(|w| w[0] <= w[1]) && starts.last().is_none_or(|&last| last <= items)
+}
+
+/// Returns true if every record has at least one of the `items`.
+fn filled(table: &[u8], record_len: usize, items: usize) -> bool {
+    let starts: Vec<usize> = table.chunks(record_len).map(|r| read_u32(r, 0) as usize).chain([items]).collect();
+    starts.windows(2).all(|w| w[0] < w[1])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+/// Returns the range of items of the `index`-th record, which ends where the next record starts.
+fn span(table: &[u8], record_len: usize, index: usize, items: usize) -> Option<(usize, usize)> {
+    let start = read_u32(table.get(index * record_len..(index + 1) * record_len)?, 0) as usize;
+    let end = table.get((index + 1) * record_len..).filter(|r| !r.is_empty()).map_or(items, |r| read_u32(r, 0) as usize);
+    Some((start, end))
+}
+
+fn inventory_index(varna: &Varna) -> u8 {
+    VarnaMap::inventory().iter().position(|v| v.hk == varna.hk).expect("every Varna comes from the inventory") as u8
+}
+
+fn danda_code(danda: Option<Danda>) -> u8 {
+    match danda {
+        None => 0,
+        Some(Danda::Single) => 1,
+        Some(Danda::Double) => 2,
+    }
+}
+
+fn accent_code(akshara: &Akshara) -> u8 {
+    let swara = akshara.swara.map_or(0, |s| Swara::ALL.iter().position(|&w| w == s).unwrap_or(0) + 1);
+    let sama_svara = akshara.sama_svara.map_or(0, |s| SamaSvara::ALL.iter().position(|&w| w == s).unwrap_or(0) + 1);
+    (swara | sama_svara << 2) as u8
+}
+
+fn accents(code: u8) -> (Option<Swara>, Option<SamaSvara>) {
+    let swara = (code & 0b11) as usize;
+    let sama_svara = (code >> 2 & 0b111) as usize;
+    (swara.checked_sub(1).map(|i| Swara::ALL[i]), sama_svara.checked_sub(1).map(|i| SamaSvara::ALL[i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{self, Lexer};
+    use crate::shiksha::TransliterationScheme;
+
+    #[test]
+    fn corpus_round_trips_with_random_access() {
+        let text = "धर्मक्षेत्रे कुरुक्षेत्रे समवेता युयुत्सवः ।\nमामकाः पाण्डवाश्चैव किमकुर्वत सञ्जय ॥ १ ॥\n\nओ३म् नमः";
+        let vaakyas = niruktam::parse(&Lexer::with_scheme(text, TransliterationScheme::Devanagari).tokenize());
+
+        let mut bytes = Vec::new();
+        write_corpus(&vaakyas, &mut bytes).unwrap();
+        let corpus = CorpusReader::open(&bytes).unwrap();
+
+        assert_eq!(corpus.vaakyas().collect::<Vec<_>>(), vaakyas);
+        assert_eq!(corpus.vaakya(1), Some(vaakyas[1].clone()));
+        assert_eq!(corpus.pada(4), Some(vaakyas[1].padas[0].clone()));
+        assert_eq!(corpus.vaakya(3), None);
+        #[cfg(feature = "serialization")]
+        assert!(bytes.len() * 10 < crate::shiksha::to_json(&vaakyas).len());
+    }
+
+    #[test]
+    fn damaged_corpora_are_rejected() {
+        let vaakyas = niruktam::parse(&Lexer::with_scheme("rAmaH", TransliterationScheme::HarvardKyoto).tokenize());
+        let bytes = CorpusWriter::new().to_bytes();
+        assert!(CorpusReader::open(&bytes).is_ok());
+
+        let mut writer = CorpusWriter::new();
+        writer.push(&vaakyas[0]);
+        let mut bytes = writer.to_bytes();
+        assert_eq!(CorpusReader::open(&bytes[..bytes.len() - 1]).unwrap_err(), CorpusError::Truncated);
+        *bytes.last_mut().unwrap() = 255;
+        assert_eq!(CorpusReader::open(&bytes).unwrap_err(), CorpusError::Corrupt("Varna"));
+        assert_eq!(CorpusReader::open(b"JSON").unwrap_err(), CorpusError::BadMagic);
+    }
+
+    #[test]
+    fn stray_bytes_are_rejected() {
+        let vaakyas = niruktam::parse(&Lexer::with_scheme("rAmaH", TransliterationScheme::HarvardKyoto).tokenize());
+        let mut writer = CorpusWriter::new();
+        writer.push(&vaakyas[0]);
+        let bytes = writer.to_bytes();
+
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(CorpusReader::open(&longer).unwrap_err(), CorpusError::Corrupt("Varna"));
+
+        // Start the only Vaakya at its second Pada, skipping the first
+        let mut skipping = bytes;
+        skipping[HEADER_LEN..HEADER_LEN + 4].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(CorpusReader::open(&skipping).unwrap_err(), CorpusError::Corrupt("Vaakya"));
+    }
+
+    #[test]
+    fn empty_aksharas_are_rejected() {
+        let vaakyas = niruktam::parse(&Lexer::with_scheme("rAmaH", TransliterationScheme::HarvardKyoto).tokenize());
+        let mut writer = CorpusWriter::new();
+        writer.push(&vaakyas[0]);
+        let mut bytes = writer.to_bytes();
+
+        // Point the second Akshara at the first Varna too, leaving the first with none
+        let second = HEADER_LEN + VAAKYA_LEN + PADA_LEN + AKSHARA_LEN;
+        bytes[second..second + 4].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(CorpusReader::open(&bytes).unwrap_err(), CorpusError::Corrupt("Akshara"));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::shiksha::{lipi, Akshara, Danda, Swara, TransliterationScheme, Vaakya};
+use crate::chandas::Kaala;
+
+/// Options for exporting annotated text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ExportOptions {
+    pub script: TransliterationScheme,          // Script of the text
+    pub reading: Option<TransliterationScheme>, // Transliteration shown with each Akshara, if any
+    pub accents: bool,                          // Write the Swaras as accent marks and HTML classes
+    pub scansion: bool,                         // Show the weight of each syllable
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            script: TransliterationScheme::Devanagari,
+            reading: Some(TransliterationScheme::Iast),
+            accents: false,
+            scansion: true,
+        }
+    }
+}
+
+/// An Akshara annotated for publishing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedAkshara {
+    pub text: String,          // The Akshara in the script of the edition
+    pub reading: String,       // Its transliteration, empty if none was asked for
+    pub swara: Option<Swara>,
+    pub weight: Option<Kaala>, // Weight of the syllable whose vowel the Akshara holds
+}
+
+/// A Vaakya annotated for publishing, Pada by Pada. HTML and LaTeX are both
+/// written from this model, so every edition shows the same analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedVaakya {
+    pub padas: Vec<Vec<AnnotatedAkshara>>,
+    pub danda: Option<Danda>,
+    pub number: Option<u32>,
+}
+
+/// Annotates a Vaakya. With the `chandas` feature the weights come from the
+/// scansion of the whole line, so a short vowel closed by the first consonant
+/// of the next Akshara is guru; without it they are the weights of the Aksharas.
+pub fn annotate(vaakya: &Vaakya, options: &ExportOptions) -> AnnotatedVaakya {
+    let mut weights = weights(vaakya).into_iter();
+    let padas = vaakya.padas.iter().map(|pada| {
+        pada.aksharas.iter().map(|akshara| AnnotatedAkshara {
+            text: write(akshara, options.script, options.accents),
+            reading: options.reading.map_or(String::new(), |scheme| write(akshara, scheme, options.accents)),
+            swara: akshara.swara,
+            weight: weights.next().flatten(),
+        }).collect()
+    }).collect();
+
+    AnnotatedVaakya { padas, danda: vaakya.danda, number: vaakya.number }
+}
+
+/// Writes Vaakyas as HTML: a `div` for each Vaakya, a `span` for each Pada
+/// and a `ruby` for each Akshara with its reading as the annotation. Each
+/// `ruby` has the class `akshara`, with accents its Swara (`udaatta`,
+/// `anudaatta`, `svarita`) and with scansion its weight (`laghu`, `guru`,
+/// `pluta`).
+pub fn to_html(vaakyas: &[Vaakya], options: &ExportOptions) -> String {
+    let mut html = String::new();
+
+    for vaakya in vaakyas {
+        let annotated = annotate(vaakya, options);
+        html.push_str("<div class=\"vaakya\" lang=\"sa\">");
+
+        let padas: Vec<String> = annotated.padas.iter().map(|pada| {
+            let aksharas: String = pada.iter().map(|akshara| {
+                let mut classes = vec!["akshara"];
+                if options.accents {
+                    classes.extend(akshara.swara.map(Swara::name));
+                }
+                if options.scansion {
+                    classes.extend(akshara.weight.map(weight_name));
+                }
+
+                let reading = if options.reading.is_some() { format!("<rt>{}</rt>", escape_html(&akshara.reading)) } else { String::new() };
+                format!("<ruby class=\"{}\">{}{}</ruby>", classes.join(" "), escape_html(&akshara.text), reading)
+            }).collect();
+            format!("<span class=\"pada\">{}</span>", aksharas)
+        }).collect();
+        html.push_str(&padas.join(" "));
+
+        if let Some(danda) = end_mark(annotated.danda, annotated.number, options.script) {
+            html.push_str(&format!(" <span class=\"danda\">{}</span>", escape_html(&danda)));
+        }
+        html.push_str("</div>\n");
+    }
+
+    html
+}
+
+/// Writes Vaakyas as LaTeX: a `tabular` for each Vaakya with a column for
+/// each Akshara, holding the text, the reading and the scansion in rows.
+/// Devanagari is wrapped in `\textsanskrit` for polyglossia; scansion uses
+/// `$\cup$` for laghu and `$-$` for guru, with `$-^{3}$` for pluta.
+pub fn to_latex(vaakyas: &[Vaakya], options: &ExportOptions) -> String {
+    let devanagari = matches!(options.script, TransliterationScheme::Devanagari | TransliterationScheme::Unicode);
+    let mut latex = String::new();
+
+    for vaakya in vaakyas {
+        let annotated = annotate(vaakya, options);
+
+        // One column per Akshara, with an empty column between Padas
+        let mut text = Vec::new();
+        let mut reading = Vec::new();
+        let mut scansion = Vec::new();
+        for (i, pada) in annotated.padas.iter().enumerate() {
+            if i > 0 {
+                text.push(String::new());
+                reading.push(String::new());
+                scansion.push(String::new());
+            }
+            for akshara in pada {
+                let written = escape_latex(&akshara.text);
+                text.push(if devanagari { format!("\\textsanskrit{{{}}}", written) } else { written });
+                reading.push(escape_latex(&akshara.reading));
+                scansion.push(akshara.weight.map_or("", weight_symbol).to_string());
+            }
+        }
+        if let Some(danda) = end_mark(annotated.danda, annotated.number, options.script) {
+            let danda = escape_latex(&danda);
+            text.push(if devanagari { format!("\\textsanskrit{{{}}}", danda) } else { danda });
+            reading.push(String::new());
+            scansion.push(String::new());
+        }
+
+        latex.push_str(&format!("\\begin{{tabular}}{{{}}}\n", "c".repeat(text.len())));
+        latex.push_str(&format!("{} \\\\\n", text.join(" & ")));
+        if options.reading.is_some() {
+            latex.push_str(&format!("{} \\\\\n", reading.join(" & ")));
+        }
+        if options.scansion {
+            latex.push_str(&format!("{} \\\\\n", scansion.join(" & ")));
+        }
+        latex.push_str("\\end{tabular}\n");
+    }
+
+    latex
+}
+
+/// The weight of each Akshara of the Vaakya that holds a vowel.
+#[cfg(feature = "chandas")]
+fn weights(vaakya: &Vaakya) -> Vec<Option<Kaala>> {
+    use crate::chandas::{syllabify, VibhagaOptions};
+
+    // Aksharas end at their vowel, so each holds the vowel of at most one syllable
+    let mut syllables = syllabify(vaakya, &VibhagaOptions::default()).into_iter();
+    vaakya.padas.iter().flat_map(|p| &p.aksharas)
+        .map(|a| if has_vowel(a) { syllables.next().map(|s| s.maatra.length) } else { None })
+        .collect()
+}
+
+#[cfg(not(feature = "chandas"))]
+fn weights(vaakya: &Vaakya) -> Vec<Option<Kaala>> {
+    vaakya.padas.iter().flat_map(|p| &p.aksharas)
+        .map(|a| has_vowel(a).then_some(a.maatra.length))
+        .collect()
+}
+
+fn has_vowel(akshara: &Akshara) -> bool {
+    akshara.varnas.iter().any(|v| v.is_vowel())
+}
+
+/// Writes an Akshara in a scheme, with its Swara marked if `accents` is set.
+/// Devanagari follows the Rigveda: anudaatta is a stroke below (॒), svarita
+/// a stroke above (॑), and udaatta is unmarked. Romanizations mark udaatta
+/// with an acute and svarita with a grave accent.
+fn write(akshara: &Akshara, scheme: TransliterationScheme, accents: bool) -> String {
+    let vowel = akshara.varnas.iter().position(|v| v.is_vowel());
+    let (Some(vowel), Some(swara), true) = (vowel, akshara.swara, accents) else {
+        return lipi::render(&akshara.varnas, scheme);
+    };
+
+    let (through_vowel, rest) = akshara.varnas.split_at(vowel + 1);
+    let mut written = if matches!(scheme, TransliterationScheme::Devanagari | TransliterationScheme::Unicode) {
+        let mark = match swara {
+            Swara::Udaatta => "",
+            Swara::Anudaatta => "\u{0952}",
+            Swara::Svarita => "\u{0951}",
+        };
+        lipi::render(through_vowel, scheme) + mark
+    } else {
+        let mark = match swara {
+            Swara::Udaatta => "\u{0301}",
+            Swara::Anudaatta => "",
+            Swara::Svarita => "\u{0300}",
+        };
+        // The accent sits on the first letter of the vowel (`ái`, not `aí`)
+        let spelled = lipi::render(&through_vowel[vowel..], scheme);
+        let first = spelled.chars().next().map_or(0, char::len_utf8);
+        lipi::render(&through_vowel[..vowel], scheme) + &spelled[..first] + mark + &spelled[first..]
+    };
+    written.push_str(&lipi::render(rest, scheme));
+    written
+}
+
+/// The danda closing a Vaakya and its verse number, in the script of the text.
+pub(crate) fn end_mark(danda: Option<Danda>, number: Option<u32>, script: TransliterationScheme) -> Option<String> {
+    let devanagari = matches!(script, TransliterationScheme::Devanagari | TransliterationScheme::Unicode);
+    let danda = match (danda?, devanagari) {
+        (Danda::Single, true) => "।",
+        (Danda::Double, true) => "॥",
+        (Danda::Single, false) => "|",
+        (Danda::Double, false) => "||",
+    };
+
+    Some(match number {
+        Some(number) if devanagari => {
+            let digits: String = number.to_string().chars().map(|d| char::from_u32('०' as u32 + d.to_digit(10).unwrap_or(0)).unwrap_or(d)).collect();
+            format!("{} {} {}", danda, digits, danda)
+        }
+        Some(number) => format!("{} {} {}", danda, number, danda),
+        None => danda.to_string(),
+    })
+}
+
+fn weight_name(kaala: Kaala) -> &'static str {
+    match kaala {
+        Kaala::One => "laghu",
+        Kaala::Two => "guru",
+        Kaala::Three => "pluta",
+    }
+}
+
+fn weight_symbol(kaala: Kaala) -> &'static str {
+    match kaala {
+        Kaala::One => "$\\cup$",
+        Kaala::Two => "$-$",
+        Kaala::Three => "$-^{3}$",
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Escapes the characters LaTeX treats specially, which the romanizations
+/// use for some Varnas (`~` in Velthuis and ITRANS, `^` in ITRANS).
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{self, Lexer};
+
+    fn vaakyas(text: &str, scheme: TransliterationScheme) -> Vec<Vaakya> {
+        niruktam::parse(&Lexer::with_scheme(text, scheme).tokenize())
+    }
+
+    #[test]
+    #[cfg(feature = "chandas")]
+    fn html_ruby_and_classes() {
+        let html = to_html(&vaakyas("rAmas tu ||", TransliterationScheme::HarvardKyoto), &ExportOptions::default());
+        assert_eq!(html, "<div class=\"vaakya\" lang=\"sa\">\
+            <span class=\"pada\"><ruby class=\"akshara guru\">रा<rt>rā</rt></ruby>\
+            <ruby class=\"akshara guru\">म<rt>ma</rt></ruby><ruby class=\"akshara\">स्<rt>s</rt></ruby></span> \
+            <span class=\"pada\"><ruby class=\"akshara laghu\">तु<rt>tu</rt></ruby></span> \
+            <span class=\"danda\">॥</span></div>\n");
+    }
+
+    #[test]
+    #[cfg(feature = "chandas")]
+    fn latex_rows() {
+        let options = ExportOptions { script: TransliterationScheme::Iast, reading: None, accents: true, scansion: true };
+        assert_eq!(to_latex(&vaakyas("hitam | 1 |", TransliterationScheme::HarvardKyoto), &options), "\
+\\begin{tabular}{cccc}
+hi & ta & m & | 1 | \\\\
+$\\cup$ & $-$ &  &  \\\\
+\\end{tabular}
+");
+    }
+
+    #[test]
+    fn accents() {
+        let options = ExportOptions { accents: true, ..ExportOptions::default() };
+        let annotated = annotate(&vaakyas("अ॒ग्निम्", TransliterationScheme::Devanagari)[0], &options);
+        let texts: Vec<(&str, &str)> = annotated.padas[0].iter().map(|a| (a.text.as_str(), a.reading.as_str())).collect();
+        assert_eq!(texts, [("अ॒", "a"), ("ग्नि", "gni\u{301}"), ("म्", "m")]);
+    }
+
+    #[test]
+    fn html_svara_classes_follow_accents() {
+        let text = vaakyas("अ॒ग्निम्", TransliterationScheme::Devanagari);
+        let plain = ExportOptions { reading: None, scansion: false, ..ExportOptions::default() };
+        assert_eq!(to_html(&text, &plain), "<div class=\"vaakya\" lang=\"sa\"><span class=\"pada\">\
+            <ruby class=\"akshara\">अ</ruby><ruby class=\"akshara\">ग्नि</ruby><ruby class=\"akshara\">म्</ruby>\
+            </span></div>\n");
+
+        let accented = ExportOptions { accents: true, ..plain };
+        assert_eq!(to_html(&text, &accented), "<div class=\"vaakya\" lang=\"sa\"><span class=\"pada\">\
+            <ruby class=\"akshara anudaatta\">अ॒</ruby><ruby class=\"akshara udaatta\">ग्नि</ruby><ruby class=\"akshara\">म्</ruby>\
+            </span></div>\n");
+    }
+
+    #[test]
+    fn latex_escapes() {
+        assert_eq!(escape_latex("~n_a{b}"), "\\textasciitilde{}n\\_a\\{b\\}");
+    }
+}
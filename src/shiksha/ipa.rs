@@ -0,0 +1,233 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::shiksha::{Varna, Swara, Matra, ParseNameError};
+use crate::shiksha::varna::{Sthanani, Prayatna};
+
+/// Regional pronunciation of Sanskrit, applied on top of the standard values.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum Pronunciation {
+    #[default]
+    Standard,    // Values reconstructed from the Shiksha descriptions
+    Hindi,       // North Indian: jña as gy, ś and ṣ merged, short a as ə
+    Marathi,     // Maharashtrian: jña as dny, ṛ as ru
+    Madhyandina, // Madhyandina Yajurveda recitation: ṣa as kha
+}
+
+impl Pronunciation {
+    pub const ALL: [Pronunciation; 4] = [
+        Pronunciation::Standard,
+        Pronunciation::Hindi,
+        Pronunciation::Marathi,
+        Pronunciation::Madhyandina,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pronunciation::Standard => "standard",
+            Pronunciation::Hindi => "hindi",
+            Pronunciation::Marathi => "marathi",
+            Pronunciation::Madhyandina => "madhyandina",
+        }
+    }
+}
+
+impl fmt::Display for Pronunciation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Pronunciation {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pronunciation::ALL.into_iter().find(|p| p.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("pronunciation", s))
+    }
+}
+
+/// Pronunciations that replace the standard value of a sequence of Varnas (by HK name).
+const OVERRIDES: &[(Pronunciation, &[&str], &str)] = &[
+    (Pronunciation::Hindi, &["j", "J"], "ɡj"),
+    (Pronunciation::Hindi, &["a"], "ə"),
+    (Pronunciation::Hindi, &["z"], "ʃ"),
+    (Pronunciation::Hindi, &["S"], "ʃ"),
+    (Pronunciation::Hindi, &["R"], "rɪ"),
+    (Pronunciation::Hindi, &["ai"], "ɛː"),
+    (Pronunciation::Hindi, &["au"], "ɔː"),
+    (Pronunciation::Marathi, &["j", "J"], "d̪ɲ"),
+    (Pronunciation::Marathi, &["R"], "ru"),
+    (Pronunciation::Madhyandina, &["S"], "kʰ"),
+];
+
+/// Options for IPA transcription.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct IpaOptions {
+    pub pronunciation: Pronunciation,
+    pub accents: bool, // Mark the Swara of each syllable with a tone diacritic
+}
+
+/// Transcribes a single Varna in the standard pronunciation.
+pub fn varna(varna: &Varna) -> String {
+    if varna.is_vowel() {
+        vowel(varna)
+    } else {
+        consonant(varna)
+    }
+}
+
+/// Transcribes the Varnas of one syllable, marking the accent on its vowel.
+pub fn syllable(varnas: &[Varna], swara: Option<Swara>, options: &IpaOptions) -> String {
+    let mut output = String::new();
+    let mut i = 0;
+
+    while i < varnas.len() {
+        let (len, sound) = OVERRIDES.iter()
+            .filter(|(p, seq, _)| *p == options.pronunciation && varnas[i..].iter().map(|v| v.hk).take(seq.len()).eq(seq.iter().copied()))
+            .max_by_key(|(_, seq, _)| seq.len())
+            .map_or_else(|| (1, varna(&varnas[i])), |(_, seq, sound)| (seq.len(), sound.to_string()));
+
+        let has_vowel = varnas[i..i + len].iter().any(|v| v.is_vowel());
+        match swara.filter(|_| options.accents && has_vowel) {
+            Some(swara) => {
+                // The tone diacritic goes on the first symbol of the vowel
+                let mut chars = sound.chars();
+                output.extend(chars.next());
+                output.push(tone_mark(swara));
+                output.extend(chars);
+            }
+            None => output.push_str(&sound),
+        }
+        i += len;
+    }
+
+    output
+}
+
+fn tone_mark(swara: Swara) -> char {
+    match swara {
+        Swara::Udaatta => '\u{0301}',   // High
+        Swara::Anudaatta => '\u{0300}', // Low
+        Swara::Svarita => '\u{0302}',   // Falling
+    }
+}
+
+/// Vowel quality follows the Sthanani and length follows the Matra.
+fn vowel(varna: &Varna) -> String {
+    let length = match varna.matra {
+        Some(Matra::Diirgha) => "ː",
+        Some(Matra::Pluta) => "ːː",
+        _ => "",
+    };
+
+    // Sandhyaksharas (e, ai, o, au) are listed under one Sthanani but have two
+    let quality = match varna.hk.trim_end_matches('3') {
+        "e" => "e",
+        "o" => "o",
+        // A diphthong is long in itself; only the pluta form adds length
+        "ai" if varna.matra == Some(Matra::Pluta) => return "ɐːi̯".to_string(),
+        "au" if varna.matra == Some(Matra::Pluta) => return "ɐːu̯".to_string(),
+        "ai" => return "ɐi̯".to_string(),
+        "au" => return "ɐu̯".to_string(),
+        _ => match varna.sthanani {
+            Some(Sthanani::Kantha) if varna.matra == Some(Matra::Hrasva) => "ɐ",
+            Some(Sthanani::Kantha) => "a",
+            Some(Sthanani::Talu) => "i",
+            Some(Sthanani::Oshtha) => "u",
+            Some(Sthanani::Murdha) => "r̩",
+            Some(Sthanani::Danta) => "l̩",
+            _ => "ə",
+        },
+    };
+
+    format!("{quality}{length}")
+}
+
+/// Consonants combine the Sthanani (place) with the Prayatna (manner and
+/// aspiration); voicing comes from the Varna's ghosha status.
+fn consonant(varna: &Varna) -> String {
+    let voiced = varna.is_ghosha();
+
+    let base = match (varna.sthanani, varna.prayatna) {
+        (_, Some(Prayatna::Anunasika)) => "ŋ",
+        (Some(place), Some(Prayatna::Nasika)) => match place {
+            Sthanani::Kantha => "ŋ",
+            Sthanani::Talu => "ɲ",
+            Sthanani::Murdha => "ɳ",
+            Sthanani::Danta => "n̪",
+            _ => "m",
+        },
+        (Some(place), Some(Prayatna::IshatSparsha)) => match (place, voiced) {
+            (Sthanani::Jihvamula, _) => "x",
+            (Sthanani::Kantha, true) => "ɦ",
+            (Sthanani::Kantha, false) => "h",
+            (Sthanani::Talu, true) => "j",
+            (Sthanani::Talu, false) => "ɕ",
+            (Sthanani::Murdha, true) => "ɾ",
+            (Sthanani::Murdha, false) => "ʂ",
+            (Sthanani::Danta, true) => "l",
+            (Sthanani::Danta, false) => "s",
+            (_, true) => "ʋ",
+            (_, false) => "ɸ",
+        },
+        (Some(place), _) => match (place, voiced) {
+            (Sthanani::Kantha, true) => "ɡ",
+            (Sthanani::Kantha, false) => "k",
+            (Sthanani::Talu, true) => "ɟ",
+            (Sthanani::Talu, false) => "c",
+            (Sthanani::Murdha, true) => "ɖ",
+            (Sthanani::Murdha, false) => "ʈ",
+            (Sthanani::Danta, true) => "d̪",
+            (Sthanani::Danta, false) => "t̪",
+            (_, true) => "b",
+            (_, false) => "p",
+        },
+        (None, _) => "",
+    };
+
+    let aspiration = match (varna.prayatna, voiced) {
+        (Some(Prayatna::Mahaprana), true) => "ʱ",
+        (Some(Prayatna::Mahaprana), false) => "ʰ",
+        _ => "",
+    };
+
+    format!("{base}{aspiration}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{self, Lexer};
+    use crate::shiksha::{Pada, TransliterationScheme};
+
+    fn pada(text: &str, scheme: TransliterationScheme) -> Pada {
+        niruktam::parse(&Lexer::with_scheme(text, scheme).tokenize())[0].padas[0].clone()
+    }
+
+    #[test]
+    fn accents_from_marks() {
+        let options = IpaOptions { accents: true, ..IpaOptions::default() };
+        assert_eq!(pada("अ॒ग्निम्", TransliterationScheme::Devanagari).ipa(&options), "ɐ\u{300}.ɡn̪i\u{301}.m");
+        assert_eq!(pada("ऋ॒त्विज॑म्", TransliterationScheme::Devanagari).ipa(&options), "r\u{300}̩.t̪ʋi\u{301}.ɟɐ\u{302}.m");
+        // Unaccented text has no Swara to mark
+        assert_eq!(pada("agnim", TransliterationScheme::HarvardKyoto).ipa(&options), "ɐ.ɡn̪i.m");
+    }
+
+    #[test]
+    fn pronunciations() {
+        let hindi = IpaOptions { pronunciation: Pronunciation::Hindi, ..IpaOptions::default() };
+        let madhyandina = IpaOptions { pronunciation: Pronunciation::Madhyandina, ..IpaOptions::default() };
+        let yajna = pada("yajJa", TransliterationScheme::HarvardKyoto);
+        assert_eq!(yajna.ipa(&IpaOptions::default()), "jɐ.ɟɲɐ");
+        assert_eq!(yajna.ipa(&hindi), "jə.ɡjə");
+        assert_eq!(pada("puruSa", TransliterationScheme::HarvardKyoto).ipa(&madhyandina), "pu.ɾu.kʰɐ");
+    }
+
+    #[test]
+    fn pronunciation_names() {
+        for pronunciation in Pronunciation::ALL {
+            assert_eq!(pronunciation.name().parse(), Ok(pronunciation));
+        }
+        assert_eq!("Hindi".parse(), Ok(Pronunciation::Hindi));
+        assert!("bengali".parse::<Pronunciation>().is_err());
+    }
+}
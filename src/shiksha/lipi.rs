@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use crate::shiksha::{Varna, VarnaMap, Swara, TransliterationScheme};
+
+/// Spellings of each Varna in the romanized schemes, keyed by Harvard-Kyoto:
+/// (HK, IAST, SLP1, ITRANS, Velthuis, Devanagari vowel sign)
+const SPELLINGS: &[(&str, &str, &str, &str, &str, &str)] = &[
+    // Swaras (Vowels)
+    ("a", "a", "a", "a", "a", ""),
+    ("A", "ā", "A", "A", "aa", "\u{093E}"),
+    ("i", "i", "i", "i", "i", "\u{093F}"),
+    ("I", "ī", "I", "I", "ii", "\u{0940}"),
+    ("u", "u", "u", "u", "u", "\u{0941}"),
+    ("U", "ū", "U", "U", "uu", "\u{0942}"),
+    ("R", "ṛ", "f", "RRi", ".r", "\u{0943}"),
+    ("RR", "ṝ", "F", "RRI", ".rr", "\u{0944}"),
+    ("lR", "ḷ", "x", "LLi", ".l", "\u{0962}"),
+    ("lRR", "ḹ", "X", "LLI", ".ll", "\u{0963}"),
+    ("e", "e", "e", "e", "e", "\u{0947}"),
+    ("ai", "ai", "E", "ai", "ai", "\u{0948}"),
+    ("o", "o", "o", "o", "o", "\u{094B}"),
+    ("au", "au", "O", "au", "au", "\u{094C}"),
+
+    // Pluta vowels, read by the lexer from a 3 after the vowel
+    ("a3", "a3", "a3", "a3", "a3", "\u{0969}"),
+    ("i3", "i3", "i3", "i3", "i3", "\u{093F}\u{0969}"),
+    ("u3", "u3", "u3", "u3", "u3", "\u{0941}\u{0969}"),
+    ("R3", "ṛ3", "f3", "RRi3", ".r3", "\u{0943}\u{0969}"),
+    ("lR3", "ḷ3", "x3", "LLi3", ".l3", "\u{0962}\u{0969}"),
+    ("e3", "e3", "e3", "e3", "e3", "\u{0947}\u{0969}"),
+    ("ai3", "ai3", "E3", "ai3", "ai3", "\u{0948}\u{0969}"),
+    ("o3", "o3", "o3", "o3", "o3", "\u{094B}\u{0969}"),
+    ("au3", "au3", "O3", "au3", "au3", "\u{094C}\u{0969}"),
+
+    // Vyanjanas (Consonants)
+    ("k", "k", "k", "k", "k", ""),
+    ("kh", "kh", "K", "kh", "kh", ""),
+    ("g", "g", "g", "g", "g", ""),
+    ("gh", "gh", "G", "gh", "gh", ""),
+    ("G", "ṅ", "N", "~N", "\"n", ""),
+    ("c", "c", "c", "ch", "c", ""),
+    ("ch", "ch", "C", "Ch", "ch", ""),
+    ("j", "j", "j", "j", "j", ""),
+    ("jh", "jh", "J", "jh", "jh", ""),
+    ("J", "ñ", "Y", "~n", "~n", ""),
+    ("T", "ṭ", "w", "T", ".t", ""),
+    ("Th", "ṭh", "W", "Th", ".th", ""),
+    ("D", "ḍ", "q", "D", ".d", ""),
+    ("Dh", "ḍh", "Q", "Dh", ".dh", ""),
+    ("N", "ṇ", "R", "N", ".n", ""),
+    ("t", "t", "t", "t", "t", ""),
+    ("th", "th", "T", "th", "th", ""),
+    ("d", "d", "d", "d", "d", ""),
+    ("dh", "dh", "D", "dh", "dh", ""),
+    ("n", "n", "n", "n", "n", ""),
+    ("p", "p", "p", "p", "p", ""),
+    ("ph", "ph", "P", "ph", "ph", ""),
+    ("b", "b", "b", "b", "b", ""),
+    ("bh", "bh", "B", "bh", "bh", ""),
+    ("m", "m", "m", "m", "m", ""),
+    ("y", "y", "y", "y", "y", ""),
+    ("r", "r", "r", "r", "r", ""),
+    ("l", "l", "l", "l", "l", ""),
+    ("v", "v", "v", "v", "v", ""),
+    ("z", "ś", "S", "sh", "\"s", ""),
+    ("S", "ṣ", "z", "Sh", ".s", ""),
+    ("s", "s", "s", "s", "s", ""),
+    ("h", "h", "h", "h", "h", ""),
+
+    // Ayogavahas
+    ("M", "ṃ", "M", "M", ".m", ""),
+    ("H", "ḥ", "H", "H", ".h", ""),
+    ("Z", "ẖ", "Z", "Z", "Z", ""),
+    ("V", "ḫ", "V", "V", "V", ""),
+];
+
+/// Additional input spellings accepted besides the canonical ones.
+/// A value with two HK names separated by a space reads as a conjunct.
+const ALTERNATES: &[(TransliterationScheme, &str, &str)] = &[
+    // Spellings of the first Varna inventory, before it was keyed by Harvard-Kyoto.
+    // Its nga and nya are not kept: ny and ng are ordinary clusters (anya, saGga).
+    (TransliterationScheme::HarvardKyoto, "aa", "A"),
+    (TransliterationScheme::HarvardKyoto, "ii", "I"),
+    (TransliterationScheme::HarvardKyoto, "uu", "U"),
+    (TransliterationScheme::HarvardKyoto, "sh", "z"),
+    (TransliterationScheme::HarvardKyoto, "Sh", "S"),
+    (TransliterationScheme::Iast, "ṁ", "M"),
+    (TransliterationScheme::Iast, "r̥", "R"),
+    (TransliterationScheme::Iast, "r̥̄", "RR"),
+    (TransliterationScheme::Iast, "l̥", "lR"),
+    (TransliterationScheme::Iast, "l̥̄", "lRR"),
+    (TransliterationScheme::Itrans, "aa", "A"),
+    (TransliterationScheme::Itrans, "ii", "I"),
+    (TransliterationScheme::Itrans, "uu", "U"),
+    (TransliterationScheme::Itrans, "R^i", "R"),
+    (TransliterationScheme::Itrans, "R^I", "RR"),
+    (TransliterationScheme::Itrans, "L^i", "lR"),
+    (TransliterationScheme::Itrans, "L^I", "lRR"),
+    (TransliterationScheme::Itrans, "c", "c"),
+    (TransliterationScheme::Itrans, "chh", "ch"),
+    (TransliterationScheme::Itrans, "w", "v"),
+    (TransliterationScheme::Itrans, "shh", "S"),
+    (TransliterationScheme::Itrans, ".n", "M"),
+    (TransliterationScheme::Itrans, ".m", "M"),
+    (TransliterationScheme::Itrans, "x", "k S"),
+    (TransliterationScheme::Itrans, "kSh", "k S"),
+    (TransliterationScheme::Itrans, "GY", "j J"),
+    (TransliterationScheme::Itrans, "dny", "j J"),
+    (TransliterationScheme::Devanagari, "\u{0901}", "M"),
+    (TransliterationScheme::Devanagari, "\u{0950}", "o m"),
+];
+
+/// Precomposed IAST letters and their decomposed (NFD) forms.
+const DECOMPOSED: &[(char, &str)] = &[
+    ('ā', "a\u{0304}"), ('ī', "i\u{0304}"), ('ū', "u\u{0304}"),
+    ('ṛ', "r\u{0323}"), ('ṝ', "r\u{0323}\u{0304}"), ('ḷ', "l\u{0323}"), ('ḹ', "l\u{0323}\u{0304}"),
+    ('ṃ', "m\u{0323}"), ('ṁ', "m\u{0307}"), ('ḥ', "h\u{0323}"),
+    ('ṅ', "n\u{0307}"), ('ñ', "n\u{0303}"), ('ṭ', "t\u{0323}"), ('ḍ', "d\u{0323}"),
+    ('ṇ', "n\u{0323}"), ('ś', "s\u{0301}"), ('ṣ', "s\u{0323}"),
+];
+
+const VIRAMA: &str = "\u{094D}";
+
+/// Vedic accent marks in the Rigveda notation: the stroke above is svarita
+/// and the stroke below anudaatta; udaatta is left unmarked.
+const ACCENTS: &[(&str, Swara)] = &[("\u{0951}", Swara::Svarita), ("\u{0952}", Swara::Anudaatta)];
+const NUKTA: &str = "\u{093C}";
+
+/// What a key of an input scheme stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    Varna(Varna),            // A vowel, consonant or ayogavaha written on its own
+    Conjunct(Varna, Varna),  // Two Varnas written with a single key (ITRANS `x`, ॐ)
+    VowelSign(Varna),        // Devanagari dependent vowel sign replacing the inherent `a`
+    Virama,                  // Devanagari virama, suppressing the inherent `a`
+    Accent(Swara),           // Vedic accent mark on the Akshara before it
+    Ignored,                 // Marks without phonemic value (nukta, zero-width joiners)
+}
+
+/// Longest-match trie from the keys of one scheme to `Glyph`s.
+/// Tables are built once per scheme and shared for the life of the program.
+pub struct GlyphMap {
+    nodes: Vec<TrieNode>, // The root is the first node
+}
+
+struct TrieNode {
+    glyph: Option<Glyph>,          // The Glyph of the key ending at this node
+    children: Vec<(char, usize)>,  // Sorted by character, indexing into `nodes`
+}
+
+impl GlyphMap {
+    /// Returns the shared input table for a transliteration scheme.
+    pub fn for_scheme(scheme: TransliterationScheme) -> &'static GlyphMap {
+        // One table per TransliterationScheme variant, in the order of `ALL`
+        const SCHEMES: usize = TransliterationScheme::ALL.len();
+        static MAPS: [OnceLock<GlyphMap>; SCHEMES] = [const { OnceLock::new() }; SCHEMES];
+        let index = TransliterationScheme::ALL.iter().position(|&s| s == scheme).expect("ALL lists every scheme");
+        MAPS[index].get_or_init(|| GlyphMap::new(scheme))
+    }
+
+    /// Builds the input table for a transliteration scheme.
+    fn new(scheme: TransliterationScheme) -> Self {
+        // Unicode input is Devanagari text
+        let scheme = match scheme {
+            TransliterationScheme::Unicode => TransliterationScheme::Devanagari,
+            other => other,
+        };
+        let mut glyphs = HashMap::new();
+
+        match scheme {
+            TransliterationScheme::Devanagari => {
+                for varna in VarnaMap::inventory().iter().filter(|v| !is_pluta(v.hk)) {
+                    glyphs.insert(varna.dev.to_string(), Glyph::Varna(*varna));
+                }
+                for &(hk, .., sign) in SPELLINGS.iter().filter(|s| !s.5.is_empty() && !is_pluta(s.0)) {
+                    glyphs.insert(sign.to_string(), Glyph::VowelSign(varna(hk)));
+                }
+                glyphs.insert(VIRAMA.to_string(), Glyph::Virama);
+                for &(mark, swara) in ACCENTS {
+                    glyphs.insert(mark.to_string(), Glyph::Accent(swara));
+                }
+                for mark in [NUKTA, "\u{200C}", "\u{200D}"] {
+                    glyphs.insert(mark.to_string(), Glyph::Ignored);
+                }
+            }
+            _ => {
+                for row in SPELLINGS.iter().filter(|s| !is_pluta(s.0)) {
+                    let key = romanized(row, scheme);
+                    glyphs.insert(key.to_string(), Glyph::Varna(varna(row.0)));
+                }
+            }
+        }
+
+        for &(_, key, value) in ALTERNATES.iter().filter(|(s, ..)| *s == scheme) {
+            let glyph = match value.split_once(' ') {
+                Some((first, second)) => Glyph::Conjunct(varna(first), varna(second)),
+                None => Glyph::Varna(varna(value)),
+            };
+            glyphs.insert(key.to_string(), glyph);
+        }
+
+        if scheme == TransliterationScheme::Iast {
+            // Accept decomposed diacritics and capitalized words
+            let keys: Vec<(String, Glyph)> = glyphs.iter().map(|(k, g)| (k.clone(), *g)).collect();
+            for (key, glyph) in keys {
+                let decomposed: String = key.chars().map(|c| {
+                    DECOMPOSED.iter().find(|(p, _)| *p == c).map_or(c.to_string(), |(_, d)| d.to_string())
+                }).collect();
+                for variant in [capitalize(&key), decomposed.clone(), capitalize(&decomposed)] {
+                    glyphs.entry(variant).or_insert(glyph);
+                }
+            }
+        }
+
+        let mut map = GlyphMap { nodes: vec![TrieNode { glyph: None, children: Vec::new() }] };
+        for (key, glyph) in glyphs {
+            map.insert(&key, glyph);
+        }
+        map
+    }
+
+    fn insert(&mut self, key: &str, glyph: Glyph) {
+        let mut node = 0;
+        for c in key.chars() {
+            node = match self.nodes[node].children.binary_search_by_key(&c, |&(k, _)| k) {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode { glyph: None, children: Vec::new() });
+                    self.nodes[node].children.insert(i, (c, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].glyph = Some(glyph);
+    }
+
+    /// Finds the longest key at the start of `input`.
+    /// Returns the matched Glyph and the number of bytes it consumed.
+    pub fn longest_match(&self, input: &str) -> Option<(Glyph, usize)> {
+        let mut node = 0;
+        let mut longest = None;
+
+        for (i, c) in input.char_indices() {
+            let children = &self.nodes[node].children;
+            match children.binary_search_by_key(&c, |&(k, _)| k) {
+                Ok(j) => node = children[j].1,
+                Err(_) => break,
+            }
+            if let Some(glyph) = self.nodes[node].glyph {
+                longest = Some((glyph, i + c.len_utf8()));
+            }
+        }
+
+        longest
+    }
+}
+
+/// Returns the canonical spelling of a Varna in the given scheme.
+/// Devanagari consonants are spelled with their inherent `a`.
+pub fn spell(varna: &Varna, scheme: TransliterationScheme) -> &'static str {
+    match scheme {
+        TransliterationScheme::HarvardKyoto => varna.hk,
+        TransliterationScheme::Devanagari | TransliterationScheme::Unicode => varna.dev,
+        _ => SPELLINGS.iter().find(|row| row.0 == varna.hk).map_or(varna.hk, |row| romanized(row, scheme)),
+    }
+}
+
+/// Renders a sequence of Varnas in the given scheme. In Devanagari, vowels
+/// after a consonant become signs and consonants without a vowel take a virama.
+pub fn render(varnas: &[Varna], scheme: TransliterationScheme) -> String {
+    if !matches!(scheme, TransliterationScheme::Devanagari | TransliterationScheme::Unicode) {
+        return varnas.iter().map(|v| spell(v, scheme)).collect();
+    }
+
+    let mut output = String::new();
+    let mut after_consonant = false;
+
+    for (i, varna) in varnas.iter().enumerate() {
+        if varna.is_vowel() && after_consonant {
+            output.push_str(vowel_sign(varna).unwrap_or(""));
+        } else {
+            output.push_str(varna.dev);
+        }
+
+        after_consonant = varna.is_consonant();
+        if after_consonant && !varnas.get(i + 1).is_some_and(|next| next.is_vowel()) {
+            output.push_str(VIRAMA);
+        }
+    }
+
+    output
+}
+
+/// Returns the Devanagari dependent vowel sign of a vowel, if it has one.
+pub fn vowel_sign(varna: &Varna) -> Option<&'static str> {
+    SPELLINGS.iter().find(|row| row.0 == varna.hk).map(|row| row.5).filter(|s| !s.is_empty())
+}
+
+fn romanized(row: &(&'static str, &'static str, &'static str, &'static str, &'static str, &'static str), scheme: TransliterationScheme) -> &'static str {
+    match scheme {
+        TransliterationScheme::Iast => row.1,
+        TransliterationScheme::Slp1 => row.2,
+        TransliterationScheme::Itrans => row.3,
+        TransliterationScheme::Velthuis => row.4,
+        _ => row.0,
+    }
+}
+
+/// Pluta vowels are written as a vowel and a 3, which could also be a numeral.
+/// They are left out of the input tables and the lexer decides instead.
+fn is_pluta(hk: &str) -> bool {
+    hk.ends_with('3')
+}
+
+fn varna(hk: &str) -> Varna {
+    VarnaMap::get(hk).unwrap_or_else(|| panic!("no Varna spelled {hk:?} in the inventory"))
+}
+
+fn capitalize(key: &str) -> String {
+    let mut chars = key.chars();
+    chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+}
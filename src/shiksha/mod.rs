@@ -1,5 +1,28 @@
 pub mod varna;
 pub mod akshara;
+pub mod lipi;
+pub mod pada;
+pub mod vaakya;
+pub mod ipa;
+pub mod sutra;
+#[cfg(feature = "serialization")]
+pub mod serialization;
+pub mod corpus;
+pub mod export;
+pub mod conllu;
+#[cfg(feature = "tei")]
+pub mod tei;
 
-pub use varna::{Varna, TransliterationScheme, VarnaMap, Swara, SamaSvara, Matra};
-pub use akshara::{Akshara};
+pub use varna::{Varna, TransliterationScheme, VarnaMap, Swara, SamaSvara, Matra, ParseNameError};
+pub use akshara::{Akshara, AksharaError};
+pub use pada::Pada;
+pub use vaakya::{Vaakya, Danda};
+pub use ipa::{IpaOptions, Pronunciation};
+pub use sutra::Sutra;
+#[cfg(feature = "serialization")]
+pub use serialization::{to_json, from_json, SerializationError};
+pub use corpus::{CorpusReader, CorpusWriter, CorpusError, write_corpus};
+pub use conllu::{to_conllu, from_conllu, ConlluSentence, ConlluToken, ConlluWord, ConlluError, ConlluErrorKind};
+pub use export::{annotate, to_html, to_latex, AnnotatedAkshara, AnnotatedVaakya, ExportOptions};
+#[cfg(feature = "tei")]
+pub use tei::{read_tei, write_tei, TeiError, TeiLine, TeiVerse};
@@ -1,7 +1,8 @@
 use crate::shiksha::Akshara;
+use crate::shiksha::ipa::IpaOptions;
 
 /// Defines a Pada (Word) as an array of Aksharas
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Pada {
     pub aksharas: Vec<Akshara>,
 }
@@ -16,4 +17,9 @@ impl Pada {
     pub fn transliterate(&self) -> String {
         self.aksharas.iter().map(|a| a.transliterate()).collect::<Vec<String>>().join(" ")
     }
+
+    /// Returns the IPA transcription of the Pada, with syllables separated by `.`
+    pub fn ipa(&self, options: &IpaOptions) -> String {
+        self.aksharas.iter().map(|a| a.ipa(options)).collect::<Vec<String>>().join(".")
+    }
 }
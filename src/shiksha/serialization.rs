@@ -1,9 +1,118 @@
-use crate::shiksha::{Varna, Akshara};
+use std::fmt;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use serde::de::DeserializeOwned;
+use crate::shiksha::{Varna, VarnaMap, Akshara, AksharaError, Pada, Vaakya, Danda, Sutra, ParseNameError};
+use crate::chandas::{Maatra, Kaala};
 
-/// JSON Serialization Structure for Varna
+/// Version of the JSON schema written by `to_json`. Raised whenever a field
+/// changes meaning or is removed; readers reject documents of other versions.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A JSON document holding one value of the shiksha hierarchy.
+///
+/// ```json
+/// { "version": 1, "kind": "pada", "data": { "aksharas": [ ... ] } }
+/// ```
+///
+/// `kind` is one of `varna`, `akshara`, `pada`, `vaakya`, `vaakyas` (a whole
+/// text) or `sutra`, and `data` is the matching `Serialized*` structure.
+/// Enumerated values (Swara, Matra, Danda, …) are written in their stable
+/// lowercase text form, such as `"udaatta"` or `"diirgha"`.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct Document<T> {
+    pub version: u32,
+    pub kind: String,
+    pub data: T,
+}
+
+/// Why a JSON document could not be read back.
+#[derive(Debug)]
+pub enum SerializationError {
+    Json(serde_json::Error),                              // Not JSON, or not the expected shape
+    UnsupportedVersion(u32),                              // Written with another schema version
+    WrongKind { expected: &'static str, found: String },  // Holds another kind of value
+    UnknownVarna(String),                                 // No Varna has this Harvard-Kyoto name
+    InvalidName(ParseNameError),                          // An enumerated value is misspelled
+    InvalidKaala(u32),                                    // A syllable weight other than 1, 2 or 3
+    InvalidAkshara(AksharaError),                         // The Varnas cannot form an Akshara
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializationError::Json(err) => write!(f, "invalid JSON: {}", err),
+            SerializationError::UnsupportedVersion(version) => write!(f, "schema version {} is not supported (expected {})", version, SCHEMA_VERSION),
+            SerializationError::WrongKind { expected, found } => write!(f, "expected a {} document, found {:?}", expected, found),
+            SerializationError::UnknownVarna(hk) => write!(f, "no Varna is spelled {:?}", hk),
+            SerializationError::InvalidName(err) => write!(f, "{}", err),
+            SerializationError::InvalidKaala(kaala) => write!(f, "a syllable lasts 1, 2 or 3 Kaalas, not {}", kaala),
+            SerializationError::InvalidAkshara(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+impl From<serde_json::Error> for SerializationError {
+    fn from(err: serde_json::Error) -> Self {
+        SerializationError::Json(err)
+    }
+}
+
+impl From<ParseNameError> for SerializationError {
+    fn from(err: ParseNameError) -> Self {
+        SerializationError::InvalidName(err)
+    }
+}
+
+impl From<AksharaError> for SerializationError {
+    fn from(err: AksharaError) -> Self {
+        SerializationError::InvalidAkshara(err)
+    }
+}
+
+/// A value that can be written as a JSON `Document`.
+pub trait Record: Sized {
+    const KIND: &'static str;
+    type Serialized: Serialize + DeserializeOwned;
+
+    fn serialize(&self) -> Self::Serialized;
+    fn deserialize(serialized: &Self::Serialized) -> Result<Self, SerializationError>;
+}
+
+/// Writes a value as a versioned JSON document.
+///
+/// ```
+/// use pss::{Lexer, Vaakya, parse};
+/// use pss::shiksha::{to_json, from_json};
+///
+/// let vaakyas = parse(&Lexer::new("namaH zivAya").tokenize());
+/// let json = to_json(&vaakyas);
+/// assert!(json.starts_with(r#"{"version":1,"kind":"vaakyas""#));
+/// assert_eq!(from_json::<Vec<Vaakya>>(&json)?, vaakyas);
+/// # Ok::<(), pss::shiksha::SerializationError>(())
+/// ```
+pub fn to_json<T: Record>(value: &T) -> String {
+    let document = Document { version: SCHEMA_VERSION, kind: T::KIND.to_string(), data: value.serialize() };
+    serde_json::to_string(&document).expect("serialized structures contain only strings, numbers and lists")
+}
+
+/// Reads a value back from a JSON document written by `to_json`.
+pub fn from_json<T: Record>(json: &str) -> Result<T, SerializationError> {
+    let document: Document<serde_json::Value> = serde_json::from_str(json)?;
+    if document.version != SCHEMA_VERSION {
+        return Err(SerializationError::UnsupportedVersion(document.version));
+    }
+    if document.kind != T::KIND {
+        return Err(SerializationError::WrongKind { expected: T::KIND, found: document.kind });
+    }
+    T::deserialize(&serde_json::from_value(document.data)?)
+}
+
+/// JSON Serialization Structure for Varna
+/// Only `hk`, `swara` and `sama_svara` are read back; the other fields
+/// describe the Varna for readers and follow from its name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SerializedVarna {
     pub hk: String,
     pub dev: String,
@@ -21,85 +130,252 @@ pub fn serialize_varna(varna: &Varna) -> SerializedVarna {
         hk: varna.hk.to_string(),
         dev: varna.dev.to_string(),
         unicode: varna.uni.to_string(),
-        swara: varna.swara.clone(),
-        sama_svara: varna.sama_svara.clone(),
-        matra: varna.matra.clone(),
-        sthanani: varna.sthanani.clone(),
-        prayatna: varna.prayatna.clone(),
+        swara: varna.swara.map(|s| s.name().to_string()),
+        sama_svara: varna.sama_svara.map(|s| s.name().to_string()),
+        matra: varna.matra.map(|m| m.name().to_string()),
+        sthanani: varna.sthanani.map(|s| s.name().to_string()),
+        prayatna: varna.prayatna.map(|p| p.name().to_string()),
     }
 }
 
 /// Converts a SerializedVarna back to Varna
-pub fn deserialize_varna(serialized: &SerializedVarna) -> Varna {
-    Varna::new(
-        &serialized.hk,
-        &serialized.dev,
-        &serialized.unicode,
-        serialized.swara.clone(),
-        serialized.sama_svara.clone(),
-        serialized.matra.clone(),
-        serialized.sthanani.clone(),
-        serialized.prayatna.clone(),
-    )
+pub fn deserialize_varna(serialized: &SerializedVarna) -> Result<Varna, SerializationError> {
+    let varna = VarnaMap::get(&serialized.hk).ok_or_else(|| SerializationError::UnknownVarna(serialized.hk.clone()))?;
+    Ok(Varna {
+        swara: serialized.swara.as_deref().map(str::parse).transpose()?,
+        sama_svara: serialized.sama_svara.as_deref().map(str::parse).transpose()?,
+        ..varna
+    })
 }
 
 /// JSON Serialization Structure for Akshara
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SerializedAkshara {
     pub varnas: Vec<SerializedVarna>,
     pub swara: Option<String>,
     pub sama_svara: Option<String>,
-    pub matra: Option<String>,
+    pub maatra: u32, // Weight in Kaalas: 1 laghu, 2 guru, 3 pluta
 }
 
 /// Converts an Akshara to a Serializable JSON Struct
 pub fn serialize_akshara(akshara: &Akshara) -> SerializedAkshara {
     SerializedAkshara {
         varnas: akshara.varnas.iter().map(serialize_varna).collect(),
-        swara: akshara.swara.clone(),
-        sama_svara: akshara.sama_svara.clone(),
-        matra: akshara.matra.clone(),
+        swara: akshara.swara.map(|s| s.name().to_string()),
+        sama_svara: akshara.sama_svara.map(|s| s.name().to_string()),
+        maatra: akshara.maatra.length.into(),
     }
 }
 
 /// Converts a SerializedAkshara back to Akshara
-pub fn deserialize_akshara(serialized: &SerializedAkshara) -> Option<Akshara> {
-    let varnas: Vec<Varna> = serialized.varnas.iter().map(deserialize_varna).collect();
-    Akshara::new(varnas)
+pub fn deserialize_akshara(serialized: &SerializedAkshara) -> Result<Akshara, SerializationError> {
+    let varnas = serialized.varnas.iter().map(deserialize_varna).collect::<Result<Vec<Varna>, _>>()?;
+    let mut akshara = Akshara::new(varnas)?;
+    akshara.swara = serialized.swara.as_deref().map(str::parse).transpose()?;
+    akshara.sama_svara = serialized.sama_svara.as_deref().map(str::parse).transpose()?;
+    akshara.maatra = match serialized.maatra {
+        1 => Maatra::from(Kaala::One),
+        2 => Maatra::from(Kaala::Two),
+        3 => Maatra::from(Kaala::Three),
+        other => return Err(SerializationError::InvalidKaala(other)),
+    };
+    Ok(akshara)
 }
 
 /// JSON Serialization Structure for Pada
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SerializedPada {
     pub aksharas: Vec<SerializedAkshara>,
 }
 
+/// Converts a Pada to a Serializable JSON Struct
+pub fn serialize_pada(pada: &Pada) -> SerializedPada {
+    SerializedPada { aksharas: pada.aksharas.iter().map(serialize_akshara).collect() }
+}
+
 /// Converts a SerializedPada back to Pada
-pub fn deserialize_pada(serialized: &SerializedPada) -> Pada {
-    let aksharas = serialized.aksharas.iter().filter_map(deserialize_akshara).collect();
-    Pada::new(aksharas)
+pub fn deserialize_pada(serialized: &SerializedPada) -> Result<Pada, SerializationError> {
+    let aksharas = serialized.aksharas.iter().map(deserialize_akshara).collect::<Result<_, _>>()?;
+    Ok(Pada::new(aksharas))
 }
 
 /// JSON Serialization Structure for Vaakya
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SerializedVaakya {
     pub padas: Vec<SerializedPada>,
+    pub danda: Option<String>, // "single" or "double"
+    pub number: Option<u32>,
+}
+
+/// Converts a Vaakya to a Serializable JSON Struct
+pub fn serialize_vaakya(vaakya: &Vaakya) -> SerializedVaakya {
+    SerializedVaakya {
+        padas: vaakya.padas.iter().map(serialize_pada).collect(),
+        danda: vaakya.danda.map(|d| d.name().to_string()),
+        number: vaakya.number,
+    }
 }
 
 /// Converts a SerializedVaakya back to Vaakya
-pub fn deserialize_vaakya(serialized: &SerializedVaakya) -> Vaakya {
-    let padas = serialized.padas.iter().map(deserialize_pada).collect();
-    Vaakya::new(padas)
+pub fn deserialize_vaakya(serialized: &SerializedVaakya) -> Result<Vaakya, SerializationError> {
+    let padas = serialized.padas.iter().map(deserialize_pada).collect::<Result<_, _>>()?;
+    let mut vaakya = Vaakya::new(padas);
+    vaakya.danda = serialized.danda.as_deref().map(str::parse::<Danda>).transpose()?;
+    vaakya.number = serialized.number;
+    Ok(vaakya)
 }
 
 /// JSON Serialization Structure for Sutra
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SerializedSutra {
-    pub varnas: HashMap<String, SerializedVarna>,
+    pub aksharas: Vec<SerializedAkshara>,
+}
+
+/// Converts a Sutra to a Serializable JSON Struct
+pub fn serialize_sutra(sutra: &Sutra) -> SerializedSutra {
+    SerializedSutra { aksharas: sutra.aksharas.iter().map(serialize_akshara).collect() }
 }
 
 /// Converts a SerializedSutra back to Sutra
-pub fn deserialize_sutra(serialized: &SerializedSutra) -> Sutra {
-    let varnas = serialized.varnas.iter().map(|(_, v)| deserialize_varna(v)).collect();
-    Sutra::new(varnas)
+pub fn deserialize_sutra(serialized: &SerializedSutra) -> Result<Sutra, SerializationError> {
+    let aksharas = serialized.aksharas.iter().map(deserialize_akshara).collect::<Result<_, _>>()?;
+    Ok(Sutra::new(aksharas))
+}
+
+impl Record for Varna {
+    const KIND: &'static str = "varna";
+    type Serialized = SerializedVarna;
+
+    fn serialize(&self) -> SerializedVarna {
+        serialize_varna(self)
+    }
+
+    fn deserialize(serialized: &SerializedVarna) -> Result<Self, SerializationError> {
+        deserialize_varna(serialized)
+    }
+}
+
+impl Record for Akshara {
+    const KIND: &'static str = "akshara";
+    type Serialized = SerializedAkshara;
+
+    fn serialize(&self) -> SerializedAkshara {
+        serialize_akshara(self)
+    }
+
+    fn deserialize(serialized: &SerializedAkshara) -> Result<Self, SerializationError> {
+        deserialize_akshara(serialized)
+    }
+}
+
+impl Record for Pada {
+    const KIND: &'static str = "pada";
+    type Serialized = SerializedPada;
+
+    fn serialize(&self) -> SerializedPada {
+        serialize_pada(self)
+    }
+
+    fn deserialize(serialized: &SerializedPada) -> Result<Self, SerializationError> {
+        deserialize_pada(serialized)
+    }
+}
+
+impl Record for Vaakya {
+    const KIND: &'static str = "vaakya";
+    type Serialized = SerializedVaakya;
+
+    fn serialize(&self) -> SerializedVaakya {
+        serialize_vaakya(self)
+    }
+
+    fn deserialize(serialized: &SerializedVaakya) -> Result<Self, SerializationError> {
+        deserialize_vaakya(serialized)
+    }
+}
+
+/// A whole text, as the parser returns it.
+impl Record for Vec<Vaakya> {
+    const KIND: &'static str = "vaakyas";
+    type Serialized = Vec<SerializedVaakya>;
+
+    fn serialize(&self) -> Vec<SerializedVaakya> {
+        self.iter().map(serialize_vaakya).collect()
+    }
+
+    fn deserialize(serialized: &Vec<SerializedVaakya>) -> Result<Self, SerializationError> {
+        serialized.iter().map(deserialize_vaakya).collect()
+    }
+}
+
+impl Record for Sutra {
+    const KIND: &'static str = "sutra";
+    type Serialized = SerializedSutra;
+
+    fn serialize(&self) -> SerializedSutra {
+        serialize_sutra(self)
+    }
+
+    fn deserialize(serialized: &SerializedSutra) -> Result<Self, SerializationError> {
+        deserialize_sutra(serialized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{self, Lexer};
+    use crate::shiksha::{Swara, TransliterationScheme};
+
+    fn vaakyas(text: &str) -> Vec<Vaakya> {
+        niruktam::parse(&Lexer::with_scheme(text, TransliterationScheme::Devanagari).tokenize())
+    }
+
+    #[test]
+    fn text_round_trips() {
+        let text = vaakyas("धर्मक्षेत्रे कुरुक्षेत्रे समवेता युयुत्सवः ।\nमामकाः पाण्डवाश्चैव किमकुर्वत सञ्जय ॥ १ ॥");
+        let json = to_json(&text);
+        assert_eq!(from_json::<Vec<Vaakya>>(&json).unwrap(), text);
+        assert_eq!(text[1].number, Some(1));
+    }
+
+    #[test]
+    fn every_level_round_trips() {
+        let text = vaakyas("ओ३म् नमः शिवाय ॥");
+        let vaakya = &text[0];
+        let pada = &vaakya.padas[1];
+        let akshara = &pada.aksharas[1];
+        let varna = &akshara.varnas[0];
+        let sutra = Sutra::new(pada.aksharas.clone());
+
+        assert_eq!(&from_json::<Vaakya>(&to_json(vaakya)).unwrap(), vaakya);
+        assert_eq!(&from_json::<Pada>(&to_json(pada)).unwrap(), pada);
+        assert_eq!(&from_json::<Akshara>(&to_json(akshara)).unwrap(), akshara);
+        assert_eq!(&from_json::<Varna>(&to_json(varna)).unwrap(), varna);
+        assert_eq!(from_json::<Sutra>(&to_json(&sutra)).unwrap(), sutra);
+        assert_eq!(from_json::<Pada>(&to_json(&vaakya.padas[0])).unwrap().aksharas[0].maatra, Maatra::pluta());
+    }
+
+    #[test]
+    fn accents_round_trip() {
+        let mut a = VarnaMap::get("a").unwrap();
+        a.swara = Some(Swara::Svarita);
+        let akshara = Akshara::new(vec![VarnaMap::get("k").unwrap(), a]).unwrap();
+
+        let json = to_json(&akshara);
+        assert!(json.contains(r#""swara":"svarita""#));
+        assert_eq!(from_json::<Akshara>(&json).unwrap(), akshara);
+    }
+
+    #[test]
+    fn documents_are_checked() {
+        let json = to_json(&VarnaMap::get("k").unwrap());
+        assert!(json.starts_with(r#"{"version":1,"kind":"varna","data":{"hk":"k""#));
+
+        assert!(matches!(from_json::<Akshara>(&json), Err(SerializationError::WrongKind { .. })));
+        let newer = json.replace(r#""version":1"#, r#""version":2"#);
+        assert!(matches!(from_json::<Varna>(&newer), Err(SerializationError::UnsupportedVersion(2))));
+        let unknown = json.replace(r#""hk":"k""#, r#""hk":"q""#);
+        assert!(matches!(from_json::<Varna>(&unknown), Err(SerializationError::UnknownVarna(_))));
+    }
 }
@@ -1,7 +1,7 @@
 use crate::shiksha::{Akshara};
 
 /// Defines a Sutra (Sequence of Aksharas without inherent meaning)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Sutra {
     pub aksharas: Vec<Akshara>, // A Sutra consists of multiple Aksharas
 }
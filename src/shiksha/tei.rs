@@ -0,0 +1,284 @@
+use std::fmt;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use crate::niruktam::{self, Lexer};
+use crate::shiksha::{lipi, Pada, TransliterationScheme, Vaakya};
+use crate::shiksha::export::{end_mark, escape_html};
+
+/// A line of verse (`<l>`) with the attributes that identify it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeiLine {
+    pub id: Option<String>, // xml:id
+    pub n: Option<String>,  // Line number or label
+    pub vaakya: Vaakya,
+}
+
+/// A group of lines (`<lg>`), usually a verse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeiVerse {
+    pub id: Option<String>,  // xml:id
+    pub n: Option<String>,   // Verse number or label
+    pub met: Option<String>, // Metrical pattern the verse is meant to follow
+    pub lines: Vec<TeiLine>,
+}
+
+/// Why a TEI document could not be read.
+#[derive(Debug)]
+pub enum TeiError {
+    Xml(quick_xml::Error),  // The document is not well-formed XML
+    Unclosed(&'static str), // The document ends inside the named element
+}
+
+impl fmt::Display for TeiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TeiError::Xml(err) => write!(f, "invalid XML: {}", err),
+            TeiError::Unclosed(element) => write!(f, "document ends inside <{}>", element),
+        }
+    }
+}
+
+impl std::error::Error for TeiError {}
+
+impl From<quick_xml::Error> for TeiError {
+    fn from(err: quick_xml::Error) -> Self {
+        TeiError::Xml(err)
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for TeiError {
+    fn from(err: quick_xml::events::attributes::AttrError) -> Self {
+        TeiError::Xml(err.into())
+    }
+}
+
+/// Reads the verse of a TEI document: every `<lg>` holding `<l>` elements
+/// becomes a TeiVerse, and every `<l>` a TeiLine whose text is lexed and
+/// parsed into one Vaakya. The text of `<w>` elements is read as separate
+/// Padas; `<note>` elements are skipped. Lines outside any `<lg>` each form a
+/// verse of their own. `scheme` is the scheme of the text, or None to detect it.
+pub fn read_tei(xml: &str, scheme: Option<TransliterationScheme>) -> Result<Vec<TeiVerse>, TeiError> {
+    let mut reader = Reader::from_str(xml);
+    let mut verses = Vec::new();
+    let mut groups: Vec<TeiVerse> = Vec::new(); // Open <lg> elements, innermost last
+    let mut line: Option<(Option<String>, Option<String>, String)> = None; // The open <l>: id, n and text
+    let mut skipping = 0; // Depth inside <note>
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if skipping > 0 && e.local_name().as_ref() == b"note" => skipping += 1,
+            Event::End(e) if skipping > 0 && e.local_name().as_ref() == b"note" => skipping -= 1,
+            Event::Start(_) | Event::End(_) | Event::Empty(_) | Event::Text(_) | Event::CData(_) if skipping > 0 => {}
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"lg" => groups.push(TeiVerse { id: attribute(&e, "xml:id")?, n: attribute(&e, "n")?, met: attribute(&e, "met")?, lines: Vec::new() }),
+                b"l" => line = Some((attribute(&e, "xml:id")?, attribute(&e, "n")?, String::new())),
+                b"note" => skipping = 1,
+                b"w" => push_space(&mut line),
+                _ => {}
+            },
+            Event::Empty(e) if matches!(e.local_name().as_ref(), b"caesura" | b"lb") => push_space(&mut line),
+            Event::Text(text) => {
+                if let Some((_, _, content)) = line.as_mut() {
+                    content.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(text) => {
+                if let Some((_, _, content)) = line.as_mut() {
+                    content.push_str(&String::from_utf8_lossy(&text));
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"w" => push_space(&mut line),
+                b"l" => {
+                    let Some((id, n, text)) = line.take() else { continue };
+                    let tei_line = TeiLine { id, n, vaakya: read_line(&text, scheme) };
+                    match groups.last_mut() {
+                        Some(group) => group.lines.push(tei_line),
+                        None => verses.push(TeiVerse { id: None, n: None, met: None, lines: vec![tei_line] }),
+                    }
+                }
+                b"lg" => {
+                    // Only groups that hold lines themselves are verses; enclosing groups are dropped
+                    if let Some(group) = groups.pop().filter(|g| !g.lines.is_empty()) {
+                        verses.push(group);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if line.is_some() {
+        return Err(TeiError::Unclosed("l"));
+    }
+    if !groups.is_empty() {
+        return Err(TeiError::Unclosed("lg"));
+    }
+    Ok(verses)
+}
+
+/// Writes verses as a TEI document, the text of each line in `script`.
+/// With the `chandas` feature each `<l>` carries its scansion in `real`,
+/// written with the symbols declared in the header: `-` guru, `U` laghu
+/// and `3` pluta. The `met` of each verse is written as it was read.
+pub fn write_tei(verses: &[TeiVerse], script: TransliterationScheme) -> String {
+    let mut xml = String::from("\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<TEI xmlns=\"http://www.tei-c.org/ns/1.0\">
+  <teiHeader>
+    <fileDesc>
+      <titleStmt><title>Untitled</title></titleStmt>
+      <publicationStmt><p>Unpublished</p></publicationStmt>
+      <sourceDesc><p>Converted from plain text</p></sourceDesc>
+    </fileDesc>
+    <encodingDesc>
+      <metDecl type=\"met real\">
+        <metSym value=\"-\">guru</metSym>
+        <metSym value=\"U\">laghu</metSym>
+        <metSym value=\"3\">pluta</metSym>
+      </metDecl>
+    </encodingDesc>
+  </teiHeader>
+  <text>
+    <body>
+");
+
+    for verse in verses {
+        xml.push_str("      <lg type=\"verse\"");
+        push_attribute(&mut xml, "xml:id", &verse.id);
+        push_attribute(&mut xml, "n", &verse.n);
+        push_attribute(&mut xml, "met", &verse.met);
+        xml.push_str(">\n");
+
+        for line in &verse.lines {
+            xml.push_str("        <l");
+            push_attribute(&mut xml, "xml:id", &line.id);
+            push_attribute(&mut xml, "n", &line.n);
+            push_attribute(&mut xml, "real", &real(&line.vaakya));
+            xml.push('>');
+            xml.push_str(&escape_html(&line_text(&line.vaakya, script)));
+            xml.push_str("</l>\n");
+        }
+        xml.push_str("      </lg>\n");
+    }
+
+    xml.push_str("    </body>\n  </text>\n</TEI>\n");
+    xml
+}
+
+/// Lexes and parses the text of a line into one Vaakya, keeping the danda
+/// and verse number that close it.
+fn read_line(text: &str, scheme: Option<TransliterationScheme>) -> Vaakya {
+    let tokens = match scheme {
+        Some(scheme) => Lexer::with_scheme(text, scheme).tokenize(),
+        None => Lexer::new(text).tokenize(),
+    };
+    let vaakyas = niruktam::parse(&tokens);
+
+    let padas: Vec<Pada> = vaakyas.iter().flat_map(|v| v.padas.iter().cloned()).collect();
+    let mut vaakya = Vaakya::new(padas);
+    if let Some(last) = vaakyas.last() {
+        vaakya.danda = last.danda;
+        vaakya.number = last.number;
+    }
+    vaakya
+}
+
+/// The text of a line: its Padas in the script, then its danda and number.
+fn line_text(vaakya: &Vaakya, script: TransliterationScheme) -> String {
+    let mut words: Vec<String> = vaakya.padas.iter().map(|pada| {
+        let varnas: Vec<_> = pada.aksharas.iter().flat_map(|a| a.varnas.iter().copied()).collect();
+        lipi::render(&varnas, script)
+    }).collect();
+    words.extend(end_mark(vaakya.danda, vaakya.number, script));
+    words.join(" ")
+}
+
+#[cfg(feature = "chandas")]
+fn real(vaakya: &Vaakya) -> Option<String> {
+    use crate::chandas::{syllabify, Kaala, VibhagaOptions};
+
+    let syllables = syllabify(vaakya, &VibhagaOptions::default());
+    (!syllables.is_empty()).then(|| syllables.iter().map(|s| match s.maatra.length {
+        Kaala::One => 'U',
+        Kaala::Two => '-',
+        Kaala::Three => '3',
+    }).collect())
+}
+
+#[cfg(not(feature = "chandas"))]
+fn real(_vaakya: &Vaakya) -> Option<String> {
+    None
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, TeiError> {
+    match element.try_get_attribute(name)? {
+        Some(attribute) => Ok(Some(attribute.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+fn push_attribute(xml: &mut String, name: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        xml.push_str(&format!(" {}=\"{}\"", name, escape_html(value)));
+    }
+}
+
+/// Separates words marked up as elements, whose text may not be separated by spaces.
+fn push_space(line: &mut Option<(Option<String>, Option<String>, String)>) {
+    if let Some((_, _, content)) = line.as_mut() {
+        content.push(' ');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GITA: &str = r#"<TEI xmlns="http://www.tei-c.org/ns/1.0"><text><body>
+<div type="chapter" n="1">
+  <lg type="verse" xml:id="bg.1.1" n="1" met="anuṣṭubh">
+    <l n="a">dharmakṣetre kurukṣetre samavetā yuyutsavaḥ <note>Dhṛtarāṣṭra speaks</note>|</l>
+    <l n="b" xml:id="bg.1.1b"><w>māmakāḥ</w><w>pāṇḍavāś</w><w>caiva</w> kim akurvata saṃjaya || 1 ||</l>
+  </lg>
+</div>
+</body></text></TEI>"#;
+
+    #[test]
+    fn reads_verse_structure() {
+        let verses = read_tei(GITA, Some(TransliterationScheme::Iast)).unwrap();
+        assert_eq!(verses.len(), 1);
+        let verse = &verses[0];
+        assert_eq!((verse.id.as_deref(), verse.n.as_deref(), verse.met.as_deref()), (Some("bg.1.1"), Some("1"), Some("anuṣṭubh")));
+
+        let lines: Vec<(Option<&str>, Option<&str>, usize)> = verse.lines.iter()
+            .map(|l| (l.id.as_deref(), l.n.as_deref(), l.vaakya.padas.len()))
+            .collect();
+        assert_eq!(lines, [(None, Some("a"), 4), (Some("bg.1.1b"), Some("b"), 6)]);
+        assert_eq!(verse.lines[1].vaakya.number, Some(1));
+    }
+
+    #[test]
+    fn round_trip() {
+        let verses = read_tei(GITA, Some(TransliterationScheme::Iast)).unwrap();
+        let xml = write_tei(&verses, TransliterationScheme::Devanagari);
+        assert!(xml.contains("<l n=\"a\""));
+        assert!(xml.contains("॥ १ ॥</l>"));
+        assert_eq!(read_tei(&xml, None).unwrap(), verses);
+    }
+
+    #[test]
+    #[cfg(feature = "chandas")]
+    fn scansion_in_real() {
+        let verses = read_tei(GITA, Some(TransliterationScheme::Iast)).unwrap();
+        let xml = write_tei(&verses, TransliterationScheme::Iast);
+        assert!(xml.contains("<l n=\"a\" real=\"----U---UU--U-U-\">"), "{xml}");
+    }
+
+    #[test]
+    fn unclosed_line() {
+        assert!(matches!(read_tei("<lg><l>rāma", None), Err(TeiError::Unclosed("l")) | Err(TeiError::Xml(_))));
+    }
+}
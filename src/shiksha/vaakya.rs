@@ -1,15 +1,50 @@
-use crate::shiksha::Pada;
+use std::str::FromStr;
+use crate::shiksha::{Pada, ParseNameError};
+
+/// The punctuation closing a Vaakya: a danda (।) ends a half-verse or
+/// sentence, a double danda (॥) ends a verse.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Danda {
+    Single, // ।
+    Double, // ॥
+}
+
+impl Danda {
+    /// Returns the stable textual form of the Danda, used in serialized data.
+    pub fn name(self) -> &'static str {
+        match self {
+            Danda::Single => "single",
+            Danda::Double => "double",
+        }
+    }
+}
+
+/// Parses the textual form of a Danda, in any letter case.
+impl FromStr for Danda {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Danda::Single, Danda::Double].into_iter().find(|d| d.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("Danda", s))
+    }
+}
 
 /// Defines a Vaakya (Sentence) as an array of Padas
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Vaakya {
     pub padas: Vec<Pada>, // A Vaakya consists of multiple Padas
+    pub danda: Option<Danda>, // How the Vaakya was closed, if by a danda
+    pub number: Option<u32>, // Verse number written after the closing danda (॥ १ ॥)
 }
 
 impl Vaakya {
     /// Creates a new Vaakya from a list of Padas
     pub fn new(padas: Vec<Pada>) -> Self {
-        Vaakya { padas }
+        Vaakya { padas, danda: None, number: None }
+    }
+
+    /// Returns true if the Vaakya ends the first half of a verse.
+    pub fn is_half_verse(&self) -> bool {
+        self.danda == Some(Danda::Single)
     }
 
     /// Returns the transliteration of the Vaakya using Harvard-Kyoto scheme
@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use crate::shiksha::ipa;
 
 /// Defines available transliteration schemes.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -6,6 +9,49 @@ pub enum TransliterationScheme {
     HarvardKyoto,
     Devanagari,
     Unicode,
+    Iast,
+    Slp1,
+    Itrans,
+    Velthuis,
+}
+
+impl TransliterationScheme {
+    /// Every scheme, in declaration order.
+    pub const ALL: [TransliterationScheme; 7] = [
+        TransliterationScheme::HarvardKyoto, TransliterationScheme::Devanagari, TransliterationScheme::Unicode,
+        TransliterationScheme::Iast, TransliterationScheme::Slp1, TransliterationScheme::Itrans, TransliterationScheme::Velthuis,
+    ];
+
+    /// Returns the short name of the scheme, as typed on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            TransliterationScheme::HarvardKyoto => "hk",
+            TransliterationScheme::Devanagari => "devanagari",
+            TransliterationScheme::Unicode => "unicode",
+            TransliterationScheme::Iast => "iast",
+            TransliterationScheme::Slp1 => "slp1",
+            TransliterationScheme::Itrans => "itrans",
+            TransliterationScheme::Velthuis => "velthuis",
+        }
+    }
+}
+
+impl fmt::Display for TransliterationScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the short name of a scheme in any letter case; `harvard-kyoto` is also accepted.
+impl FromStr for TransliterationScheme {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("harvard-kyoto") {
+            return Ok(TransliterationScheme::HarvardKyoto);
+        }
+        TransliterationScheme::ALL.into_iter().find(|t| t.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("transliteration scheme", s))
+    }
 }
 
 /// Defines the pitch (Swara) based on Pāṇini's Śikṣā 2.2
@@ -28,6 +74,89 @@ pub enum SamaSvara {
     Ni, // निषाद (Nishada) - Corresponds to Udaatta
 }
 
+impl Swara {
+    /// Every Swara, high, low and falling.
+    pub const ALL: [Swara; 3] = [Swara::Udaatta, Swara::Anudaatta, Swara::Svarita];
+
+    /// Returns the stable textual form of the Swara, used in serialized data.
+    pub fn name(self) -> &'static str {
+        match self {
+            Swara::Udaatta => "udaatta",
+            Swara::Anudaatta => "anudaatta",
+            Swara::Svarita => "svarita",
+        }
+    }
+}
+
+impl fmt::Display for Swara {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the textual form of a Swara, in any letter case.
+impl FromStr for Swara {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Swara::ALL.into_iter().find(|w| w.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("Swara", s))
+    }
+}
+
+impl SamaSvara {
+    /// Every Sama Svara, in scale order.
+    pub const ALL: [SamaSvara; 7] = [SamaSvara::Sa, SamaSvara::Re, SamaSvara::Ga, SamaSvara::Ma, SamaSvara::Pa, SamaSvara::Dha, SamaSvara::Ni];
+
+    /// Returns the stable textual form of the Sama Svara, used in serialized data.
+    pub fn name(self) -> &'static str {
+        match self {
+            SamaSvara::Sa => "sa",
+            SamaSvara::Re => "re",
+            SamaSvara::Ga => "ga",
+            SamaSvara::Ma => "ma",
+            SamaSvara::Pa => "pa",
+            SamaSvara::Dha => "dha",
+            SamaSvara::Ni => "ni",
+        }
+    }
+}
+
+impl fmt::Display for SamaSvara {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the textual form of a Sama Svara, in any letter case.
+impl FromStr for SamaSvara {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SamaSvara::ALL.into_iter().find(|w| w.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("Sama Svara", s))
+    }
+}
+
+/// Text that does not name any value of the type it was parsed as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNameError {
+    pub expected: &'static str, // What the text should have named
+    pub input: String,          // The text as given
+}
+
+impl ParseNameError {
+    pub fn new(expected: &'static str, input: &str) -> Self {
+        ParseNameError { expected, input: input.to_string() }
+    }
+}
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} is not a {}", self.input, self.expected)
+    }
+}
+
+impl std::error::Error for ParseNameError {}
+
 /// Defines vowel duration (Matra) from Pāṇini Śikṣā 2.2
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Matra {
@@ -36,6 +165,17 @@ pub enum Matra {
     Pluta,   // Prolonged vowel
 }
 
+impl Matra {
+    /// Returns the stable textual form of the Matra, used in serialized data.
+    pub fn name(self) -> &'static str {
+        match self {
+            Matra::Hrasva => "hrasva",
+            Matra::Diirgha => "diirgha",
+            Matra::Pluta => "pluta",
+        }
+    }
+}
+
 /// Defines place of articulation (Sthanani) based on Pāṇini Śikṣā 4.8
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Sthanani {
@@ -49,6 +189,22 @@ pub enum Sthanani {
     Talu,   // तालु (Palate)
 }
 
+impl Sthanani {
+    /// Returns the stable textual form of the Sthanani, used in serialized data.
+    pub fn name(self) -> &'static str {
+        match self {
+            Sthanani::Uras => "uras",
+            Sthanani::Kantha => "kantha",
+            Sthanani::Murdha => "murdha",
+            Sthanani::Jihvamula => "jihvamula",
+            Sthanani::Danta => "danta",
+            Sthanani::Nasika => "nasika",
+            Sthanani::Oshtha => "oshtha",
+            Sthanani::Talu => "talu",
+        }
+    }
+}
+
 /// Defines articulation effort (Prayatna) based on Pāṇini Śikṣā 2.1
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Prayatna {
@@ -62,6 +218,22 @@ pub enum Prayatna {
     Anunasika,  // Semi-nasalized articulation
 }
 
+impl Prayatna {
+    /// Returns the stable textual form of the Prayatna, used in serialized data.
+    pub fn name(self) -> &'static str {
+        match self {
+            Prayatna::Sprishta => "sprishta",
+            Prayatna::IshatSparsha => "ishat_sparsha",
+            Prayatna::Vivrita => "vivrita",
+            Prayatna::Samvruta => "samvruta",
+            Prayatna::Alpaprana => "alpaprana",
+            Prayatna::Mahaprana => "mahaprana",
+            Prayatna::Nasika => "nasika",
+            Prayatna::Anunasika => "anunasika",
+        }
+    }
+}
+
 /// Represents a complete Sanskrit phonetic unit (Varna) with phonetic attributes.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Varna {
@@ -77,6 +249,7 @@ pub struct Varna {
 
 impl Varna {
     /// Creates a new Varna with extended phonetic properties.
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         hk: &'static str, 
         dev: &'static str, 
@@ -91,87 +264,152 @@ impl Varna {
     }
 }
 
+/// The Varna inventory, one entry per phoneme, spelled in Harvard-Kyoto.
+/// Consonants are bare (no inherent `a`); the lexer supplies the vowel.
+/// The older spellings `aa`, `ii`, `uu`, `sh` and `Sh` are still read as aliases.
+const VARNAS: &[Varna] = &[
+    // Swaras (Vowels)
+    Varna::new("a", "अ", "\u{0905}", Some(Swara::Anudaatta), Some(SamaSvara::Sa), Some(Matra::Hrasva), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("A", "आ", "\u{0906}", Some(Swara::Anudaatta), Some(SamaSvara::Sa), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("i", "इ", "\u{0907}", Some(Swara::Udaatta), Some(SamaSvara::Ga), Some(Matra::Hrasva), Some(Sthanani::Talu), Some(Prayatna::Vivrita)),
+    Varna::new("I", "ई", "\u{0908}", Some(Swara::Udaatta), Some(SamaSvara::Ga), Some(Matra::Diirgha), Some(Sthanani::Talu), Some(Prayatna::Vivrita)),
+    Varna::new("u", "उ", "\u{0909}", Some(Swara::Anudaatta), Some(SamaSvara::Re), Some(Matra::Hrasva), Some(Sthanani::Oshtha), Some(Prayatna::Vivrita)),
+    Varna::new("U", "ऊ", "\u{090A}", Some(Swara::Anudaatta), Some(SamaSvara::Re), Some(Matra::Diirgha), Some(Sthanani::Oshtha), Some(Prayatna::Vivrita)),
+    Varna::new("R", "ऋ", "\u{090B}", Some(Swara::Svarita), Some(SamaSvara::Ma), Some(Matra::Hrasva), Some(Sthanani::Murdha), Some(Prayatna::Vivrita)),
+    Varna::new("RR", "ॠ", "\u{0960}", Some(Swara::Svarita), Some(SamaSvara::Ma), Some(Matra::Diirgha), Some(Sthanani::Murdha), Some(Prayatna::Vivrita)),
+    Varna::new("lR", "ऌ", "\u{090C}", Some(Swara::Svarita), Some(SamaSvara::Pa), Some(Matra::Hrasva), Some(Sthanani::Danta), Some(Prayatna::Vivrita)),
+    Varna::new("lRR", "ॡ", "\u{0961}", Some(Swara::Svarita), Some(SamaSvara::Pa), Some(Matra::Diirgha), Some(Sthanani::Danta), Some(Prayatna::Vivrita)),
+    Varna::new("e", "ए", "\u{090F}", Some(Swara::Udaatta), Some(SamaSvara::Dha), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("ai", "ऐ", "\u{0910}", Some(Swara::Udaatta), Some(SamaSvara::Ni), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("o", "ओ", "\u{0913}", Some(Swara::Anudaatta), Some(SamaSvara::Dha), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("au", "औ", "\u{0914}", Some(Swara::Anudaatta), Some(SamaSvara::Ni), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+
+    // Pluta (prolonged) vowels, written with a 3 after the vowel
+    Varna::new("a3", "अ३", "\u{0905}\u{0969}", Some(Swara::Anudaatta), Some(SamaSvara::Sa), Some(Matra::Pluta), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("i3", "इ३", "\u{0907}\u{0969}", Some(Swara::Udaatta), Some(SamaSvara::Ga), Some(Matra::Pluta), Some(Sthanani::Talu), Some(Prayatna::Vivrita)),
+    Varna::new("u3", "उ३", "\u{0909}\u{0969}", Some(Swara::Anudaatta), Some(SamaSvara::Re), Some(Matra::Pluta), Some(Sthanani::Oshtha), Some(Prayatna::Vivrita)),
+    Varna::new("R3", "ऋ३", "\u{090B}\u{0969}", Some(Swara::Svarita), Some(SamaSvara::Ma), Some(Matra::Pluta), Some(Sthanani::Murdha), Some(Prayatna::Vivrita)),
+    Varna::new("lR3", "ऌ३", "\u{090C}\u{0969}", Some(Swara::Svarita), Some(SamaSvara::Pa), Some(Matra::Pluta), Some(Sthanani::Danta), Some(Prayatna::Vivrita)),
+    Varna::new("e3", "ए३", "\u{090F}\u{0969}", Some(Swara::Udaatta), Some(SamaSvara::Dha), Some(Matra::Pluta), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("ai3", "ऐ३", "\u{0910}\u{0969}", Some(Swara::Udaatta), Some(SamaSvara::Ni), Some(Matra::Pluta), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("o3", "ओ३", "\u{0913}\u{0969}", Some(Swara::Anudaatta), Some(SamaSvara::Dha), Some(Matra::Pluta), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+    Varna::new("au3", "औ३", "\u{0914}\u{0969}", Some(Swara::Anudaatta), Some(SamaSvara::Ni), Some(Matra::Pluta), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
+
+    // Vyanjanas (Consonants)
+    // Ka-varga (Gutturals)
+    Varna::new("k", "क", "\u{0915}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Sprishta)),
+    Varna::new("kh", "ख", "\u{0916}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Mahaprana)),
+    Varna::new("g", "ग", "\u{0917}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Sprishta)),
+    Varna::new("gh", "घ", "\u{0918}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Mahaprana)),
+    Varna::new("G", "ङ", "\u{0919}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Nasika)),
+
+    // Cha-varga (Palatals)
+    Varna::new("c", "च", "\u{091A}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Sprishta)),
+    Varna::new("ch", "छ", "\u{091B}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Mahaprana)),
+    Varna::new("j", "ज", "\u{091C}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Sprishta)),
+    Varna::new("jh", "झ", "\u{091D}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Mahaprana)),
+    Varna::new("J", "ञ", "\u{091E}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Nasika)),
+
+    // Ta-varga (Cerebrals)
+    Varna::new("T", "ट", "\u{091F}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Sprishta)),
+    Varna::new("Th", "ठ", "\u{0920}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Mahaprana)),
+    Varna::new("D", "ड", "\u{0921}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Sprishta)),
+    Varna::new("Dh", "ढ", "\u{0922}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Mahaprana)),
+    Varna::new("N", "ण", "\u{0923}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Nasika)),
+
+    // ta-varga (Dentals)
+    Varna::new("t", "त", "\u{0924}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Sprishta)),
+    Varna::new("th", "थ", "\u{0925}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Mahaprana)),
+    Varna::new("d", "द", "\u{0926}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Sprishta)),
+    Varna::new("dh", "ध", "\u{0927}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Mahaprana)),
+    Varna::new("n", "न", "\u{0928}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Nasika)),
+
+    // pa-varga (Labials)
+    Varna::new("p", "प", "\u{092A}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Sprishta)),
+    Varna::new("ph", "फ", "\u{092B}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Mahaprana)),
+    Varna::new("b", "ब", "\u{092C}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Sprishta)),
+    Varna::new("bh", "भ", "\u{092D}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Mahaprana)),
+    Varna::new("m", "म", "\u{092E}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Nasika)),
+
+    // Antahstha (Semi-vowels)
+    Varna::new("y", "य", "\u{092F}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::IshatSparsha)),
+    Varna::new("r", "र", "\u{0930}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::IshatSparsha)),
+    Varna::new("l", "ल", "\u{0932}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::IshatSparsha)),
+    Varna::new("v", "व", "\u{0935}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::IshatSparsha)),
+
+    // Ushman (Sibilants and Aspirate)
+    Varna::new("z", "श", "\u{0936}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::IshatSparsha)),
+    Varna::new("S", "ष", "\u{0937}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::IshatSparsha)),
+    Varna::new("s", "स", "\u{0938}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::IshatSparsha)),
+    Varna::new("h", "ह", "\u{0939}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::IshatSparsha)),
+
+    // Ayogavahas: Anusvara, Visarga and its allophones
+    Varna::new("M", "ं", "\u{0902}", None, None, None, None, Some(Prayatna::Anunasika)),
+    Varna::new("H", "ः", "\u{0903}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::IshatSparsha)),
+    Varna::new("Z", "ᳵ", "\u{1CF5}", None, None, None, Some(Sthanani::Jihvamula), Some(Prayatna::IshatSparsha)), // Jihvamuliya, visarga before k kh
+    Varna::new("V", "ᳶ", "\u{1CF6}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::IshatSparsha)),    // Upadhmaniya, visarga before p ph
+];
+
+impl Varna {
+    /// Returns true for vowels (every vowel carries a Matra).
+    pub fn is_vowel(&self) -> bool {
+        self.matra.is_some()
+    }
+
+    /// Returns true for anusvara, visarga, jihvamuliya and upadhmaniya,
+    /// which follow a vowel without being one.
+    pub fn is_ayogavaha(&self) -> bool {
+        matches!(self.hk, "M" | "H" | "Z" | "V")
+    }
+
+    /// Returns the pluta (prolonged) form of a vowel. Long and short vowels
+    /// share one pluta form; the diphthongs have their own.
+    pub fn pluta(&self) -> Option<Varna> {
+        let short = match self.hk {
+            "A" => "a",
+            "I" => "i",
+            "U" => "u",
+            "RR" => "R",
+            "lRR" => "lR",
+            hk => hk,
+        };
+        VARNAS.iter().find(|v| v.matra == Some(Matra::Pluta) && v.hk.strip_suffix('3') == Some(short)).copied()
+    }
+
+    /// Returns true for consonants (vyanjanas).
+    pub fn is_consonant(&self) -> bool {
+        !self.is_vowel() && !self.is_ayogavaha()
+    }
+
+    /// Returns true for voiced (ghosha) Varnas: vowels, voiced stops, nasals,
+    /// semi-vowels and ha. Voicing is a bahya prayatna and is not part of `Prayatna`.
+    pub fn is_ghosha(&self) -> bool {
+        self.is_vowel() || matches!(self.hk,
+            "g" | "gh" | "G" | "j" | "jh" | "J" | "D" | "Dh" | "N" | "d" | "dh" | "n" |
+            "b" | "bh" | "m" | "y" | "r" | "l" | "v" | "h" | "M")
+    }
+
+    /// Returns the IPA transcription of the Varna in the standard pronunciation.
+    pub fn ipa(&self) -> String {
+        ipa::varna(self)
+    }
+}
+
 /// Provides a mapping of transliterations to `Varna`
 pub struct VarnaMap;
 
 impl VarnaMap {
+    /// Returns every known Varna in inventory order.
+    pub fn inventory() -> &'static [Varna] {
+        VARNAS
+    }
+
+    /// Looks up a Varna by its Harvard-Kyoto spelling.
+    pub fn get(hk: &str) -> Option<Varna> {
+        VARNAS.iter().find(|v| v.hk == hk).copied()
+    }
+
     pub fn get_map() -> HashMap<&'static str, Varna> {
-        let mut map = HashMap::new();
-        
-        let varnas = vec![
-            // Swaras (Vowels)
-            ("a", "अ", "\u{0905}", Some(Swara::Anudaatta), Some(SamaSvara::Sa), Some(Matra::Hrasva), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
-            ("aa", "आ", "\u{0906}", Some(Swara::Anudaatta), Some(SamaSvara::Sa), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
-            ("i", "इ", "\u{0907}", Some(Swara::Udaatta), Some(SamaSvara::Ga), Some(Matra::Hrasva), Some(Sthanani::Talu), Some(Prayatna::Vivrita)),
-            ("ii", "ई", "\u{0908}", Some(Swara::Udaatta), Some(SamaSvara::Ga), Some(Matra::Diirgha), Some(Sthanani::Talu), Some(Prayatna::Vivrita)),
-            ("u", "उ", "\u{0909}", Some(Swara::Anudaatta), Some(SamaSvara::Re), Some(Matra::Hrasva), Some(Sthanani::Oshtha), Some(Prayatna::Vivrita)),
-            ("uu", "ऊ", "\u{090A}", Some(Swara::Anudaatta), Some(SamaSvara::Re), Some(Matra::Diirgha), Some(Sthanani::Oshtha), Some(Prayatna::Vivrita)),
-            ("R", "ऋ", "\u{090B}", Some(Swara::Svarita), Some(SamaSvara::Ma), Some(Matra::Hrasva), Some(Sthanani::Murdha), Some(Prayatna::Vivrita)),
-            ("RR", "ॠ", "\u{0960}", Some(Swara::Svarita), Some(SamaSvara::Ma), Some(Matra::Diirgha), Some(Sthanani::Murdha), Some(Prayatna::Vivrita)),
-            ("lR", "ऌ", "\u{090C}", Some(Swara::Svarita), Some(SamaSvara::Pa), Some(Matra::Hrasva), Some(Sthanani::Danta), Some(Prayatna::Vivrita)),
-            ("lRR", "ॡ", "\u{0961}", Some(Swara::Svarita), Some(SamaSvara::Pa), Some(Matra::Diirgha), Some(Sthanani::Danta), Some(Prayatna::Vivrita)),
-            ("e", "ए", "\u{090F}", Some(Swara::Udaatta), Some(SamaSvara::Dha), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
-            ("ai", "ऐ", "\u{0910}", Some(Swara::Udaatta), Some(SamaSvara::Ni), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
-            ("o", "ओ", "\u{0913}", Some(Swara::Anudaatta), Some(SamaSvara::Dha), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
-            ("au", "औ", "\u{0914}", Some(Swara::Anudaatta), Some(SamaSvara::Ni), Some(Matra::Diirgha), Some(Sthanani::Kantha), Some(Prayatna::Vivrita)),
-
-            // Vyanjanas (Consonants)
-            // Ka-varga (Gutturals)
-            ("ka", "क", "\u{0915}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Sprishta)),
-            ("kha", "ख", "\u{0916}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Mahaprana)),
-            ("ga", "ग", "\u{0917}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Sprishta)),
-            ("gha", "घ", "\u{0918}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Mahaprana)),
-            ("nga", "ङ", "\u{0919}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::Nasika)),
-
-            // Cha-varga (Palatals)
-            ("ca", "च", "\u{091A}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Sprishta)),
-            ("cha", "छ", "\u{091B}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Mahaprana)),
-            ("ja", "ज", "\u{091C}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Sprishta)),
-            ("jha", "झ", "\u{091D}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Mahaprana)),
-            ("nya", "ञ", "\u{091E}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::Nasika)),
-
-            // Ta-varga (Cerebrals)
-            ("Ta", "ट", "\u{091F}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Sprishta)),
-            ("Tha", "ठ", "\u{0920}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Mahaprana)),
-            ("Da", "ड", "\u{0921}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Sprishta)),
-            ("Dha", "ढ", "\u{0922}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Mahaprana)),
-            ("Na", "ण", "\u{0923}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::Nasika)),
-
-            // ta-varga (Dentals)
-            ("ta", "त", "\u{0924}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Sprishta)),
-            ("tha", "थ", "\u{0925}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Mahaprana)),
-            ("da", "द", "\u{0926}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Sprishta)),
-            ("dha", "ध", "\u{0927}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Mahaprana)),
-            ("na", "न", "\u{0928}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::Nasika)),
-
-            // pa-varga (Labials)
-            ("pa", "प", "\u{092A}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Sprishta)),
-            ("pha", "फ", "\u{092B}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Mahaprana)),
-            ("ba", "ब", "\u{092C}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Sprishta)),
-            ("bha", "भ", "\u{092D}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Mahaprana)),
-            ("ma", "म", "\u{092E}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::Nasika)),
-
-            // Antahstha (Semi-vowels)
-            ("ya", "य", "\u{092F}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::IshatSparsha)),
-            ("ra", "र", "\u{0930}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::IshatSparsha)),
-            ("la", "ल", "\u{0932}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::IshatSparsha)),
-            ("va", "व", "\u{0935}", None, None, None, Some(Sthanani::Oshtha), Some(Prayatna::IshatSparsha)),
-
-            // Ushman (Sibilants and Aspirate)
-            ("sha", "श", "\u{0936}", None, None, None, Some(Sthanani::Talu), Some(Prayatna::IshatSparsha)),
-            ("Sha", "ष", "\u{0937}", None, None, None, Some(Sthanani::Murdha), Some(Prayatna::IshatSparsha)),
-            ("sa", "स", "\u{0938}", None, None, None, Some(Sthanani::Danta), Some(Prayatna::IshatSparsha)),
-            ("ha", "ह", "\u{0939}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::IshatSparsha)),
-
-            // Anusvara and Visarga
-            ("M", "ं", "\u{0902}", None, None, None, None, Some(Prayatna::Anunasika)),
-            ("H", "ः", "\u{0903}", None, None, None, Some(Sthanani::Kantha), Some(Prayatna::IshatSparsha))
-        ];
-        
-        for (hk, dev, uni, swara, sama_svara, matra, sthanani, prayatna) in varnas {
-            map.insert(hk, Varna::new(hk, dev, uni, swara, sama_svara, matra, sthanani, prayatna));
-        }
-        
-        map
+        VARNAS.iter().map(|v| (v.hk, *v)).collect()
     }
 }
@@ -0,0 +1,349 @@
+// The sutras of the Ashtadhyayi that the grammar refers to. This is a
+// selection, not the complete sutrapatha: the samjnas and paribhashas of the
+// first adhyaya, the headings, and the rules behind the sandhi, conjugation
+// and declension in this module. Sutras outside it are not looked up.
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use crate::niruktam::{Lexer, Token};
+use crate::shiksha::{lipi, Akshara, Pada, ParseNameError, Sutra, TransliterationScheme};
+
+/// The place of a sutra in the Ashtadhyayi: adhyaya, pada and sutra.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct SutraNumber {
+    pub adhyaya: u8, // 1 to 8
+    pub pada: u8,    // 1 to 4
+    pub sutra: u16,
+}
+
+impl SutraNumber {
+    pub fn new(adhyaya: u8, pada: u8, sutra: u16) -> Self {
+        SutraNumber { adhyaya, pada, sutra }
+    }
+}
+
+impl fmt::Display for SutraNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.adhyaya, self.pada, self.sutra)
+    }
+}
+
+/// Parses a number written adhyaya.pada.sutra, as in "6.1.77".
+impl FromStr for SutraNumber {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('.').collect();
+        match parts[..] {
+            [adhyaya, pada, sutra] => match (adhyaya.parse(), pada.parse(), sutra.parse()) {
+                (Ok(adhyaya @ 1..=8), Ok(pada @ 1..=4), Ok(sutra @ 1..)) => Ok(SutraNumber { adhyaya, pada, sutra }),
+                _ => Err(ParseNameError::new("sutra number", s)),
+            },
+            _ => Err(ParseNameError::new("sutra number", s)),
+        }
+    }
+}
+
+/// The six kinds of sutra by what they do.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SutraType {
+    Samjna,     // Defines a technical term
+    Paribhasha, // Governs how other sutras are read
+    Vidhi,      // Prescribes an operation
+    Niyama,     // Restricts a rule that would apply anyway
+    Atidesha,   // Extends the properties of one thing to another
+    Adhikara,   // A heading carried into the sutras that follow
+}
+
+impl SutraType {
+    pub const ALL: [SutraType; 6] = [
+        SutraType::Samjna, SutraType::Paribhasha, SutraType::Vidhi,
+        SutraType::Niyama, SutraType::Atidesha, SutraType::Adhikara,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SutraType::Samjna => "samjna",
+            SutraType::Paribhasha => "paribhasha",
+            SutraType::Vidhi => "vidhi",
+            SutraType::Niyama => "niyama",
+            SutraType::Atidesha => "atidesha",
+            SutraType::Adhikara => "adhikara",
+        }
+    }
+}
+
+impl fmt::Display for SutraType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a kind of sutra in any letter case.
+impl FromStr for SutraType {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SutraType::ALL.into_iter().find(|t| t.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("sutra type", s))
+    }
+}
+
+/// A word a sutra inherits from an earlier one (anuvritti).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Anuvritti {
+    pub pada: Pada,
+    pub from: SutraNumber,
+}
+
+/// A sutra of the Ashtadhyayi.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AshtadhyayiSutra {
+    pub number: SutraNumber,
+    pub text: Sutra,                // As recited, in continuous sandhi
+    pub kind: SutraType,
+    pub anuvritti: Vec<Anuvritti>,  // Words carried from earlier sutras, in the order they are read
+    pub scope: Option<SutraNumber>, // The last sutra an adhikara governs
+}
+
+impl AshtadhyayiSutra {
+    /// Returns the text written in the given scheme.
+    pub fn render(&self, scheme: TransliterationScheme) -> String {
+        let varnas: Vec<_> = self.text.aksharas.iter().flat_map(|akshara| akshara.varnas.iter().copied()).collect();
+        lipi::render(&varnas, scheme)
+    }
+
+    /// The headings (adhikara) whose scope the sutra falls in, in order.
+    pub fn adhikaras(&self) -> Vec<&'static AshtadhyayiSutra> {
+        ashtadhyayi().iter()
+            .filter(|s| s.number < self.number && s.scope.is_some_and(|scope| self.number <= scope))
+            .collect()
+    }
+
+    /// The sutra read in full, with its inherited words and headings.
+    pub fn expand(&self) -> ExpandedSutra<'_> {
+        ExpandedSutra { sutra: self, anuvritti: self.anuvritti.clone(), adhikaras: self.adhikaras() }
+    }
+}
+
+/// A sutra with the words it is read with: the anuvritti and the headings in
+/// force at its place.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExpandedSutra<'a> {
+    pub sutra: &'a AshtadhyayiSutra,
+    pub anuvritti: Vec<Anuvritti>,
+    pub adhikaras: Vec<&'static AshtadhyayiSutra>,
+}
+
+/// Shows the number and text in IAST, then the inherited words in
+/// parentheses and the headings in brackets, as "8.4.41 ṣṭunāṣṭuḥ (stoḥ) [pūrvatrāsiddham]".
+impl fmt::Display for ExpandedSutra<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.sutra.number, self.sutra.render(TransliterationScheme::Iast))?;
+        if !self.anuvritti.is_empty() {
+            let words: Vec<String> = self.anuvritti.iter().map(|a| iast(&a.pada.aksharas)).collect();
+            write!(f, " ({})", words.join(" "))?;
+        }
+        if !self.adhikaras.is_empty() {
+            let headings: Vec<String> = self.adhikaras.iter().map(|s| s.render(TransliterationScheme::Iast)).collect();
+            write!(f, " [{}]", headings.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+fn iast(aksharas: &[Akshara]) -> String {
+    let varnas: Vec<_> = aksharas.iter().flat_map(|akshara| akshara.varnas.iter().copied()).collect();
+    lipi::render(&varnas, TransliterationScheme::Iast)
+}
+
+/// Number, text in Harvard-Kyoto with words separated by spaces, type,
+/// inherited words with the sutra they come from, and for an adhikara the
+/// last sutra it governs.
+type Entry = (&'static str, &'static str, SutraType, &'static [(&'static str, &'static str)], &'static str);
+
+use SutraType::*;
+
+const ENTRIES: &[Entry] = &[
+    ("1.1.1", "vRddhir Adaic", Samjna, &[], ""),
+    ("1.1.2", "adeG guNaH", Samjna, &[], ""),
+    ("1.1.3", "iko guNavRddhI", Paribhasha, &[], ""),
+    ("1.1.4", "na dhAtulopa ArdhadhAtuke", Paribhasha, &[("ikaH", "1.1.3"), ("guNavRddhI", "1.1.3")], ""),
+    ("1.1.5", "kGiti ca", Paribhasha, &[("ikaH", "1.1.3"), ("guNavRddhI", "1.1.3"), ("na", "1.1.4")], ""),
+    ("1.1.6", "dIdhIvevITAm", Paribhasha, &[("ikaH", "1.1.3"), ("guNavRddhI", "1.1.3"), ("na", "1.1.4")], ""),
+    ("1.1.7", "halo 'nantarAH saMyogaH", Samjna, &[], ""),
+    ("1.1.8", "mukhanAsikAvacano 'nunAsikaH", Samjna, &[], ""),
+    ("1.1.9", "tulyAsyaprayatnaM savarNam", Samjna, &[], ""),
+    ("1.1.11", "IdUdeddvivacanaM pragRhyam", Samjna, &[], ""),
+    ("1.1.37", "svarAdinipAtam avyayam", Samjna, &[], ""),
+    ("1.1.42", "zi sarvanAmasthAnam", Samjna, &[], ""),
+    ("1.1.43", "suD anapuMsakasya", Samjna, &[("sarvanAmasthAnam", "1.1.42")], ""),
+    ("1.1.45", "ig yaNaH samprasAraNam", Samjna, &[], ""),
+    ("1.1.46", "Adyantau Takitau", Paribhasha, &[], ""),
+    ("1.1.49", "SaSThI sthAneyogA", Paribhasha, &[], ""),
+    ("1.1.50", "sthAne 'ntaratamaH", Paribhasha, &[], ""),
+    ("1.1.51", "ur aN raparaH", Paribhasha, &[("sthAne", "1.1.50")], ""),
+    ("1.1.52", "alo 'ntyasya", Paribhasha, &[("SaSThI", "1.1.49")], ""),
+    ("1.1.56", "sthAnivad Adezo 'nalvidhau", Atidesha, &[], ""),
+    ("1.1.57", "acaH parasmin pUrvavidhau", Atidesha, &[("sthAnivat", "1.1.56"), ("AdezaH", "1.1.56")], ""),
+    ("1.1.60", "adarzanaM lopaH", Samjna, &[], ""),
+    ("1.1.62", "pratyayalope pratyayalakSaNam", Atidesha, &[], ""),
+    ("1.1.68", "svaM rUpaM zabdasyAzabdasaMjJA", Paribhasha, &[], ""),
+    ("1.1.70", "taparas tatkAlasya", Paribhasha, &[], ""),
+    ("1.1.71", "Adir antyena sahetA", Samjna, &[], ""),
+    ("1.1.72", "yena vidhis tadantasya", Paribhasha, &[], ""),
+    ("1.2.27", "UkAlo 'jjhrasvadIrghaplutaH", Samjna, &[], ""),
+    ("1.3.1", "bhUvAdayo dhAtavaH", Samjna, &[], ""),
+    ("1.3.2", "upadeze 'j anunAsika it", Samjna, &[], ""),
+    ("1.3.3", "hal antyam", Samjna, &[("upadeze", "1.3.2"), ("it", "1.3.2")], ""),
+    ("1.3.9", "tasya lopaH", Vidhi, &[], ""),
+    ("1.3.12", "anudAttaGita Atmanepadam", Vidhi, &[], ""),
+    ("1.3.78", "zeSAt kartari parasmaipadam", Niyama, &[], ""),
+    ("1.4.1", "A kaDArAd ekA saMjJA", Adhikara, &[], "2.2.38"),
+    ("1.4.2", "vipratiSedhe paraM kAryam", Paribhasha, &[], ""),
+    ("1.4.3", "yU stryAkhyau nadI", Samjna, &[], ""),
+    ("1.4.14", "suptiGantaM padam", Samjna, &[], ""),
+    ("1.4.99", "laH parasmaipadam", Samjna, &[], ""),
+    ("1.4.100", "taGAnAv Atmanepadam", Samjna, &[], ""),
+    ("1.4.109", "paraH saMnikarSaH saMhitA", Samjna, &[], ""),
+    ("1.4.110", "virAmo 'vasAnam", Samjna, &[], ""),
+    ("2.1.3", "prAk kaDArAt samAsaH", Adhikara, &[], "2.2.38"),
+    ("3.1.1", "pratyayaH", Adhikara, &[], "5.4.160"),
+    ("3.1.2", "paraz ca", Adhikara, &[], "5.4.160"),
+    ("3.1.3", "AdyudAttaz ca", Adhikara, &[], "5.4.160"),
+    ("3.1.67", "sArvadhAtuke yak", Vidhi, &[], ""),
+    ("3.1.68", "kartari zap", Vidhi, &[("sArvadhAtuke", "3.1.67")], ""),
+    ("3.1.69", "divAdibhyaH zyan", Vidhi, &[("sArvadhAtuke", "3.1.67"), ("kartari", "3.1.68")], ""),
+    ("3.1.73", "svAdibhyaH znuH", Vidhi, &[("sArvadhAtuke", "3.1.67"), ("kartari", "3.1.68")], ""),
+    ("3.1.77", "tudAdibhyaH zaH", Vidhi, &[("sArvadhAtuke", "3.1.67"), ("kartari", "3.1.68")], ""),
+    ("3.1.78", "rudhAdibhyaH znam", Vidhi, &[("sArvadhAtuke", "3.1.67"), ("kartari", "3.1.68")], ""),
+    ("3.1.79", "tanAdikRJbhya uH", Vidhi, &[("sArvadhAtuke", "3.1.67"), ("kartari", "3.1.68")], ""),
+    ("3.1.81", "kryAdibhyaH znA", Vidhi, &[("sArvadhAtuke", "3.1.67"), ("kartari", "3.1.68")], ""),
+    ("3.1.91", "dhAtoH", Adhikara, &[], "3.4.117"),
+    ("3.4.77", "lasya", Adhikara, &[], "3.4.117"),
+    ("3.4.78", "tiptasjhisipthasthamibvasmastAtAMjhathAsAthAMdhvamiDvahimahiG", Vidhi, &[], ""),
+    ("4.1.1", "GyAp prAtipadikAt", Adhikara, &[], "5.4.160"),
+    ("4.1.2", "svaujasamauTchaSTAbhyAmbhisGebhyAmbhyasGasibhyAmbhyasGasosAmGyossup", Vidhi, &[], ""),
+    ("4.1.3", "striyAm", Adhikara, &[], "4.1.81"),
+    ("4.1.4", "ajAdyataS TAp", Vidhi, &[], ""),
+    ("6.1.72", "saMhitAyAm", Adhikara, &[], "6.1.157"),
+    ("6.1.77", "iko yaN aci", Vidhi, &[], ""),
+    ("6.1.78", "eco 'yavAyAvaH", Vidhi, &[("aci", "6.1.77")], ""),
+    ("6.1.84", "ekaH pUrvaparayoH", Adhikara, &[], "6.1.111"),
+    ("6.1.87", "Ad guNaH", Vidhi, &[("aci", "6.1.77")], ""),
+    ("6.1.88", "vRddhir eci", Vidhi, &[("At", "6.1.87")], ""),
+    ("6.1.101", "akaH savarNe dIrghaH", Vidhi, &[("aci", "6.1.77")], ""),
+    ("6.4.1", "aGgasya", Adhikara, &[], "7.4.97"),
+    ("7.1.1", "yuvor anAkau", Vidhi, &[], ""),
+    ("7.3.82", "mider guNaH", Vidhi, &[], ""),
+    ("7.3.84", "sArvadhAtukArdhadhAtukayoH", Vidhi, &[("guNaH", "7.3.82")], ""),
+    ("8.1.16", "padasya", Adhikara, &[], "8.3.54"),
+    ("8.2.1", "pUrvatrAsiddham", Adhikara, &[], "8.4.68"),
+    ("8.2.7", "nalopaH prAtipadikAntasya", Vidhi, &[], ""),
+    ("8.2.66", "sasajuSo ruH", Vidhi, &[], ""),
+    ("8.3.55", "apadAntasya mUrdhanyaH", Adhikara, &[], "8.3.119"),
+    ("8.3.56", "saheH sADaH saH", Vidhi, &[], ""),
+    ("8.3.57", "iNkoH", Adhikara, &[], "8.3.119"),
+    ("8.3.59", "AdezapratyayayoH", Vidhi, &[("saH", "8.3.56")], ""),
+    ("8.4.1", "raSAbhyAM no NaH samAnapade", Vidhi, &[], ""),
+    ("8.4.2", "aTkupvAGnumvyavAye 'pi", Vidhi, &[("raSAbhyAm", "8.4.1"), ("naH", "8.4.1"), ("NaH", "8.4.1"), ("samAnapade", "8.4.1")], ""),
+    ("8.4.40", "stoH zcunA zcuH", Vidhi, &[], ""),
+    ("8.4.41", "STunA STuH", Vidhi, &[("stoH", "8.4.40")], ""),
+];
+
+/// Reads Harvard-Kyoto text into Aksharas, as one run with the spaces between
+/// words and the avagraha left out.
+fn aksharas(hk: &str) -> Vec<Akshara> {
+    let text: String = hk.chars().filter(|c| !c.is_whitespace() && *c != '\'').collect();
+    Lexer::with_scheme(&text, TransliterationScheme::HarvardKyoto).tokenize().into_iter().filter_map(|token| match token {
+        Token::Akshara(akshara, _) => Some(akshara),
+        Token::Unknown(..) => None,
+    }).collect()
+}
+
+fn number(s: &str) -> SutraNumber {
+    s.parse().unwrap_or_else(|_| panic!("bad sutra number {s:?} in the table"))
+}
+
+/// The sutras of the Ashtadhyayi known to the grammar, in order. This is a
+/// selection of some eighty sutras, not the whole text: the definitions,
+/// headings and rules the rest of the module draws on.
+pub fn ashtadhyayi() -> &'static [AshtadhyayiSutra] {
+    static ASHTADHYAYI: OnceLock<Vec<AshtadhyayiSutra>> = OnceLock::new();
+    ASHTADHYAYI.get_or_init(|| {
+        ENTRIES.iter().map(|&(n, text, kind, anuvritti, scope)| AshtadhyayiSutra {
+            number: number(n),
+            text: Sutra::new(aksharas(text)),
+            kind,
+            anuvritti: anuvritti.iter().map(|&(word, from)| Anuvritti { pada: Pada::new(aksharas(word)), from: number(from) }).collect(),
+            scope: (!scope.is_empty()).then(|| number(scope)),
+        }).collect()
+    })
+}
+
+/// Finds a sutra by its number. None for a number outside the selection,
+/// whether or not the Ashtadhyayi has a sutra there.
+pub fn sutra(number: SutraNumber) -> Option<&'static AshtadhyayiSutra> {
+    ashtadhyayi().iter().find(|s| s.number == number)
+}
+
+/// Finds the sutras whose text contains `query`, given in Harvard-Kyoto,
+/// IAST or Devanagari. Spaces and avagraha in the query are ignored, as the
+/// text is kept in continuous sandhi.
+pub fn search(query: &str) -> Vec<&'static AshtadhyayiSutra> {
+    let query: String = query.chars().filter(|c| !c.is_whitespace() && !matches!(c, '\'' | 'ऽ')).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    ashtadhyayi().iter().filter(|s| {
+        [TransliterationScheme::HarvardKyoto, TransliterationScheme::Iast, TransliterationScheme::Devanagari].into_iter()
+            .any(|scheme| s.render(scheme).contains(&query))
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let first = sutra(SutraNumber::new(1, 1, 1)).unwrap();
+        assert_eq!(first.render(TransliterationScheme::Iast), "vṛddhirādaic");
+        assert_eq!(first.kind, SutraType::Samjna);
+        assert_eq!(sutra("6.1.77".parse().unwrap()).unwrap().render(TransliterationScheme::HarvardKyoto), "ikoyaNaci");
+        assert_eq!(sutra(SutraNumber::new(1, 1, 6)).unwrap().render(TransliterationScheme::HarvardKyoto), "dIdhIvevITAm");
+        // The first pada ends at 1.1.75
+        assert!(sutra(SutraNumber::new(1, 1, 76)).is_none());
+        assert!(ashtadhyayi().windows(2).all(|pair| pair[0].number < pair[1].number));
+    }
+
+    #[test]
+    fn search_text() {
+        let numbers: Vec<String> = search("guNa").iter().map(|s| s.number.to_string()).collect();
+        assert_eq!(numbers, ["1.1.2", "1.1.3", "6.1.87", "7.3.82"]);
+        assert_eq!(search("savarNe dIrghaH")[0].number, SutraNumber::new(6, 1, 101));
+        assert_eq!(search("ṣṭunā")[0].number, SutraNumber::new(8, 4, 41));
+        assert!(search(" ").is_empty());
+    }
+
+    #[test]
+    fn expand() {
+        let stuna = sutra(SutraNumber::new(8, 4, 41)).unwrap();
+        assert_eq!(stuna.expand().to_string(), "8.4.41 ṣṭunāṣṭuḥ (stoḥ) [pūrvatrāsiddham]");
+        let owned = stuna.clone();
+        assert_eq!(owned.expand(), stuna.expand());
+        let zyan = sutra(SutraNumber::new(3, 1, 69)).unwrap().expand();
+        assert_eq!(zyan.to_string(), "3.1.69 divādibhyaḥśyan (sārvadhātuke kartari) [pratyayaḥ paraśca ādyudāttaśca]");
+        assert_eq!(zyan.anuvritti[1].from, SutraNumber::new(3, 1, 68));
+        let satva = sutra(SutraNumber::new(8, 3, 59)).unwrap().adhikaras();
+        assert_eq!(satva.iter().map(|s| s.number.to_string()).collect::<Vec<_>>(), ["8.2.1", "8.3.55", "8.3.57"]);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("8.4.68".parse::<SutraNumber>(), Ok(SutraNumber::new(8, 4, 68)));
+        assert!("9.1.1".parse::<SutraNumber>().is_err());
+        assert!("1.1".parse::<SutraNumber>().is_err());
+        assert_eq!("Atidesha".parse::<SutraType>(), Ok(SutraType::Atidesha));
+        assert_eq!(SutraType::Niyama.to_string(), "niyama");
+    }
+}
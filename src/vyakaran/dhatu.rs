@@ -0,0 +1,368 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use crate::shiksha::{lipi, ParseNameError, TransliterationScheme, Varna};
+use crate::vyakaran::varnas;
+
+/// The ten classes (gana) of the Dhatupatha, named after their first root.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Gana {
+    Bhvadi,    // 1, vikarana śap
+    Adadi,     // 2, śap elided
+    Juhotyadi, // 3, śap elided, the root reduplicated
+    Divadi,    // 4, vikarana śyan
+    Svadi,     // 5, vikarana śnu
+    Tudadi,    // 6, vikarana śa
+    Rudhadi,   // 7, vikarana śnam inside the root
+    Tanadi,    // 8, vikarana u
+    Kryadi,    // 9, vikarana śnā
+    Curadi,    // 10, ṇic before śap
+}
+
+impl Gana {
+    pub const ALL: [Gana; 10] = [
+        Gana::Bhvadi, Gana::Adadi, Gana::Juhotyadi, Gana::Divadi, Gana::Svadi,
+        Gana::Tudadi, Gana::Rudhadi, Gana::Tanadi, Gana::Kryadi, Gana::Curadi,
+    ];
+
+    /// Returns the number of the class, from 1 to 10.
+    pub fn number(self) -> u8 {
+        Gana::ALL.iter().position(|&g| g == self).unwrap_or(0) as u8 + 1
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Gana::Bhvadi => "bhvadi",
+            Gana::Adadi => "adadi",
+            Gana::Juhotyadi => "juhotyadi",
+            Gana::Divadi => "divadi",
+            Gana::Svadi => "svadi",
+            Gana::Tudadi => "tudadi",
+            Gana::Rudhadi => "rudhadi",
+            Gana::Tanadi => "tanadi",
+            Gana::Kryadi => "kryadi",
+            Gana::Curadi => "curadi",
+        }
+    }
+}
+
+impl fmt::Display for Gana {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a class in any letter case, or its number.
+impl FromStr for Gana {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Gana::ALL.into_iter().find(|g| g.name().eq_ignore_ascii_case(s) || g.number().to_string() == s).ok_or_else(|| ParseNameError::new("gana", s))
+    }
+}
+
+/// The endings a root takes: those of parasmaipada, of ātmanepada, or either.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Padi {
+    Parasmai,
+    Atmane,
+    Ubhaya,
+}
+
+impl Padi {
+    pub const ALL: [Padi; 3] = [Padi::Parasmai, Padi::Atmane, Padi::Ubhaya];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Padi::Parasmai => "parasmaipada",
+            Padi::Atmane => "atmanepada",
+            Padi::Ubhaya => "ubhayapada",
+        }
+    }
+}
+
+impl fmt::Display for Padi {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Padi {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Padi::ALL.into_iter().find(|p| p.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("pada", s))
+    }
+}
+
+/// Whether the augment iṭ is inserted before ārdhadhātuka suffixes
+/// beginning with a consonant other than y (7.2.35).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Idagama {
+    Set,  // Takes iṭ
+    Anit, // Does not (7.2.10)
+    Vet,  // Optionally takes it
+}
+
+impl Idagama {
+    pub const ALL: [Idagama; 3] = [Idagama::Set, Idagama::Anit, Idagama::Vet];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Idagama::Set => "set",
+            Idagama::Anit => "anit",
+            Idagama::Vet => "vet",
+        }
+    }
+}
+
+impl fmt::Display for Idagama {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Idagama {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Idagama::ALL.into_iter().find(|i| i.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("iḍāgama", s))
+    }
+}
+
+/// A verbal root (dhatu) as listed in the Dhatupatha.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Dhatu {
+    pub upadesha: Vec<Varna>,        // The root as taught, with its it-markers
+    pub root: Vec<Varna>,            // The root as used, without them
+    pub anubandhas: Vec<Vec<Varna>>, // The it-markers, in the order they occur
+    pub gana: Gana,
+    pub padi: Padi,
+    pub idagama: Idagama,
+    pub artha: String,               // The meaning given in the Dhatupatha, in IAST
+    pub gloss: &'static str,         // The meaning in English
+}
+
+impl Dhatu {
+    /// Returns the root written in the given scheme.
+    pub fn render(&self, scheme: TransliterationScheme) -> String {
+        lipi::render(&self.root, scheme)
+    }
+}
+
+/// Upadesha, root and it-markers in Harvard-Kyoto, gana, pada, iḍāgama,
+/// meaning in the Dhatupatha in Harvard-Kyoto, and meaning in English.
+/// Nasalized it-vowels are written without their nasalization.
+type Entry = (&'static str, &'static str, &'static [&'static str], Gana, Padi, Idagama, &'static str, &'static str);
+
+use Gana::*;
+use Idagama::*;
+use Padi::*;
+
+const ENTRIES: &[Entry] = &[
+    // Bhvadi
+    ("bhU", "bhU", &[], Bhvadi, Parasmai, Set, "sattAyAm", "be, become"),
+    ("edha", "edh", &["a"], Bhvadi, Atmane, Set, "vRddhau", "grow, prosper"),
+    ("paTha", "paTh", &["a"], Bhvadi, Parasmai, Set, "vyaktAyAM vAci", "read, recite"),
+    ("vada", "vad", &["a"], Bhvadi, Parasmai, Set, "vyaktAyAM vAci", "speak, say"),
+    ("gamlR", "gam", &["lR"], Bhvadi, Parasmai, Anit, "gatau", "go"),
+    ("DupacaS", "pac", &["Du", "a", "S"], Bhvadi, Ubhaya, Anit, "pAke", "cook"),
+    ("NIJ", "nI", &["J"], Bhvadi, Ubhaya, Anit, "prApaNe", "lead, carry"),
+    ("hRJ", "hR", &["J"], Bhvadi, Ubhaya, Anit, "haraNe", "take, carry away"),
+    ("ji", "ji", &[], Bhvadi, Parasmai, Anit, "jaye", "conquer, win"),
+    ("smR", "smR", &[], Bhvadi, Parasmai, Anit, "AdhyAne", "remember"),
+    ("SThA", "sthA", &[], Bhvadi, Parasmai, Anit, "gatinivRttau", "stand, stay"),
+    ("pA", "pA", &[], Bhvadi, Parasmai, Anit, "pAne", "drink"),
+    ("dRzir", "dRz", &["ir"], Bhvadi, Parasmai, Anit, "prekSaNe", "see"),
+    ("zru", "zru", &[], Bhvadi, Parasmai, Anit, "zravaNe", "hear"),
+    ("DulabhaS", "labh", &["Du", "a", "S"], Bhvadi, Atmane, Anit, "prAptau", "obtain"),
+    ("SevR", "sev", &["R"], Bhvadi, Atmane, Set, "sevane", "serve, attend"),
+    ("vRtu", "vRt", &["u"], Bhvadi, Atmane, Set, "vartane", "turn, exist"),
+    ("vRdhu", "vRdh", &["u"], Bhvadi, Atmane, Set, "vRddhau", "grow, increase"),
+    ("muda", "mud", &["a"], Bhvadi, Atmane, Set, "harSe", "rejoice"),
+    ("ramu", "ram", &["u"], Bhvadi, Atmane, Anit, "krIDAyAm", "play, delight"),
+    ("yaja", "yaj", &["a"], Bhvadi, Ubhaya, Anit, "devapUjAsaMgatikaraNadAneSu", "worship, sacrifice"),
+    ("vasa", "vas", &["a"], Bhvadi, Parasmai, Anit, "nivAse", "dwell"),
+    ("vaha", "vah", &["a"], Bhvadi, Ubhaya, Anit, "prApaNe", "carry, convey"),
+    ("bhaja", "bhaj", &["a"], Bhvadi, Ubhaya, Anit, "sevAyAm", "share, worship"),
+    ("tRR", "tRR", &[], Bhvadi, Parasmai, Set, "plavanataraNayoH", "cross over"),
+    ("SadlR", "sad", &["lR"], Bhvadi, Parasmai, Anit, "vizaraNagatyavasAdaneSu", "sit, sink"),
+    ("jIva", "jIv", &["a"], Bhvadi, Parasmai, Set, "prANadhAraNe", "live"),
+    ("cara", "car", &["a"], Bhvadi, Parasmai, Set, "gatibhakSaNayoH", "move, graze"),
+    ("patlR", "pat", &["lR"], Bhvadi, Parasmai, Set, "gatau", "fall, fly"),
+    ("kramu", "kram", &["u"], Bhvadi, Parasmai, Set, "pAdavikSepe", "step, stride"),
+    ("gai", "gai", &[], Bhvadi, Parasmai, Anit, "zabde", "sing"),
+    ("dhyai", "dhyai", &[], Bhvadi, Parasmai, Anit, "cintAyAm", "meditate, think of"),
+    ("sR", "sR", &[], Bhvadi, Parasmai, Anit, "gatau", "run, flow"),
+    ("khAda", "khAd", &["a"], Bhvadi, Parasmai, Set, "bhakSaNe", "eat, chew"),
+    ("zuca", "zuc", &["a"], Bhvadi, Parasmai, Set, "zoke", "grieve"),
+    ("rakSa", "rakS", &["a"], Bhvadi, Parasmai, Set, "pAlane", "protect"),
+    ("Tunadi", "nand", &["Tu", "i"], Bhvadi, Parasmai, Set, "samRddhau", "rejoice"),
+    ("bhASa", "bhAS", &["a"], Bhvadi, Atmane, Set, "vyaktAyAM vAci", "speak"),
+    ("IkSa", "IkS", &["a"], Bhvadi, Atmane, Set, "darzane", "see, look"),
+    ("yatI", "yat", &["I"], Bhvadi, Atmane, Set, "prayatne", "strive"),
+    ("Saha", "sah", &["a"], Bhvadi, Atmane, Set, "marSaNe", "endure"),
+    ("ruha", "ruh", &["a"], Bhvadi, Parasmai, Anit, "bIjajanmani prAdurbhAve ca", "grow, ascend"),
+    // Adadi
+    ("ada", "ad", &["a"], Adadi, Parasmai, Anit, "bhakSaNe", "eat"),
+    ("asa", "as", &["a"], Adadi, Parasmai, Set, "bhuvi", "be, exist"),
+    ("hana", "han", &["a"], Adadi, Parasmai, Anit, "hiMsAgatyoH", "strike, kill"),
+    ("iN", "i", &["N"], Adadi, Parasmai, Anit, "gatau", "go"),
+    ("vida", "vid", &["a"], Adadi, Parasmai, Set, "jJAne", "know"),
+    ("brUJ", "brU", &["J"], Adadi, Ubhaya, Set, "vyaktAyAM vAci", "speak, tell"),
+    ("zIG", "zI", &["G"], Adadi, Atmane, Set, "svapne", "lie down"),
+    ("Asa", "As", &["a"], Adadi, Atmane, Set, "upavezane", "sit"),
+    ("dviSa", "dviS", &["a"], Adadi, Ubhaya, Anit, "aprItau", "hate"),
+    ("duha", "duh", &["a"], Adadi, Ubhaya, Anit, "prapUraNe", "milk"),
+    ("liha", "lih", &["a"], Adadi, Ubhaya, Anit, "AsvAdane", "lick"),
+    ("yA", "yA", &[], Adadi, Parasmai, Anit, "prApaNe", "go"),
+    ("JiSvapa", "svap", &["Ji", "a"], Adadi, Parasmai, Anit, "zaye", "sleep"),
+    ("rudir", "rud", &["ir"], Adadi, Parasmai, Set, "azruvimocane", "weep"),
+    ("STuJ", "stu", &["J"], Adadi, Ubhaya, Anit, "stutau", "praise"),
+    ("vaca", "vac", &["a"], Adadi, Parasmai, Anit, "paribhASaNe", "speak"),
+    ("jAgR", "jAgR", &[], Adadi, Parasmai, Set, "nidrAkSaye", "be awake"),
+    ("zAsu", "zAs", &["u"], Adadi, Parasmai, Set, "anuziSTau", "teach, rule"),
+    // Juhotyadi
+    ("hu", "hu", &[], Juhotyadi, Parasmai, Anit, "dAnAdanayoH", "offer, sacrifice"),
+    ("DudAJ", "dA", &["Du", "J"], Juhotyadi, Ubhaya, Anit, "dAne", "give"),
+    ("DudhAJ", "dhA", &["Du", "J"], Juhotyadi, Ubhaya, Anit, "dhAraNapoSaNayoH", "put, hold"),
+    ("DubhRJ", "bhR", &["Du", "J"], Juhotyadi, Ubhaya, Anit, "dhAraNapoSaNayoH", "bear, support"),
+    ("ohAk", "hA", &["o", "k"], Juhotyadi, Parasmai, Anit, "tyAge", "leave, abandon"),
+    ("JibhI", "bhI", &["Ji"], Juhotyadi, Parasmai, Anit, "bhaye", "fear"),
+    ("mAG", "mA", &["G"], Juhotyadi, Atmane, Anit, "mAne zabde ca", "measure"),
+    ("hrI", "hrI", &[], Juhotyadi, Parasmai, Anit, "lajjAyAm", "be ashamed"),
+    // Divadi
+    ("divu", "div", &["u"], Divadi, Parasmai, Set, "krIDAvijigISAvyavahAradyutistutimodamadasvapnakAntigatiSu", "play, shine"),
+    ("nRtI", "nRt", &["I"], Divadi, Parasmai, Set, "gAtravikSepe", "dance"),
+    ("Naza", "naz", &["a"], Divadi, Parasmai, Vet, "adarzane", "perish, vanish"),
+    ("janI", "jan", &["I"], Divadi, Atmane, Set, "prAdurbhAve", "be born"),
+    ("mana", "man", &["a"], Divadi, Atmane, Anit, "jJAne", "think"),
+    ("yudha", "yudh", &["a"], Divadi, Atmane, Anit, "samprahAre", "fight"),
+    ("budha", "budh", &["a"], Divadi, Atmane, Anit, "avagamane", "understand, awake"),
+    ("kupa", "kup", &["a"], Divadi, Parasmai, Set, "krodhe", "be angry"),
+    ("muha", "muh", &["a"], Divadi, Parasmai, Vet, "vaicittye", "be bewildered"),
+    ("tuSa", "tuS", &["a"], Divadi, Parasmai, Anit, "prItau", "be pleased"),
+    ("Sidhu", "sidh", &["u"], Divadi, Parasmai, Anit, "saMrAddhau", "succeed"),
+    ("zamu", "zam", &["u"], Divadi, Parasmai, Set, "upazame", "be calm"),
+    ("pada", "pad", &["a"], Divadi, Atmane, Anit, "gatau", "go, fall"),
+    ("vyadha", "vyadh", &["a"], Divadi, Parasmai, Anit, "tADane", "pierce"),
+    // Svadi
+    ("SuJ", "su", &["J"], Svadi, Ubhaya, Anit, "abhiSave", "press out"),
+    ("ciJ", "ci", &["J"], Svadi, Ubhaya, Anit, "cayane", "gather"),
+    ("AplR", "Ap", &["lR"], Svadi, Parasmai, Anit, "vyAptau", "obtain, reach"),
+    ("zaklR", "zak", &["lR"], Svadi, Parasmai, Anit, "zaktau", "be able"),
+    ("vRJ", "vR", &["J"], Svadi, Ubhaya, Set, "varaNe", "choose, cover"),
+    // Tudadi
+    ("tuda", "tud", &["a"], Tudadi, Ubhaya, Anit, "vyathane", "strike, wound"),
+    ("likha", "likh", &["a"], Tudadi, Parasmai, Set, "akSaravinyAse", "write"),
+    ("viza", "viz", &["a"], Tudadi, Parasmai, Anit, "pravezane", "enter"),
+    ("iSu", "iS", &["u"], Tudadi, Parasmai, Set, "icchAyAm", "wish"),
+    ("sRja", "sRj", &["a"], Tudadi, Parasmai, Anit, "visarge", "release, create"),
+    ("spRza", "spRz", &["a"], Tudadi, Parasmai, Anit, "saMsparzane", "touch"),
+    ("kSipa", "kSip", &["a"], Tudadi, Ubhaya, Anit, "preraNe", "throw"),
+    ("pracha", "prach", &["a"], Tudadi, Parasmai, Anit, "jJIpsAyAm", "ask"),
+    ("muclR", "muc", &["lR"], Tudadi, Ubhaya, Anit, "mokSaNe", "release"),
+    ("Sica", "sic", &["a"], Tudadi, Ubhaya, Anit, "kSaraNe", "sprinkle"),
+    ("mRG", "mR", &["G"], Tudadi, Atmane, Anit, "prANatyAge", "die"),
+    // Rudhadi
+    ("rudhir", "rudh", &["ir"], Rudhadi, Ubhaya, Anit, "AvaraNe", "obstruct"),
+    ("bhidir", "bhid", &["ir"], Rudhadi, Ubhaya, Anit, "vidAraNe", "split"),
+    ("chidir", "chid", &["ir"], Rudhadi, Ubhaya, Anit, "dvaidhIkaraNe", "cut"),
+    ("yujir", "yuj", &["ir"], Rudhadi, Ubhaya, Anit, "yoge", "join, yoke"),
+    ("bhuja", "bhuj", &["a"], Rudhadi, Parasmai, Anit, "pAlanAbhyavahArayoH", "protect, eat"),
+    ("bhaJjo", "bhaJj", &["o"], Rudhadi, Parasmai, Anit, "Amardane", "break"),
+    // Tanadi
+    ("tanu", "tan", &["u"], Tanadi, Ubhaya, Set, "vistAre", "stretch, spread"),
+    ("DukRJ", "kR", &["Du", "J"], Tanadi, Ubhaya, Anit, "karaNe", "do, make"),
+    ("manu", "man", &["u"], Tanadi, Atmane, Set, "avabodhane", "think, consider"),
+    // Kryadi
+    ("DukrIJ", "krI", &["Du", "J"], Kryadi, Ubhaya, Anit, "dravyavinimaye", "buy"),
+    ("jJA", "jJA", &[], Kryadi, Parasmai, Anit, "avabodhane", "know"),
+    ("graha", "grah", &["a"], Kryadi, Ubhaya, Set, "upAdAne", "seize, take"),
+    ("bandha", "bandh", &["a"], Kryadi, Parasmai, Anit, "bandhane", "bind"),
+    ("prIJ", "prI", &["J"], Kryadi, Ubhaya, Anit, "tarpaNe kAntau ca", "please, love"),
+    ("pUJ", "pU", &["J"], Kryadi, Ubhaya, Set, "pavane", "purify"),
+    ("aza", "az", &["a"], Kryadi, Parasmai, Set, "bhojane", "eat"),
+    ("lUJ", "lU", &["J"], Kryadi, Ubhaya, Set, "chedane", "cut, reap"),
+    // Curadi
+    ("cura", "cur", &["a"], Curadi, Ubhaya, Set, "steye", "steal"),
+    ("citi", "cint", &["i"], Curadi, Ubhaya, Set, "smRtyAm", "think"),
+    ("katha", "kath", &["a"], Curadi, Ubhaya, Set, "vAkyaprabandhe", "tell, narrate"),
+    ("gaNa", "gaN", &["a"], Curadi, Ubhaya, Set, "saMkhyAne", "count"),
+    ("pAla", "pAl", &["a"], Curadi, Ubhaya, Set, "rakSaNe", "protect"),
+    ("bhakSa", "bhakS", &["a"], Curadi, Ubhaya, Set, "adane", "eat"),
+    ("pUja", "pUj", &["a"], Curadi, Ubhaya, Set, "pUjAyAm", "honour, worship"),
+    ("kSala", "kSal", &["a"], Curadi, Ubhaya, Set, "zaucakarmaNi", "wash"),
+];
+
+/// The roots of the Dhatupatha known to the grammar: a selection of the
+/// commonest roots of each class, in the order of the Dhatupatha.
+pub fn dhatupatha() -> &'static [Dhatu] {
+    static DHATUPATHA: OnceLock<Vec<Dhatu>> = OnceLock::new();
+    DHATUPATHA.get_or_init(|| {
+        ENTRIES.iter().map(|&(upadesha, root, anubandhas, gana, padi, idagama, artha, gloss)| Dhatu {
+            upadesha: varnas(upadesha),
+            root: varnas(root),
+            anubandhas: anubandhas.iter().map(|it| varnas(it)).collect(),
+            gana,
+            padi,
+            idagama,
+            artha: artha.split(' ').map(|word| lipi::render(&varnas(word), TransliterationScheme::Iast)).collect::<Vec<_>>().join(" "),
+            gloss,
+        }).collect()
+    })
+}
+
+/// Finds the roots spelled `root`, with or without their it-markers. A root
+/// taught in several classes or with several meanings is found once for each.
+pub fn find(root: &[Varna]) -> Vec<&'static Dhatu> {
+    dhatupatha().iter().filter(|d| d.root == root || d.upadesha == root).collect()
+}
+
+/// Lists the roots of a class.
+pub fn in_gana(gana: Gana) -> Vec<&'static Dhatu> {
+    dhatupatha().iter().filter(|d| d.gana == gana).collect()
+}
+
+/// Finds the roots whose meaning, in English or as given in the
+/// Dhatupatha in IAST, contains `query`, in any letter case.
+pub fn by_meaning(query: &str) -> Vec<&'static Dhatu> {
+    let query = query.to_lowercase();
+    dhatupatha().iter().filter(|d| d.gloss.to_lowercase().contains(&query) || d.artha.to_lowercase().contains(&query)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let bhu = find(&varnas("bhU"));
+        assert_eq!(bhu.len(), 1);
+        assert_eq!((bhu[0].gana, bhu[0].padi, bhu[0].idagama, bhu[0].artha.as_str()), (Bhvadi, Parasmai, Set, "sattāyām"));
+
+        let kr = find(&varnas("DukRJ"));
+        assert_eq!(kr[0].render(TransliterationScheme::Iast), "kṛ");
+        assert_eq!(kr[0].anubandhas, [varnas("Du"), varnas("J")]);
+
+        let man: Vec<Gana> = find(&varnas("man")).iter().map(|d| d.gana).collect();
+        assert_eq!(man, [Divadi, Tanadi]);
+    }
+
+    #[test]
+    fn search() {
+        assert_eq!(in_gana(Curadi).len(), 8);
+        assert!(in_gana(Svadi).iter().all(|d| d.gana.number() == 5));
+        let speak: Vec<String> = by_meaning("vyaktāyāṃ").iter().map(|d| d.render(TransliterationScheme::HarvardKyoto)).collect();
+        assert_eq!(speak, ["paTh", "vad", "bhAS", "brU"]);
+        assert_eq!(by_meaning("Be Born")[0].render(TransliterationScheme::HarvardKyoto), "jan");
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("7".parse::<Gana>(), Ok(Rudhadi));
+        assert_eq!("Tudadi".parse::<Gana>(), Ok(Tudadi));
+        assert_eq!("atmanepada".parse::<Padi>(), Ok(Atmane));
+        assert!("seT".parse::<Idagama>().is_ok());
+    }
+}
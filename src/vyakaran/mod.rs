@@ -1 +1,33 @@
-pub mod vyakaran;
+// Paninian grammar (Vyakarana).
+pub mod sandhi;
+pub mod dhatu;
+pub mod tinanta;
+pub mod subanta;
+pub mod vishleshana;
+pub mod ashtadhyayi;
+mod prakriya;
+
+use crate::shiksha::{Varna, VarnaMap};
+
+pub use sandhi::{join, split, Sandhi, Split};
+pub use dhatu::{dhatupatha, find, in_gana, by_meaning, Dhatu, Gana, Padi, Idagama};
+pub use tinanta::{tinanta, conjugate, Lakara, Purusha, Vacana, Tinanta};
+pub use subanta::{subanta, decline, pratipadikas, Vibhakti, Linga, Subanta, Pratipadika};
+pub use vishleshana::{analyze, Analysis};
+pub use ashtadhyayi::{ashtadhyayi, sutra, search, AshtadhyayiSutra, SutraNumber, SutraType, Anuvritti, ExpandedSutra};
+
+/// Reads Harvard-Kyoto spelling into Varnas, taking the longest spelling at
+/// each point. Used for the grammar's own tables, which are all spelled in HK.
+pub(crate) fn varnas(hk: &str) -> Vec<Varna> {
+    let mut varnas = Vec::new();
+    let mut rest = hk;
+    while !rest.is_empty() {
+        let (varna, length) = (1..=rest.len().min(3)).rev()
+            .filter(|&n| rest.is_char_boundary(n))
+            .find_map(|n| VarnaMap::get(&rest[..n]).map(|v| (v, n)))
+            .unwrap_or_else(|| panic!("no Varna spelled at the start of {rest:?}"));
+        varnas.push(varna);
+        rest = &rest[length..];
+    }
+    varnas
+}
@@ -0,0 +1,406 @@
+// Internal sandhi: the sound changes inside a word as it is derived from a
+// root or stem and its suffixes. Words are handled as the Harvard-Kyoto
+// spellings of their Varnas, so the rules can match them as slices.
+use crate::niruktam::{Lexer, Token};
+use crate::shiksha::{lipi, Pada, TransliterationScheme, VarnaMap};
+use crate::vyakaran::varnas;
+
+/// A word being derived, as the Harvard-Kyoto spellings of its Varnas.
+pub(crate) type Word = Vec<&'static str>;
+
+/// Reads a word spelled in Harvard-Kyoto.
+pub(crate) fn word(hk: &str) -> Word {
+    varnas(hk).iter().map(|v| v.hk).collect()
+}
+
+/// Builds the Pada of a finished word, with its Aksharas as the lexer would read them.
+pub(crate) fn pada(word: &[&str]) -> Pada {
+    let varnas: Vec<_> = word.iter().filter_map(|hk| VarnaMap::get(hk)).collect();
+    let text = lipi::render(&varnas, TransliterationScheme::Devanagari);
+    let aksharas = Lexer::with_scheme(&text, TransliterationScheme::Devanagari).tokenize().into_iter().filter_map(|token| match token {
+        Token::Akshara(akshara, _) => Some(akshara),
+        Token::Unknown(..) => None,
+    }).collect();
+    Pada::new(aksharas)
+}
+
+pub(crate) fn is_vowel(hk: &str) -> bool {
+    matches!(hk, "a" | "A" | "i" | "I" | "u" | "U" | "R" | "RR" | "lR" | "lRR" | "e" | "ai" | "o" | "au")
+}
+
+pub(crate) fn is_consonant(hk: &str) -> bool {
+    !is_vowel(hk) && !matches!(hk, "M" | "H")
+}
+
+/// Stops other than nasals, sibilants and h (the pratyahara jhal).
+pub(crate) fn is_jhal(hk: &str) -> bool {
+    is_consonant(hk) && !matches!(hk, "G" | "J" | "N" | "n" | "m" | "y" | "r" | "l" | "v")
+}
+
+pub(crate) fn is_short(hk: &str) -> bool {
+    matches!(hk, "a" | "i" | "u" | "R" | "lR")
+}
+
+pub(crate) fn long(hk: &'static str) -> &'static str {
+    match hk {
+        "a" => "A",
+        "i" => "I",
+        "u" => "U",
+        "R" => "RR",
+        "lR" => "lRR",
+        _ => hk,
+    }
+}
+
+pub(crate) fn short(hk: &'static str) -> &'static str {
+    match hk {
+        "A" => "a",
+        "I" | "e" | "ai" => "i",
+        "U" | "o" | "au" => "u",
+        "RR" => "R",
+        "lRR" => "lR",
+        _ => hk,
+    }
+}
+
+/// The savarna group of a vowel, named by its short form.
+fn group(hk: &str) -> Option<&'static str> {
+    match hk {
+        "a" | "A" => Some("a"),
+        "i" | "I" => Some("i"),
+        "u" | "U" => Some("u"),
+        "R" | "RR" => Some("R"),
+        "lR" | "lRR" => Some("lR"),
+        _ => None,
+    }
+}
+
+/// The guna grade of a vowel (1.1.2); other Varnas are unchanged.
+pub(crate) fn guna(hk: &'static str) -> Word {
+    match hk {
+        "i" | "I" => vec!["e"],
+        "u" | "U" => vec!["o"],
+        "R" | "RR" => vec!["a", "r"],
+        "lR" | "lRR" => vec!["a", "l"],
+        _ => vec![hk],
+    }
+}
+
+/// The vrddhi grade of a vowel (1.1.1); other Varnas are unchanged.
+pub(crate) fn vrddhi(hk: &'static str) -> Word {
+    match hk {
+        "a" | "A" => vec!["A"],
+        "i" | "I" | "e" | "ai" => vec!["ai"],
+        "u" | "U" | "o" | "au" => vec!["au"],
+        "R" | "RR" => vec!["A", "r"],
+        "lR" | "lRR" => vec!["A", "l"],
+        _ => vec![hk],
+    }
+}
+
+/// Index of the last vowel of a word.
+pub(crate) fn last_vowel(word: &[&str]) -> Option<usize> {
+    word.iter().rposition(|hk| is_vowel(hk))
+}
+
+/// Raises the last vowel of a root by `grade` where the root allows it: a
+/// final vowel, or a short vowel before a single final consonant (7.3.84, 7.3.86).
+pub(crate) fn strengthen(root: &[&'static str], grade: fn(&'static str) -> Word) -> Word {
+    let Some(i) = last_vowel(root) else { return root.to_vec() };
+    if i + 1 == root.len() || (i + 2 == root.len() && is_short(root[i])) {
+        [&root[..i], &grade(root[i])[..], &root[i + 1..]].concat()
+    } else {
+        root.to_vec()
+    }
+}
+
+/// The vowel of the augment aṭ or āṭ before a verb (6.4.71, 6.4.72).
+pub(crate) fn augment(word: &[&'static str]) -> Word {
+    match word.first() {
+        Some(&first) if is_vowel(first) => [&vrddhi(first)[..], &word[1..]].concat(),
+        _ => [&["a"][..], word].concat(),
+    }
+}
+
+/// The unaspirated form of a stop, and the voiceless unaspirated one.
+fn deaspirate(hk: &'static str) -> &'static str {
+    match hk {
+        "kh" => "k",
+        "gh" => "g",
+        "ch" => "c",
+        "jh" => "j",
+        "Th" => "T",
+        "Dh" => "D",
+        "th" => "t",
+        "dh" => "d",
+        "ph" => "p",
+        "bh" => "b",
+        _ => hk,
+    }
+}
+
+fn voiceless(hk: &'static str) -> &'static str {
+    match deaspirate(hk) {
+        "g" => "k",
+        "j" => "c",
+        "D" => "T",
+        "d" => "t",
+        "b" => "p",
+        hk => hk,
+    }
+}
+
+pub(crate) fn voiced(hk: &'static str) -> &'static str {
+    match deaspirate(hk) {
+        "k" => "g",
+        "c" => "j",
+        "T" => "D",
+        "t" => "d",
+        "p" => "b",
+        hk => hk,
+    }
+}
+
+fn aspirate(hk: &'static str) -> &'static str {
+    match hk {
+        "g" => "gh",
+        "j" => "jh",
+        "D" => "Dh",
+        "d" => "dh",
+        "b" => "bh",
+        _ => hk,
+    }
+}
+
+/// The nasal of the class of a stop.
+pub(crate) fn nasal_of(hk: &str) -> &'static str {
+    match hk {
+        "k" | "kh" | "g" | "gh" => "G",
+        "c" | "ch" | "j" | "jh" => "J",
+        "T" | "Th" | "D" | "Dh" => "N",
+        "t" | "th" | "d" | "dh" => "n",
+        "p" | "ph" | "b" | "bh" => "m",
+        _ => "M",
+    }
+}
+
+/// Gives the aspiration lost by a final aspirate to the first consonant of
+/// its syllable, if that is b, g, D, d or j (8.2.37).
+fn bhas(word: &mut [&'static str], end: usize) {
+    let Some(vowel) = word[..end].iter().rposition(|hk| is_vowel(hk)) else { return };
+    if vowel > 0 && is_consonant(word[vowel - 1]) {
+        word[vowel - 1] = aspirate(word[vowel - 1]);
+    }
+}
+
+/// Whether the final j or ś of a root becomes ṣ rather than k before
+/// consonants: yaj, sṛj, mṛj, rāj, bhrāj and the like (8.2.36).
+fn vrasca(stem: &[&str]) -> bool {
+    matches!(stem, [.., "y", "a", "j"] | [.., "r", "a", "j"] | [.., "r", "A", "j"] | [.., "R", "j"] | [.., "z"] | [.., "ch"])
+}
+
+/// Whether a root ending in h begins with d, so that its h acts as gh (8.2.32).
+fn dadi(stem: &[&str]) -> bool {
+    let start = last_vowel(stem).map_or(0, |v| stem[..v].iter().rposition(|hk| !is_consonant(hk)).map_or(0, |i| i + 1));
+    stem.get(start) == Some(&"d")
+}
+
+/// Joins a stem and a suffix with the sandhi of their junction: vowel
+/// sandhi, the treatment of stops and sibilants before consonants, ṣatva of
+/// the suffix's s (8.3.59) and ṣṭutva after it.
+pub(crate) fn attach(stem: &[&'static str], suffix: &[&'static str]) -> Word {
+    let (Some(&last), Some(&next)) = (stem.last(), suffix.first()) else { return [stem, suffix].concat() };
+    let mut head: Word = stem[..stem.len() - 1].to_vec();
+    let mut tail: Word = suffix.to_vec();
+
+    if is_vowel(last) && is_vowel(next) {
+        let conjunct = head.len() >= 2 && is_consonant(head[head.len() - 1]) && is_consonant(head[head.len() - 2]);
+        let monosyllable = head.iter().all(|hk| !is_vowel(hk)) && !head.is_empty();
+        match (last, next) {
+            _ if group(last).is_some() && group(last) == group(next) => { tail[0] = long(group(last).unwrap_or(last)); }
+            ("a" | "A", "i" | "I") => tail[0] = "e",
+            ("a" | "A", "u" | "U") => tail[0] = "o",
+            ("a" | "A", "e" | "ai") => tail[0] = "ai",
+            ("a" | "A", "o" | "au") => tail[0] = "au",
+            ("a" | "A", _) => head.push(last),
+            ("i" | "I", _) if conjunct || monosyllable => head.extend([short(last), "y"]),
+            ("i" | "I", _) => head.push("y"),
+            ("u" | "U", _) if conjunct || monosyllable => head.extend([short(last), "v"]),
+            ("u" | "U", _) => head.push("v"),
+            ("R" | "RR", _) => head.push("r"),
+            ("e", _) => head.extend(["a", "y"]),
+            ("o", _) => head.extend(["a", "v"]),
+            ("ai", _) => head.extend(["A", "y"]),
+            ("au", _) => head.extend(["A", "v"]),
+            _ => head.push(last),
+        }
+        return [head, tail].concat();
+    }
+
+    if is_consonant(last) && is_consonant(next) {
+        let mut last = last;
+        match next {
+            "t" | "th" => {
+                match last {
+                    "gh" | "jh" | "Dh" | "dh" | "bh" => { last = deaspirate(last); tail[0] = "dh"; }
+                    "h" if dadi(stem) => { last = "g"; tail[0] = "dh"; }
+                    "h" => {
+                        // ho ḍhaḥ, then ḍho ḍhe lopaḥ and the lengthening before it (8.2.31, 8.3.13, 6.3.111)
+                        tail[0] = "Dh";
+                        lengthen(&mut head);
+                        return [head, tail].concat();
+                    }
+                    "j" | "z" | "ch" if vrasca(stem) => last = "S",
+                    "c" | "j" | "ch" => last = "k",
+                    "z" => last = "S",
+                    _ => last = voiceless(last),
+                }
+            }
+            "dh" => match last {
+                "h" if dadi(stem) => last = "g",
+                "h" => {
+                    tail[0] = "Dh";
+                    lengthen(&mut head);
+                    return [head, tail].concat();
+                }
+                "s" => return [head, tail].concat(), // dhi ca (8.2.25)
+                "j" | "z" | "ch" if vrasca(stem) => last = "D",
+                "S" | "z" => last = "D",
+                "c" | "j" | "ch" | "k" | "g" => last = "g",
+                "gh" | "jh" | "Dh" | "dh" | "bh" => {
+                    last = deaspirate(last);
+                    if suffix.get(1) == Some(&"v") {
+                        head.push(last);
+                        let end = head.len() - 1;
+                        bhas(&mut head, end);
+                        return finish_junction([head, tail].concat(), stem.len() - 1);
+                    }
+                }
+                _ => last = voiced(if last == "ch" { "c" } else { last }),
+            },
+            "s" => match last {
+                "c" | "j" | "ch" | "z" | "S" | "k" | "kh" | "g" | "Dh" | "D" | "T" => last = "k",
+                "h" | "gh" => {
+                    head.push("k");
+                    if dadi(stem) || last == "gh" {
+                        let end = head.len() - 1;
+                        bhas(&mut head, end);
+                    }
+                    tail[0] = "S";
+                    return [head, tail].concat();
+                }
+                "jh" | "dh" | "bh" => {
+                    head.push(voiceless(last));
+                    let end = head.len() - 1;
+                    bhas(&mut head, end);
+                    return [head, tail].concat();
+                }
+                "t" | "th" | "d" => last = "t",
+                "p" | "ph" | "b" => last = "p",
+                "n" | "m" => last = "M",
+                _ => {}
+            },
+            "m" | "v" | "y" | "n" => {}
+            _ => {}
+        }
+        head.push(last);
+        return finish_junction([head, tail].concat(), stem.len() - 1);
+    }
+
+    finish_junction([stem, suffix].concat(), stem.len() - 1)
+}
+
+/// Lengthens the vowel before a ḍh that is dropped (6.3.111); the a of vah
+/// and sah becomes o (6.3.112).
+fn lengthen(head: &mut Word) {
+    let n = head.len();
+    match head.as_slice() {
+        [.., "v" | "s", "a" | "A"] => head[n - 1] = "o",
+        [.., vowel] if is_vowel(vowel) => head[n - 1] = long(head[n - 1]),
+        _ => {}
+    }
+}
+
+/// Applies the rules that follow a junction: a nasal before a stop takes its
+/// class, ṣatva of an s after i, u, ṛ, the diphthongs or k, and ṣṭutva.
+fn finish_junction(mut word: Word, at: usize) -> Word {
+    for i in at.saturating_sub(1)..=at {
+        if i + 1 < word.len() && matches!(word[i], "n" | "m" | "J" | "G" | "N" | "M") && is_consonant(word[i + 1]) && !matches!(word[i + 1], "y" | "r" | "l" | "v" | "m" | "n") {
+            word[i] = if matches!(word[i + 1], "z" | "S" | "s" | "h") { "M" } else { nasal_of(word[i + 1]) };
+        }
+    }
+    if at + 1 < word.len() && word[at + 1] == "s" && at + 2 < word.len() {
+        let before = word[at];
+        if (is_vowel(before) && !matches!(before, "a" | "A")) || matches!(before, "k" | "r" | "l") {
+            word[at + 1] = "S";
+        }
+    }
+    for i in at..word.len().saturating_sub(1) {
+        if matches!(word[i], "S" | "T" | "Th" | "D" | "Dh") {
+            word[i + 1] = match word[i + 1] {
+                "t" => "T",
+                "th" => "Th",
+                "d" => "D",
+                "dh" => "Dh",
+                "n" => "N",
+                hk => hk,
+            };
+        }
+        if i > at { break; }
+    }
+    word
+}
+
+/// Finishes a word: ṇatva (8.4.1, 8.4.2) and the treatment of its final
+/// consonant: a final cluster keeps its first consonant, stops lose voice and
+/// aspiration, and s and r become visarga.
+pub(crate) fn finish(mut word: Word) -> Word {
+    // Final consonants
+    while word.len() >= 2 && is_consonant(word[word.len() - 1]) && is_consonant(word[word.len() - 2]) {
+        word.pop();
+    }
+    if let Some(&last) = word.last() {
+        let end = word.len() - 1;
+        match last {
+            "s" | "r" => word[end] = "H",
+            "h" if dadi(&word) => { word[end] = "k"; bhas(&mut word, end); }
+            "h" | "S" | "ch" | "Dh" | "D" => word[end] = "T",
+            "z" | "j" if vrasca(&word) => word[end] = "T",
+            "j" | "z" | "c" | "g" | "gh" | "kh" => {
+                if matches!(last, "gh") { bhas(&mut word, end); }
+                word[end] = "k";
+            }
+            "d" | "dh" | "th" => {
+                if last == "dh" { bhas(&mut word, end); }
+                word[end] = "t";
+            }
+            "b" | "bh" | "ph" => {
+                if last == "bh" { bhas(&mut word, end); }
+                word[end] = "p";
+            }
+            _ => {}
+        }
+    }
+
+    // che ca: ch is doubled after a short vowel (6.1.73)
+    let mut i = 1;
+    while i < word.len() {
+        if word[i] == "ch" && is_short(word[i - 1]) {
+            word.insert(i, "c");
+            i += 1;
+        }
+        i += 1;
+    }
+
+    // Natva: n becomes ṇ after r, ṛ or ṣ, across vowels, semivowels, h, velars, labials and anusvara
+    let mut trigger = false;
+    for i in 0..word.len() {
+        match word[i] {
+            "r" | "R" | "RR" | "S" => trigger = true,
+            "n" if trigger && i + 1 < word.len() && (is_vowel(word[i + 1]) || matches!(word[i + 1], "n" | "m" | "y" | "v")) => word[i] = "N",
+            hk if is_vowel(hk) || matches!(hk, "h" | "y" | "v" | "k" | "kh" | "g" | "gh" | "G" | "p" | "ph" | "b" | "bh" | "m" | "M") => {}
+            _ => trigger = false,
+        }
+    }
+    word
+}
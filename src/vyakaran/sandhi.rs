@@ -0,0 +1,341 @@
+use std::sync::OnceLock;
+use crate::shiksha::{Varna, VarnaMap};
+use crate::vyakaran::varnas;
+
+/// The result of joining two words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandhi {
+    pub varnas: Vec<Varna>,         // The joined words, written as one unless there is a gap
+    pub gap: Option<usize>,         // Where the words stay apart, as an index into `varnas`
+    pub rule: Option<&'static str>, // The rule applied at the junction, if any
+}
+
+impl Sandhi {
+    /// Returns the joined text as it is written: one word, or two where a
+    /// lopa leaves two vowels side by side (8.3.19, 8.3.22) or an r is
+    /// dropped before r (8.3.14). The hiatus is not closed by further sandhi,
+    /// so `rAma iti` is never `rAmaiti`.
+    pub fn words(&self) -> Vec<&[Varna]> {
+        match self.gap {
+            Some(gap) => vec![&self.varnas[..gap], &self.varnas[gap..]],
+            None => vec![&self.varnas],
+        }
+    }
+}
+
+/// One way a word could have come from two words joined by sandhi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+    pub first: Vec<Varna>,
+    pub second: Vec<Varna>,
+    pub rule: &'static str,
+}
+
+/// How the end of the first word and the start of the second change when they meet.
+struct Junction {
+    tail: usize,               // Varnas replaced at the end of the first word (the final, or a vowel and visarga)
+    insert: Vec<&'static str>, // What replaces them and the first Varna of the second word
+    rule: &'static str,
+    hiatus: bool,              // A lopa leaves the words apart
+}
+
+/// A junction read backwards: where `result` is found after `kept`, the first
+/// word ended in `tail` and the second began with `next`. A hiatus is read as
+/// it looks when written without the gap, so `ai` may be `a` + `i`.
+struct Reversal {
+    result: Vec<Varna>,
+    kept: Vec<Varna>, // Varnas of the first word the rule needs but leaves in place
+    tail: Vec<Varna>,
+    next: Varna,
+    rule: &'static str,
+}
+
+/// Joins two words with external sandhi: vowel sandhi, the finals before
+/// voiced sounds and nasals, anusvara, and the visarga rules. Where the lopa
+/// of y and v is optional (8.3.19) only the form with lopa is given, so
+/// `vane` and `Aste` make `vana Aste` and not `vanayAste`; `split` reads both.
+pub fn join(first: &[Varna], second: &[Varna]) -> Sandhi {
+    let Some((&next, rest)) = second.split_first() else {
+        return Sandhi { varnas: first.to_vec(), gap: None, rule: None };
+    };
+
+    match junction(first, next) {
+        Some(junction) => {
+            let mut varnas = first[..first.len() - junction.tail].to_vec();
+            varnas.extend(junction.insert.iter().map(|hk| varna(hk)));
+            // The second word starts with the last Varna inserted
+            let gap = junction.hiatus.then(|| varnas.len() - 1);
+            varnas.extend_from_slice(rest);
+            Sandhi { varnas, gap, rule: Some(junction.rule) }
+        }
+        None => Sandhi { varnas: [first, second].concat(), gap: None, rule: None },
+    }
+}
+
+/// Lists the ways a word could be split into two words joined by sandhi.
+/// Both parts contain a vowel; every reading the rules allow is given.
+pub fn split(word: &[Varna]) -> Vec<Split> {
+    let mut splits: Vec<Split> = Vec::new();
+
+    for position in 1..word.len() {
+        for reversal in reversals().iter().filter(|r| word[position..].starts_with(&r.result) && word[..position].ends_with(&r.kept)) {
+            let mut first = word[..position].to_vec();
+            first.extend_from_slice(&reversal.tail);
+            let mut second = vec![reversal.next];
+            second.extend_from_slice(&word[position + reversal.result.len()..]);
+
+            let split = Split { first, second, rule: reversal.rule };
+            if split.first.iter().any(|v| v.is_vowel()) && split.second.iter().any(|v| v.is_vowel()) && !splits.contains(&split) {
+                splits.push(split);
+            }
+        }
+    }
+
+    splits
+}
+
+/// Every junction the rules produce, for reading them backwards.
+fn reversals() -> &'static [Reversal] {
+    static REVERSALS: OnceLock<Vec<Reversal>> = OnceLock::new();
+    REVERSALS.get_or_init(|| {
+        let inventory: Vec<Varna> = VarnaMap::inventory().iter().filter(|v| !v.hk.ends_with('3')).copied().collect();
+        let vowels: Vec<Varna> = inventory.iter().filter(|v| v.is_vowel()).copied().collect();
+        let visarga = varna("H");
+
+        // Endings of the first word: a single final, or a vowel with visarga
+        let endings = inventory.iter().filter(|v| !v.is_ayogavaha()).map(|v| vec![*v])
+            .chain(vowels.iter().map(|v| vec![*v, visarga]));
+
+        let mut reversals = Vec::new();
+        for ending in endings {
+            for &next in inventory.iter().filter(|v| !v.is_ayogavaha()) {
+                if let Some(junction) = junction(&ending, next) {
+                    let result = if junction.hiatus { varnas(&junction.insert.concat()) } else { junction.insert.iter().map(|hk| varna(hk)).collect() };
+                    reversals.push(Reversal {
+                        result,
+                        kept: ending[..ending.len() - junction.tail].to_vec(),
+                        tail: ending[ending.len() - junction.tail..].to_vec(),
+                        next,
+                        rule: junction.rule,
+                    });
+                }
+            }
+        }
+
+        // The lopa of 8.3.19 is optional, so y and v may also stand before the vowel
+        for (ending, glide) in [("e", "ay"), ("o", "av"), ("ai", "Ay")] {
+            for &next in vowels.iter().filter(|v| ending == "ai" || v.hk != "a") {
+                reversals.push(Reversal {
+                    result: [varnas(glide), vec![next]].concat(),
+                    kept: Vec::new(),
+                    tail: vec![varna(ending)],
+                    next,
+                    rule: "eco'yavāyāvaḥ (6.1.78)",
+                });
+            }
+        }
+        reversals
+    })
+}
+
+/// Finds the rule that applies between the end of `first` and `next`.
+fn junction(first: &[Varna], next: Varna) -> Option<Junction> {
+    let last = first.last()?;
+    let n = next.hk;
+    let voiced = next.is_vowel() || next.is_ghosha();
+    let replace = |tail: usize, insert: Vec<&'static str>, rule| Some(Junction { tail, insert, rule, hiatus: false });
+    let apart = |tail: usize, insert: Vec<&'static str>, rule| Some(Junction { tail, insert, rule, hiatus: next.is_vowel() });
+    let dropped = |tail: usize, insert: Vec<&'static str>, rule| Some(Junction { tail, insert, rule, hiatus: true });
+
+    if last.hk == "H" {
+        let vowel = first.len().checked_sub(2).map(|i| first[i].hk)?;
+        return match (vowel, n) {
+            ("a", "a") => replace(2, vec!["o"], "ato ror aplutād aplute, pūrvarūpa (6.1.113, 6.1.109)"),
+            ("a", _) if next.is_consonant() && voiced => replace(2, vec!["o", n], "haśi ca (6.1.114)"),
+            ("a", _) if next.is_vowel() => apart(2, vec!["a", n], "bhobhagoaghoapūrvasya yo'śi, lopaḥ śākalyasya (8.3.17, 8.3.19)"),
+            ("A", _) if voiced => apart(2, vec!["A", n], "bhobhagoaghoapūrvasya yo'śi, hali sarveṣām (8.3.17, 8.3.22)"),
+            ("i" | "u" | "R", "r") => dropped(2, vec![long(vowel), n], "ro ri, ḍhralope pūrvasya dīrgho'ṇaḥ (8.3.14, 6.3.111)"),
+            (_, "r") if !matches!(vowel, "a" | "A") => dropped(2, vec![vowel, n], "ro ri (8.3.14)"),
+            (_, _) if voiced && !matches!(vowel, "a" | "A") => replace(1, vec!["r", n], "sasajuṣo ruḥ (8.2.66)"),
+            (_, "c" | "ch") => replace(1, vec!["z", n], "visarjanīyasya saḥ, stoḥ ścunā ścuḥ (8.3.34, 8.4.40)"),
+            (_, "T" | "Th") => replace(1, vec!["S", n], "visarjanīyasya saḥ, ṣṭunā ṣṭuḥ (8.3.34, 8.4.41)"),
+            (_, "t" | "th") => replace(1, vec!["s", n], "visarjanīyasya saḥ (8.3.34)"),
+            _ => None,
+        };
+    }
+
+    if last.is_vowel() && next.is_vowel() {
+        let l = last.hk;
+        return match (l, n) {
+            _ if group(l).is_some() && group(l) == group(n) => replace(1, vec![long(group(l)?)], "akaḥ savarṇe dīrghaḥ (6.1.101)"),
+            ("a" | "A", "i" | "I") => replace(1, vec!["e"], "ādguṇaḥ (6.1.87)"),
+            ("a" | "A", "u" | "U") => replace(1, vec!["o"], "ādguṇaḥ (6.1.87)"),
+            ("a" | "A", "R" | "RR") => replace(1, vec!["a", "r"], "ādguṇaḥ, uraṇ raparaḥ (6.1.87, 1.1.51)"),
+            ("a" | "A", "lR" | "lRR") => replace(1, vec!["a", "l"], "ādguṇaḥ, uraṇ raparaḥ (6.1.87, 1.1.51)"),
+            ("a" | "A", "e" | "ai") => replace(1, vec!["ai"], "vṛddhireci (6.1.88)"),
+            ("a" | "A", "o" | "au") => replace(1, vec!["au"], "vṛddhireci (6.1.88)"),
+            ("i" | "I", _) => replace(1, vec!["y", n], "iko yaṇaci (6.1.77)"),
+            ("u" | "U", _) => replace(1, vec!["v", n], "iko yaṇaci (6.1.77)"),
+            ("R" | "RR", _) => replace(1, vec!["r", n], "iko yaṇaci (6.1.77)"),
+            ("lR" | "lRR", _) => replace(1, vec!["l", n], "iko yaṇaci (6.1.77)"),
+            ("e" | "o", "a") => replace(1, vec![l], "eṅaḥ padāntād ati (6.1.109)"),
+            ("e" | "o", _) => apart(1, vec!["a", n], "eco'yavāyāvaḥ, lopaḥ śākalyasya (6.1.78, 8.3.19)"),
+            ("ai", _) => apart(1, vec!["A", n], "eco'yavāyāvaḥ, lopaḥ śākalyasya (6.1.78, 8.3.19)"),
+            ("au", _) => replace(1, vec!["A", "v", n], "eco'yavāyāvaḥ (6.1.78)"),
+            _ => None,
+        };
+    }
+
+    let nasal = matches!(n, "G" | "J" | "N" | "n" | "m");
+    match (last.hk, n) {
+        ("m", _) if next.is_consonant() => replace(1, vec!["M", n], "mo'nusvāraḥ (8.3.23)"),
+        ("t", "c" | "ch") => replace(1, vec!["c", n], "stoḥ ścunā ścuḥ (8.4.40)"),
+        ("t", "j" | "jh") => replace(1, vec!["j", n], "jhalāṃ jaśo'nte, stoḥ ścunā ścuḥ (8.2.39, 8.4.40)"),
+        ("t", "T" | "Th") => replace(1, vec!["T", n], "ṣṭunā ṣṭuḥ (8.4.41)"),
+        ("t", "D" | "Dh") => replace(1, vec!["D", n], "jhalāṃ jaśo'nte, ṣṭunā ṣṭuḥ (8.2.39, 8.4.41)"),
+        ("t", "l") => replace(1, vec!["l", n], "torli (8.4.60)"),
+        ("t", "z") => replace(1, vec!["c", "ch"], "stoḥ ścunā ścuḥ, śaścho'ṭi (8.4.40, 8.4.63)"),
+        ("t", "h") => replace(1, vec!["d", "dh"], "jhalāṃ jaśo'nte, jhayo ho'nyatarasyām (8.2.39, 8.4.62)"),
+        ("k", "h") => replace(1, vec!["g", "gh"], "jhalāṃ jaśo'nte, jhayo ho'nyatarasyām (8.2.39, 8.4.62)"),
+        ("p", "h") => replace(1, vec!["b", "bh"], "jhalāṃ jaśo'nte, jhayo ho'nyatarasyām (8.2.39, 8.4.62)"),
+        ("k" | "T" | "t" | "p", _) if nasal => replace(1, vec![nasal_of(last.hk), n], "yaro'nunāsike'nunāsiko vā (8.4.45)"),
+        ("k" | "T" | "t" | "p", _) if voiced => replace(1, vec![voiced_of(last.hk), n], "jhalāṃ jaśo'nte (8.2.39)"),
+        ("n", "c" | "ch") => replace(1, vec!["M", "z", n], "naś chavy apraśān (8.3.7)"),
+        ("n", "T" | "Th") => replace(1, vec!["M", "S", n], "naś chavy apraśān (8.3.7)"),
+        ("n", "t" | "th") => replace(1, vec!["M", "s", n], "naś chavy apraśān (8.3.7)"),
+        ("n", "j" | "jh") => replace(1, vec!["J", n], "stoḥ ścunā ścuḥ (8.4.40)"),
+        ("n", "D" | "Dh") => replace(1, vec!["N", n], "ṣṭunā ṣṭuḥ (8.4.41)"),
+        ("n", "l") => replace(1, vec!["l", n], "torli (8.4.60)"),
+        _ => None,
+    }
+}
+
+/// The savarna group of a vowel, named by its short form.
+fn group(hk: &str) -> Option<&'static str> {
+    match hk {
+        "a" | "A" => Some("a"),
+        "i" | "I" => Some("i"),
+        "u" | "U" => Some("u"),
+        "R" | "RR" => Some("R"),
+        "lR" | "lRR" => Some("lR"),
+        _ => None,
+    }
+}
+
+/// The long vowel of a savarna group.
+fn long(short: &str) -> &'static str {
+    match short {
+        "a" => "A",
+        "i" => "I",
+        "u" => "U",
+        "R" => "RR",
+        _ => "lRR",
+    }
+}
+
+fn nasal_of(stop: &str) -> &'static str {
+    match stop {
+        "k" => "G",
+        "T" => "N",
+        "t" => "n",
+        _ => "m",
+    }
+}
+
+fn voiced_of(stop: &str) -> &'static str {
+    match stop {
+        "k" => "g",
+        "T" => "D",
+        "t" => "d",
+        _ => "b",
+    }
+}
+
+fn varna(hk: &str) -> Varna {
+    VarnaMap::get(hk).unwrap_or_else(|| panic!("no Varna spelled {hk:?} in the inventory"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{Lexer, Token};
+    use crate::shiksha::{lipi, TransliterationScheme};
+
+    fn varnas(hk: &str) -> Vec<Varna> {
+        Lexer::with_scheme(hk, TransliterationScheme::HarvardKyoto).tokenize().iter().flat_map(|token| match token {
+            Token::Akshara(akshara, _) => akshara.varnas.clone(),
+            Token::Unknown(..) => Vec::new(),
+        }).collect()
+    }
+
+    fn joined(first: &str, second: &str) -> String {
+        lipi::render(&join(&varnas(first), &varnas(second)).varnas, TransliterationScheme::HarvardKyoto)
+    }
+
+    #[test]
+    fn vowel_sandhi() {
+        assert_eq!(joined("deva", "indra"), "devendra");
+        assert_eq!(joined("mahA", "RSi"), "maharSi");
+        assert_eq!(joined("iti", "Adi"), "ityAdi");
+        assert_eq!(joined("vidyA", "Alaya"), "vidyAlaya");
+        assert_eq!(joined("te", "api"), "tepi");
+        assert_eq!(joined("nau", "ika"), "nAvika");
+    }
+
+    #[test]
+    fn consonant_and_visarga_sandhi() {
+        assert_eq!(joined("jagat", "nAtha"), "jagannAtha");
+        assert_eq!(joined("tat", "zrutvA"), "tacchrutvA");
+        assert_eq!(joined("vAk", "Iza"), "vAgIza");
+        assert_eq!(joined("rAmaH", "gacchati"), "rAmogacchati");
+        assert_eq!(joined("hariH", "atra"), "hariratra");
+        assert_eq!(joined("rAmaH", "ca"), "rAmazca");
+        assert_eq!(joined("hariH", "ramate"), "harIramate");
+    }
+
+    #[test]
+    fn lopa_keeps_the_words_apart() {
+        let written = |first: &str, second: &str| {
+            let joined = join(&varnas(first), &varnas(second));
+            joined.words().iter().map(|word| lipi::render(word, TransliterationScheme::HarvardKyoto)).collect::<Vec<_>>().join(" ")
+        };
+        assert_eq!(written("rAmaH", "iti"), "rAma iti");
+        assert_eq!(written("te", "iti"), "ta iti");
+        assert_eq!(written("tasmai", "iti"), "tasmA iti");
+        assert_eq!(written("devAH", "atra"), "devA atra");
+        assert_eq!(written("devAH", "gacchanti"), "devAgacchanti");
+        assert_eq!(written("vane", "Aste"), "vana Aste");
+        // ro ri: the r goes, and only a short i, u or ṛ is lengthened
+        assert_eq!(written("hariH", "ramate"), "harI ramate");
+        assert_eq!(written("nadIH", "rAjate"), "nadI rAjate");
+        assert_eq!(written("gurUH", "rakSati"), "gurU rakSati");
+        assert_eq!(join(&varnas("nadIH"), &varnas("rAjate")).rule, Some("ro ri (8.3.14)"));
+        assert_eq!(join(&varnas("rAmaH"), &varnas("iti")).gap, Some(4));
+    }
+
+    #[test]
+    fn split_reverses_lopa() {
+        let pairs = |word: &str| -> Vec<(String, String)> {
+            split(&varnas(word)).iter().map(|s| {
+                (lipi::render(&s.first, TransliterationScheme::HarvardKyoto), lipi::render(&s.second, TransliterationScheme::HarvardKyoto))
+            }).collect()
+        };
+        assert!(pairs("rAmaiti").contains(&("rAmaH".to_string(), "iti".to_string())));
+        assert!(pairs("taiti").contains(&("te".to_string(), "iti".to_string())));
+        assert!(pairs("tasmAiti").contains(&("tasmai".to_string(), "iti".to_string())));
+        // Without the optional lopa
+        assert!(pairs("vanayAste").contains(&("vane".to_string(), "Aste".to_string())));
+        assert!(pairs("tasmAyiti").contains(&("tasmai".to_string(), "iti".to_string())));
+        assert!(pairs("nadIrAjate").contains(&("nadIH".to_string(), "rAjate".to_string())));
+        // The vrddhi reading is still offered
+        assert!(pairs("rAmaiti").contains(&("rAma".to_string(), "eti".to_string())));
+    }
+
+    #[test]
+    fn split_recovers_the_words() {
+        let splits = split(&varnas("devendra"));
+        assert!(splits.iter().any(|s| s.first == varnas("deva") && s.second == varnas("indra")));
+        let splits = split(&varnas("hariratra"));
+        assert!(splits.iter().any(|s| s.first == varnas("hariH") && s.second == varnas("atra")));
+    }
+}
@@ -0,0 +1,769 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use crate::shiksha::{lipi, Pada, ParseNameError, TransliterationScheme, Varna};
+use crate::vyakaran::prakriya::{attach, finish, is_consonant, is_vowel, long, nasal_of, pada, short, voiced, word, Word};
+use crate::vyakaran::dhatu::{find, Gana};
+use crate::vyakaran::tinanta::Vacana;
+use crate::vyakaran::varnas;
+
+/// The seven cases (vibhakti) of the noun, with the vocative counted as an
+/// eighth as the grammarians do.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Vibhakti {
+    Prathama,   // Nominative
+    Dvitiya,    // Accusative
+    Trtiya,     // Instrumental
+    Caturthi,   // Dative
+    Pancami,    // Ablative
+    Sasthi,     // Genitive
+    Saptami,    // Locative
+    Sambodhana, // Vocative
+}
+
+impl Vibhakti {
+    pub const ALL: [Vibhakti; 8] = [
+        Vibhakti::Prathama, Vibhakti::Dvitiya, Vibhakti::Trtiya, Vibhakti::Caturthi,
+        Vibhakti::Pancami, Vibhakti::Sasthi, Vibhakti::Saptami, Vibhakti::Sambodhana,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Vibhakti::Prathama => "prathama",
+            Vibhakti::Dvitiya => "dvitiya",
+            Vibhakti::Trtiya => "trtiya",
+            Vibhakti::Caturthi => "caturthi",
+            Vibhakti::Pancami => "pancami",
+            Vibhakti::Sasthi => "sasthi",
+            Vibhakti::Saptami => "saptami",
+            Vibhakti::Sambodhana => "sambodhana",
+        }
+    }
+}
+
+impl fmt::Display for Vibhakti {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a case in any letter case.
+impl FromStr for Vibhakti {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Vibhakti::ALL.into_iter().find(|v| v.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("vibhakti", s))
+    }
+}
+
+/// Grammatical gender.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Linga {
+    Pum,       // Masculine
+    Stri,      // Feminine
+    Napumsaka, // Neuter
+}
+
+impl Linga {
+    pub const ALL: [Linga; 3] = [Linga::Pum, Linga::Stri, Linga::Napumsaka];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Linga::Pum => "pumlinga",
+            Linga::Stri => "strilinga",
+            Linga::Napumsaka => "napumsakalinga",
+        }
+    }
+}
+
+impl fmt::Display for Linga {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a gender in any letter case.
+impl FromStr for Linga {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Linga::ALL.into_iter().find(|l| l.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("linga", s))
+    }
+}
+
+/// An inflected noun or pronoun with what it expresses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Subanta {
+    pub pada: Pada,
+    pub vibhakti: Vibhakti,
+    pub vacana: Vacana,
+    pub linga: Linga,
+}
+
+/// Declines a nominal stem in one case and number, with the sandhi inside the
+/// word and ṇatva applied. Where grammar allows more than one form all are
+/// given, the commoner first. The result is empty for a stem of a kind the
+/// tables do not cover and for the vocative of pronouns, which have none.
+/// The personal pronouns asmad and yuṣmad are the same in every gender.
+pub fn subanta(stem: &[Varna], linga: Linga, vibhakti: Vibhakti, vacana: Vacana) -> Vec<Pada> {
+    let stem = lipi::render(stem, TransliterationScheme::HarvardKyoto);
+    let index = vibhakti as usize * 3 + vacana as usize;
+    let mut words: Vec<Word> = Vec::new();
+    for form in forms(&stem, linga, index).into_iter().map(finish) {
+        if !words.contains(&form) {
+            words.push(form);
+        }
+    }
+    words.iter().map(|w| pada(w)).collect()
+}
+
+/// Declines a stem in every case and number of a gender.
+pub fn decline(stem: &[Varna], linga: Linga) -> Vec<Subanta> {
+    let mut forms = Vec::new();
+    for vibhakti in Vibhakti::ALL {
+        for vacana in Vacana::ALL {
+            forms.extend(subanta(stem, linga, vibhakti, vacana).into_iter()
+                .map(|pada| Subanta { pada, vibhakti, vacana, linga }));
+        }
+    }
+    forms
+}
+
+/// A nominal stem (prātipadika) known to the grammar, with the root or stem
+/// it is derived from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Pratipadika {
+    pub stem: Vec<Varna>,
+    pub linga: Linga,
+    pub prakriti: Option<Vec<Varna>>, // The root or stem it is derived from
+    pub pratyayas: Vec<Vec<Varna>>,   // The kṛt, taddhita and feminine suffixes, in the order added
+    pub gloss: &'static str,          // Meaning in English
+}
+
+impl Pratipadika {
+    /// Returns the stem written in the given scheme.
+    pub fn render(&self, scheme: TransliterationScheme) -> String {
+        lipi::render(&self.stem, scheme)
+    }
+
+    /// Whether the forms of the stem follow its gender. Asmad and yuṣmad are
+    /// the same in every gender, so the gender they are entered in means nothing.
+    pub fn gendered(&self) -> bool {
+        !matches!(lipi::render(&self.stem, TransliterationScheme::HarvardKyoto).as_str(), "asmad" | "yuSmad")
+    }
+}
+
+/// Stem, gender, source and suffixes in Harvard-Kyoto, and meaning in English.
+/// The pronouns are entered in each gender, asmad and yuṣmad only once as they
+/// do not distinguish it.
+type Entry = (&'static str, Linga, &'static str, &'static [&'static str], &'static str);
+
+use Linga::*;
+
+const ENTRIES: &[Entry] = &[
+    // a-stems
+    ("rAma", Pum, "", &[], "Rama"),
+    ("deva", Pum, "div", &["ac"], "god"),
+    ("jana", Pum, "jan", &["ac"], "person, people"),
+    ("vRkSa", Pum, "", &[], "tree"),
+    ("vana", Napumsaka, "", &[], "forest"),
+    ("phala", Napumsaka, "", &[], "fruit"),
+    ("jJAna", Napumsaka, "jJA", &["lyuT"], "knowledge"),
+    // ā-stems
+    ("ramA", Stri, "", &[], "Rama, Lakshmi"),
+    ("latA", Stri, "", &[], "creeper"),
+    ("vidyA", Stri, "vid", &["kyap", "TAp"], "knowledge"),
+    ("gopA", Pum, "go", &["vic"], "cowherd"),
+    // i- and u-stems
+    ("hari", Pum, "", &[], "Hari, Vishnu"),
+    ("agni", Pum, "", &[], "fire"),
+    ("mati", Stri, "man", &["ktin"], "thought"),
+    ("buddhi", Stri, "budh", &["ktin"], "intellect"),
+    ("vAri", Napumsaka, "", &[], "water"),
+    ("guru", Pum, "", &[], "teacher"),
+    ("bhAnu", Pum, "", &[], "sun"),
+    ("dhenu", Stri, "", &[], "cow"),
+    ("madhu", Napumsaka, "", &[], "honey"),
+    ("vastu", Napumsaka, "", &[], "thing"),
+    // ī- and ū-stems
+    ("nadI", Stri, "", &[], "river"),
+    ("devI", Stri, "deva", &["GIp"], "goddess"),
+    ("lakSmI", Stri, "", &[], "Lakshmi, fortune"),
+    ("strI", Stri, "", &[], "woman"),
+    ("dhI", Stri, "", &[], "thought"),
+    ("zrI", Stri, "", &[], "fortune, splendour"),
+    ("senAnI", Pum, "", &[], "general"),
+    ("vadhU", Stri, "", &[], "bride"),
+    ("bhrU", Stri, "", &[], "eyebrow"),
+    ("svayambhU", Pum, "", &[], "self-existent"),
+    // ṛ-stems
+    ("pitR", Pum, "", &[], "father"),
+    ("bhrAtR", Pum, "", &[], "brother"),
+    ("mAtR", Stri, "", &[], "mother"),
+    ("duhitR", Stri, "", &[], "daughter"),
+    ("svasR", Stri, "", &[], "sister"),
+    ("dAtR", Pum, "dA", &["tRc"], "giver"),
+    ("kartR", Pum, "kR", &["tRc"], "doer, agent"),
+    // Consonant stems
+    ("vAc", Stri, "vac", &["kvip"], "speech"),
+    ("marut", Pum, "", &[], "wind"),
+    ("suhRd", Pum, "", &[], "friend"),
+    ("diz", Stri, "diz", &["kvin"], "direction"),
+    ("jagat", Napumsaka, "", &[], "world"),
+    ("rAjan", Pum, "rAj", &["kanin"], "king"),
+    ("Atman", Pum, "", &[], "self"),
+    ("nAman", Napumsaka, "", &[], "name"),
+    ("karman", Napumsaka, "kR", &["manin"], "action"),
+    ("brahman", Napumsaka, "", &[], "Brahman"),
+    ("balin", Pum, "bala", &["ini"], "strong"),
+    ("yogin", Pum, "yoga", &["ini"], "yogi"),
+    ("bhagavat", Pum, "bhaga", &["matup"], "the Lord"),
+    ("bhagavat", Stri, "bhaga", &["matup", "GIp"], "the Goddess"),
+    ("zrImat", Pum, "zrI", &["matup"], "glorious"),
+    ("gacchat", Pum, "gam", &["zatR"], "going"),
+    ("gacchat", Stri, "gam", &["zatR", "GIp"], "going"),
+    ("manas", Napumsaka, "", &[], "mind"),
+    ("payas", Napumsaka, "", &[], "milk, water"),
+    ("candramas", Pum, "", &[], "moon"),
+    ("havis", Napumsaka, "", &[], "oblation"),
+    ("dhanus", Napumsaka, "", &[], "bow"),
+    // Pronouns
+    ("sarva", Pum, "", &[], "all"),
+    ("sarva", Stri, "", &[], "all"),
+    ("sarva", Napumsaka, "", &[], "all"),
+    ("anya", Pum, "", &[], "other"),
+    ("anya", Stri, "", &[], "other"),
+    ("anya", Napumsaka, "", &[], "other"),
+    ("tad", Pum, "", &[], "that, he"),
+    ("tad", Stri, "", &[], "that, she"),
+    ("tad", Napumsaka, "", &[], "that, it"),
+    ("etad", Pum, "", &[], "this"),
+    ("etad", Stri, "", &[], "this"),
+    ("etad", Napumsaka, "", &[], "this"),
+    ("yad", Pum, "", &[], "which, who"),
+    ("yad", Stri, "", &[], "which, who"),
+    ("yad", Napumsaka, "", &[], "which, what"),
+    ("kim", Pum, "", &[], "who"),
+    ("kim", Stri, "", &[], "who"),
+    ("kim", Napumsaka, "", &[], "what"),
+    ("idam", Pum, "", &[], "this"),
+    ("idam", Stri, "", &[], "this"),
+    ("idam", Napumsaka, "", &[], "this"),
+    ("adas", Pum, "", &[], "that"),
+    ("adas", Stri, "", &[], "that"),
+    ("adas", Napumsaka, "", &[], "that"),
+    ("asmad", Pum, "", &[], "I, we"),
+    ("yuSmad", Pum, "", &[], "you"),
+];
+
+/// The nominal stems known to the grammar: nouns of each kind of stem the
+/// declension tables cover, and the pronouns.
+pub fn pratipadikas() -> &'static [Pratipadika] {
+    static PRATIPADIKAS: OnceLock<Vec<Pratipadika>> = OnceLock::new();
+    PRATIPADIKAS.get_or_init(|| {
+        ENTRIES.iter().map(|&(stem, linga, prakriti, pratyayas, gloss)| Pratipadika {
+            stem: varnas(stem),
+            linga,
+            prakriti: (!prakriti.is_empty()).then(|| varnas(prakriti)),
+            pratyayas: pratyayas.iter().map(|pratyaya| varnas(pratyaya)).collect(),
+            gloss,
+        }).collect()
+    })
+}
+
+/// The endings of a declension as they stand after the stem's final vowel is
+/// taken off, case by case in the three numbers, the vocative last.
+/// Alternatives are separated by "/"; an empty cell has no form.
+type Table = [&'static str; 24];
+
+const A_PUM: Table = [
+    "aH", "au", "AH", "am", "au", "An", "ena", "AbhyAm", "aiH", "Aya", "AbhyAm", "ebhyaH",
+    "At", "AbhyAm", "ebhyaH", "asya", "ayoH", "AnAm", "e", "ayoH", "eSu", "a", "au", "AH",
+];
+const A_NAPUMSAKA: Table = [
+    "am", "e", "Ani", "am", "e", "Ani", "ena", "AbhyAm", "aiH", "Aya", "AbhyAm", "ebhyaH",
+    "At", "AbhyAm", "ebhyaH", "asya", "ayoH", "AnAm", "e", "ayoH", "eSu", "a", "e", "Ani",
+];
+const AA_STRI: Table = [
+    "A", "e", "AH", "Am", "e", "AH", "ayA", "AbhyAm", "AbhiH", "Ayai", "AbhyAm", "AbhyaH",
+    "AyAH", "AbhyAm", "AbhyaH", "AyAH", "ayoH", "AnAm", "AyAm", "ayoH", "Asu", "e", "e", "AH",
+];
+const AA_PUM: Table = [
+    "AH", "au", "AH", "Am", "au", "aH", "A", "AbhyAm", "AbhiH", "e", "AbhyAm", "AbhyaH",
+    "aH", "AbhyAm", "AbhyaH", "aH", "oH", "Am", "i", "oH", "Asu", "AH", "au", "AH",
+];
+const I_PUM: Table = [
+    "iH", "I", "ayaH", "im", "I", "In", "inA", "ibhyAm", "ibhiH", "aye", "ibhyAm", "ibhyaH",
+    "eH", "ibhyAm", "ibhyaH", "eH", "yoH", "InAm", "au", "yoH", "iSu", "e", "I", "ayaH",
+];
+const I_STRI: Table = [
+    "iH", "I", "ayaH", "im", "I", "IH", "yA", "ibhyAm", "ibhiH", "aye/yai", "ibhyAm", "ibhyaH",
+    "eH/yAH", "ibhyAm", "ibhyaH", "eH/yAH", "yoH", "InAm", "au/yAm", "yoH", "iSu", "e", "I", "ayaH",
+];
+const I_NAPUMSAKA: Table = [
+    "i", "inI", "Ini", "i", "inI", "Ini", "inA", "ibhyAm", "ibhiH", "ine", "ibhyAm", "ibhyaH",
+    "inaH", "ibhyAm", "ibhyaH", "inaH", "inoH", "InAm", "ini", "inoH", "iSu", "e/i", "inI", "Ini",
+];
+const U_PUM: Table = [
+    "uH", "U", "avaH", "um", "U", "Un", "unA", "ubhyAm", "ubhiH", "ave", "ubhyAm", "ubhyaH",
+    "oH", "ubhyAm", "ubhyaH", "oH", "voH", "UnAm", "au", "voH", "uSu", "o", "U", "avaH",
+];
+const U_STRI: Table = [
+    "uH", "U", "avaH", "um", "U", "UH", "vA", "ubhyAm", "ubhiH", "ave/vai", "ubhyAm", "ubhyaH",
+    "oH/vAH", "ubhyAm", "ubhyaH", "oH/vAH", "voH", "UnAm", "au/vAm", "voH", "uSu", "o", "U", "avaH",
+];
+const U_NAPUMSAKA: Table = [
+    "u", "unI", "Uni", "u", "unI", "Uni", "unA", "ubhyAm", "ubhiH", "une", "ubhyAm", "ubhyaH",
+    "unaH", "ubhyAm", "ubhyaH", "unaH", "unoH", "UnAm", "uni", "unoH", "uSu", "o/u", "unI", "Uni",
+];
+// Nadī stems: feminines in ī and ū of more than one syllable (1.4.3)
+const II_NADI: Table = [
+    "I", "yau", "yaH", "Im", "yau", "IH", "yA", "IbhyAm", "IbhiH", "yai", "IbhyAm", "IbhyaH",
+    "yAH", "IbhyAm", "IbhyaH", "yAH", "yoH", "InAm", "yAm", "yoH", "ISu", "i", "yau", "yaH",
+];
+const UU_NADI: Table = [
+    "UH", "vau", "vaH", "Um", "vau", "UH", "vA", "UbhyAm", "UbhiH", "vai", "UbhyAm", "UbhyaH",
+    "vAH", "UbhyAm", "UbhyaH", "vAH", "voH", "UnAm", "vAm", "voH", "USu", "u", "vau", "vaH",
+];
+// Monosyllables in ī and ū, with iyaṅ and uvaṅ (6.4.77) and the nadī forms optional in the feminine (1.4.4, 1.4.6)
+const II_IYAN: Table = [
+    "IH", "iyau", "iyaH", "iyam", "iyau", "iyaH", "iyA", "IbhyAm", "IbhiH", "iye/iyai", "IbhyAm", "IbhyaH",
+    "iyaH/iyAH", "IbhyAm", "IbhyaH", "iyaH/iyAH", "iyoH", "iyAm/InAm", "iyi/iyAm", "iyoH", "ISu", "IH", "iyau", "iyaH",
+];
+const UU_UVAN: Table = [
+    "UH", "uvau", "uvaH", "uvam", "uvau", "uvaH", "uvA", "UbhyAm", "UbhiH", "uve/uvai", "UbhyAm", "UbhyaH",
+    "uvaH/uvAH", "UbhyAm", "UbhyaH", "uvaH/uvAH", "uvoH", "uvAm/UnAm", "uvi/uvAm", "uvoH", "USu", "UH", "uvau", "uvaH",
+];
+// Masculines in ī and ū of more than one syllable, with yaṇ (6.4.82, 6.4.83)
+const II_PUM: Table = [
+    "IH", "yau", "yaH", "yam", "yau", "yaH", "yA", "IbhyAm", "IbhiH", "ye", "IbhyAm", "IbhyaH",
+    "yaH", "IbhyAm", "IbhyaH", "yaH", "yoH", "yAm", "yAm", "yoH", "ISu", "IH", "yau", "yaH",
+];
+const UU_PUM: Table = [
+    "UH", "vau", "vaH", "vam", "vau", "vaH", "vA", "UbhyAm", "UbhiH", "ve", "UbhyAm", "UbhyaH",
+    "vaH", "UbhyAm", "UbhyaH", "vaH", "voH", "vAm", "vi", "voH", "USu", "UH", "vau", "vaH",
+];
+// Nouns of relationship in ṛ take guṇa in the strong cases (7.3.110), agent nouns vṛddhi (6.4.11)
+const R_PUM: Table = [
+    "A", "arau", "araH", "aram", "arau", "RRn", "rA", "RbhyAm", "RbhiH", "re", "RbhyAm", "RbhyaH",
+    "uH", "RbhyAm", "RbhyaH", "uH", "roH", "RRnAm", "ari", "roH", "RSu", "aH", "arau", "araH",
+];
+const R_PUM_AGENT: Table = [
+    "A", "Arau", "AraH", "Aram", "Arau", "RRn", "rA", "RbhyAm", "RbhiH", "re", "RbhyAm", "RbhyaH",
+    "uH", "RbhyAm", "RbhyaH", "uH", "roH", "RRnAm", "ari", "roH", "RSu", "aH", "Arau", "AraH",
+];
+const R_STRI: Table = [
+    "A", "arau", "araH", "aram", "arau", "RRH", "rA", "RbhyAm", "RbhiH", "re", "RbhyAm", "RbhyaH",
+    "uH", "RbhyAm", "RbhyaH", "uH", "roH", "RRnAm", "ari", "roH", "RSu", "aH", "arau", "araH",
+];
+const R_STRI_AGENT: Table = [
+    "A", "Arau", "AraH", "Aram", "Arau", "RRH", "rA", "RbhyAm", "RbhiH", "re", "RbhyAm", "RbhyaH",
+    "uH", "RbhyAm", "RbhyaH", "uH", "roH", "RRnAm", "ari", "roH", "RSu", "aH", "Arau", "AraH",
+];
+const R_NAPUMSAKA: Table = [
+    "R", "RnI", "RRni", "R", "RnI", "RRni", "RnA", "RbhyAm", "RbhiH", "Rne", "RbhyAm", "RbhyaH",
+    "RnaH", "RbhyAm", "RbhyaH", "RnaH", "RnoH", "RRnAm", "Rni", "RnoH", "RSu", "aH/R", "RnI", "RRni",
+];
+
+// Pronouns (sarvanāman) take smai, smāt, smin, syai and sām (7.1.14 to 7.1.16, 7.3.114, 7.1.52)
+const SARVA_PUM: Table = [
+    "aH", "au", "e", "am", "au", "An", "ena", "AbhyAm", "aiH", "asmai", "AbhyAm", "ebhyaH",
+    "asmAt", "AbhyAm", "ebhyaH", "asya", "ayoH", "eSAm", "asmin", "ayoH", "eSu", "a", "au", "e",
+];
+const SARVA_STRI: Table = [
+    "A", "e", "AH", "Am", "e", "AH", "ayA", "AbhyAm", "AbhiH", "asyai", "AbhyAm", "AbhyaH",
+    "asyAH", "AbhyAm", "AbhyaH", "asyAH", "ayoH", "AsAm", "asyAm", "ayoH", "Asu", "e", "e", "AH",
+];
+const SARVA_NAPUMSAKA: Table = [
+    "am", "e", "Ani", "am", "e", "Ani", "ena", "AbhyAm", "aiH", "asmai", "AbhyAm", "ebhyaH",
+    "asmAt", "AbhyAm", "ebhyaH", "asya", "ayoH", "eSAm", "asmin", "ayoH", "eSu", "a", "e", "Ani",
+];
+const IDAM_PUM: Table = [
+    "ayam", "imau", "ime", "imam", "imau", "imAn", "anena", "AbhyAm", "ebhiH", "asmai", "AbhyAm", "ebhyaH",
+    "asmAt", "AbhyAm", "ebhyaH", "asya", "anayoH", "eSAm", "asmin", "anayoH", "eSu", "", "", "",
+];
+const IDAM_STRI: Table = [
+    "iyam", "ime", "imAH", "imAm", "ime", "imAH", "anayA", "AbhyAm", "AbhiH", "asyai", "AbhyAm", "AbhyaH",
+    "asyAH", "AbhyAm", "AbhyaH", "asyAH", "anayoH", "AsAm", "asyAm", "anayoH", "Asu", "", "", "",
+];
+const IDAM_NAPUMSAKA: Table = [
+    "idam", "ime", "imAni", "idam", "ime", "imAni", "anena", "AbhyAm", "ebhiH", "asmai", "AbhyAm", "ebhyaH",
+    "asmAt", "AbhyAm", "ebhyaH", "asya", "anayoH", "eSAm", "asmin", "anayoH", "eSu", "", "", "",
+];
+const ADAS_PUM: Table = [
+    "asau", "amU", "amI", "amum", "amU", "amUn", "amunA", "amUbhyAm", "amIbhiH", "amuSmai", "amUbhyAm", "amIbhyaH",
+    "amuSmAt", "amUbhyAm", "amIbhyaH", "amuSya", "amuyoH", "amISAm", "amuSmin", "amuyoH", "amISu", "", "", "",
+];
+const ADAS_STRI: Table = [
+    "asau", "amU", "amUH", "amUm", "amU", "amUH", "amuyA", "amUbhyAm", "amUbhiH", "amuSyai", "amUbhyAm", "amUbhyaH",
+    "amuSyAH", "amUbhyAm", "amUbhyaH", "amuSyAH", "amuyoH", "amUSAm", "amuSyAm", "amuyoH", "amUSu", "", "", "",
+];
+const ADAS_NAPUMSAKA: Table = [
+    "adaH", "amU", "amUni", "adaH", "amU", "amUni", "amunA", "amUbhyAm", "amIbhiH", "amuSmai", "amUbhyAm", "amIbhyaH",
+    "amuSmAt", "amUbhyAm", "amIbhyaH", "amuSya", "amuyoH", "amISAm", "amuSmin", "amuyoH", "amISu", "", "", "",
+];
+// The enclitic forms of the personal pronouns (8.1.20 to 8.1.23) come second
+const ASMAD: Table = [
+    "aham", "AvAm", "vayam", "mAm/mA", "AvAm/nau", "asmAn/naH", "mayA", "AvAbhyAm", "asmAbhiH", "mahyam/me", "AvAbhyAm/nau", "asmabhyam/naH",
+    "mat", "AvAbhyAm", "asmat", "mama/me", "AvayoH/nau", "asmAkam/naH", "mayi", "AvayoH", "asmAsu", "", "", "",
+];
+const YUSMAD: Table = [
+    "tvam", "yuvAm", "yUyam", "tvAm/tvA", "yuvAm/vAm", "yuSmAn/vaH", "tvayA", "yuvAbhyAm", "yuSmAbhiH", "tubhyam/te", "yuvAbhyAm/vAm", "yuSmabhyam/vaH",
+    "tvat", "yuvAbhyAm", "yuSmat", "tava/te", "yuvayoH/vAm", "yuSmAkam/vaH", "tvayi", "yuvayoH", "yuSmAsu", "", "", "",
+];
+
+/// The pronouns declined as sarva; the first five take -at in the neuter
+/// nominative and accusative singular (7.1.25).
+const SARVADI: [&str; 11] = ["anya", "anyatara", "itara", "katara", "katama", "sarva", "vizva", "ubhaya", "eka", "pUrva", "para"];
+/// Feminines in ī that keep the s of the nominative singular.
+const AVI: [&str; 5] = ["avI", "lakSmI", "tarI", "tantrI", "strI"];
+/// Masculines and feminines in ṛ that are nouns of relationship.
+const RELATION: [&str; 7] = ["pitR", "bhrAtR", "jAmAtR", "devR", "mAtR", "duhitR", "yAtR"];
+/// Consonant stems from kvin, whose final becomes k (8.2.62).
+const KVIN: [&str; 5] = ["diz", "dRz", "spRz", "sraj", "Rtvij"];
+
+/// Every form of a stem in one cell of its paradigm, before the word is finished.
+fn forms(stem: &str, linga: Linga, index: usize) -> Vec<Word> {
+    if let Some(forms) = pronoun(stem, linga, index) {
+        return forms;
+    }
+    let stem = word(stem);
+    let Some(&last) = stem.last() else { return Vec::new() };
+    if is_vowel(last) {
+        return vowel(&stem, linga, index);
+    }
+    // The feminine of at-stems is made with ṅīp (4.1.6) and declined as a nadī
+    if linga == Linga::Stri && stem.len() > 2 && stem.ends_with(&["a", "t"]) {
+        return before_i(&stem).into_iter().flat_map(|base| vowel(&[&base[..], &["I"][..]].concat(), linga, index)).collect();
+    }
+    consonant(&stem, linga, index)
+}
+
+/// The bases of an at-stem before the ī of the feminine and of the neuter
+/// dual: the śatṛ of a root with śap or śyan takes num (7.1.81), that of a
+/// root with śa optionally (7.1.80); other at-stems such as jagat and
+/// bhagavat stay as they are.
+fn before_i(stem: &Word) -> Vec<Word> {
+    let hk = stem.concat();
+    let shatr = varnas("zatR");
+    let gana = pratipadikas().iter()
+        .filter(|p| p.render(TransliterationScheme::HarvardKyoto) == hk && p.pratyayas.contains(&shatr))
+        .find_map(|p| p.prakriti.as_ref().and_then(|root| find(root).first().map(|d| d.gana)));
+    let num = [&stem[..stem.len() - 1], &["n", "t"][..]].concat();
+    match gana {
+        Some(Gana::Bhvadi | Gana::Divadi | Gana::Curadi) => vec![num],
+        Some(Gana::Tudadi) => vec![stem.clone(), num],
+        _ => vec![stem.clone()],
+    }
+}
+
+/// Puts the endings of a cell after a base.
+fn inflect(base: &[&'static str], table: &Table, index: usize) -> Vec<Word> {
+    table[index].split('/').filter(|ending| !ending.is_empty()).map(|ending| [base, &word(ending)[..]].concat()).collect()
+}
+
+fn pronoun(stem: &str, linga: Linga, index: usize) -> Option<Vec<Word>> {
+    let table = match (stem, linga) {
+        ("asmad", _) => return Some(inflect(&[], &ASMAD, index)),
+        ("yuSmad", _) => return Some(inflect(&[], &YUSMAD, index)),
+        ("idam", Linga::Pum) => return Some(inflect(&[], &IDAM_PUM, index)),
+        ("idam", Linga::Stri) => return Some(inflect(&[], &IDAM_STRI, index)),
+        ("idam", Linga::Napumsaka) => return Some(inflect(&[], &IDAM_NAPUMSAKA, index)),
+        ("adas", Linga::Pum) => return Some(inflect(&[], &ADAS_PUM, index)),
+        ("adas", Linga::Stri) => return Some(inflect(&[], &ADAS_STRI, index)),
+        ("adas", Linga::Napumsaka) => return Some(inflect(&[], &ADAS_NAPUMSAKA, index)),
+        (_, Linga::Pum) => &SARVA_PUM,
+        (_, Linga::Stri) => &SARVA_STRI,
+        (_, Linga::Napumsaka) => &SARVA_NAPUMSAKA,
+    };
+    // tyadādīnām aḥ: the d of tad, etad and yad and the im of kim become a (7.2.102, 7.2.103)
+    let (base, special) = match stem {
+        "tad" => ("t", true),
+        "etad" => ("et", true),
+        "yad" => ("y", true),
+        "kim" => ("k", true),
+        _ if SARVADI.contains(&stem) => (&stem[..stem.len() - 1], false),
+        _ => return None,
+    };
+    let base = word(base);
+    if special && index >= 21 {
+        return Some(Vec::new());
+    }
+    Some(match (stem, linga, index) {
+        // tadoḥ saḥ sāv anantyayoḥ (7.2.106), with ṣatva after the e of etad (8.3.59)
+        ("tad", Linga::Pum, 0) => vec![word("saH")],
+        ("tad", Linga::Stri, 0) => vec![word("sA")],
+        ("etad", Linga::Pum, 0) => vec![word("eSaH")],
+        ("etad", Linga::Stri, 0) => vec![word("eSA")],
+        ("kim", Linga::Napumsaka, 0 | 3) => vec![word("kim")],
+        (_, Linga::Napumsaka, 0 | 3 | 21) if special || SARVADI[..5].contains(&stem) => vec![[&base[..], &["a", "t"][..]].concat()],
+        _ => inflect(&base, table, index),
+    })
+}
+
+fn vowel(stem: &Word, linga: Linga, index: usize) -> Vec<Word> {
+    let n = stem.len();
+    let last = stem[n - 1];
+    // hrasvo napuṃsake prātipadikasya (1.2.47)
+    let last = if linga == Linga::Napumsaka { short(last) } else { last };
+    let base = &stem[..n - 1];
+    let hk: String = stem.concat();
+    let monosyllable = stem.iter().filter(|hk| is_vowel(hk)).count() == 1;
+    let table = match (last, linga) {
+        ("a", Linga::Pum) => &A_PUM,
+        ("a", Linga::Napumsaka) => &A_NAPUMSAKA,
+        ("A", Linga::Stri) => &AA_STRI,
+        ("A", Linga::Pum) => &AA_PUM,
+        ("i", Linga::Pum) => &I_PUM,
+        ("i", Linga::Stri) => &I_STRI,
+        ("i", Linga::Napumsaka) => &I_NAPUMSAKA,
+        ("u", Linga::Pum) => &U_PUM,
+        ("u", Linga::Stri) => &U_STRI,
+        ("u", Linga::Napumsaka) => &U_NAPUMSAKA,
+        ("I", Linga::Stri) if hk == "strI" => return stri(index),
+        ("I", Linga::Stri) if AVI.contains(&hk.as_str()) && index == 0 => return vec![word(&format!("{hk}H"))],
+        ("I" | "U", _) if monosyllable => {
+            let forms = inflect(base, if last == "I" { &II_IYAN } else { &UU_UVAN }, index);
+            // The nadī forms are the second of two alternatives and only feminine
+            return if linga == Linga::Stri { forms } else { forms.into_iter().take(1).collect() };
+        }
+        ("I", Linga::Stri) => &II_NADI,
+        ("U", Linga::Stri) => &UU_NADI,
+        ("I", _) => &II_PUM,
+        // na bhūsudhiyoḥ (6.4.85): compounds of bhū keep uvaṅ
+        ("U", _) if hk.ends_with("bhU") => return inflect(base, &UU_UVAN, index).into_iter().take(1).collect(),
+        ("U", _) => &UU_PUM,
+        ("R", Linga::Pum) if RELATION.contains(&hk.as_str()) => &R_PUM,
+        ("R", Linga::Pum) => &R_PUM_AGENT,
+        ("R", Linga::Stri) if RELATION.contains(&hk.as_str()) => &R_STRI,
+        ("R", Linga::Stri) => &R_STRI_AGENT,
+        ("R", Linga::Napumsaka) => &R_NAPUMSAKA,
+        _ => return Vec::new(),
+    };
+    inflect(base, table, index)
+}
+
+/// Strī, which takes iyaṅ though it is not a monosyllabic root (6.4.79) and
+/// has it optionally before am and śas (6.4.80).
+fn stri(index: usize) -> Vec<Word> {
+    let forms = match index {
+        0 => "strI",
+        3 => "striyam/strIm",
+        5 => "striyaH/strIH",
+        21 => "stri",
+        _ => return inflect(&word("str"), &II_IYAN, index).into_iter().rev().take(1).collect(),
+    };
+    forms.split('/').map(word).collect()
+}
+
+/// The sup endings after a consonant, with the s of su dropped (6.1.68).
+const SUP: [&str; 24] = [
+    "", "au", "as", "am", "au", "as", "A", "bhyAm", "bhis", "e", "bhyAm", "bhyas",
+    "as", "bhyAm", "bhyas", "as", "os", "Am", "i", "os", "su", "", "au", "as",
+];
+
+/// The stems of a consonant-final noun: strong before the
+/// sarvanāmasthāna endings (1.1.42, 1.1.43), middle (pada) before those in a
+/// consonant (1.4.17) and weak (bha) before the other vowels (1.4.18).
+struct Stems {
+    strong: Word,
+    middle: Word,
+    weak: Word,
+    weak_alt: Option<Word>, // Optional unreduced weak stem in the locative singular and neuter dual
+    nominative: Vec<Word>,  // Nominative singular, the neuter accusative as well
+    vocative: Vec<Word>,
+    plural: Word,           // Neuter nominative plural
+    locative: Word,         // Locative plural
+}
+
+fn consonant(stem: &Word, linga: Linga, index: usize) -> Vec<Word> {
+    let Some(stems) = stems(stem, linga) else { return Vec::new() };
+    let neuter = linga == Linga::Napumsaka;
+    let ending = word(SUP[index]);
+    let weak = |ending: &[&'static str]| {
+        let mut forms = vec![attach(&stems.weak, ending)];
+        forms.extend(stems.weak_alt.iter().map(|weak| attach(weak, ending)));
+        forms
+    };
+    match index {
+        0 => stems.nominative.clone(),
+        3 if neuter => stems.nominative.clone(),
+        21 => stems.vocative.clone(),
+        1 | 4 | 22 if neuter && stem.len() > 2 && stem.ends_with(&["a", "t"]) => before_i(stem).iter().map(|base| attach(base, &["I"])).collect(),
+        1 | 4 | 22 if neuter => weak(&["I"]),
+        2 | 5 | 23 if neuter => vec![stems.plural.clone()],
+        1..=4 | 22 | 23 => vec![attach(&stems.strong, &ending)],
+        20 => vec![stems.locative.clone()],
+        18 => weak(&ending),
+        _ if is_vowel(ending[0]) => vec![attach(&stems.weak, &ending)],
+        _ => vec![[&stems.middle[..], &ending[..]].concat()],
+    }
+}
+
+fn stems(stem: &Word, linga: Linga) -> Option<Stems> {
+    let n = stem.len();
+    let hk: String = stem.concat();
+    let neuter = linga == Linga::Napumsaka;
+    let head = |cut: usize, tail: &[&'static str]| -> Word { [&stem[..n - cut], tail].concat() };
+    if n < 2 {
+        return None;
+    }
+    Some(match &stem[n - 2..] {
+        // an-stems: upadhā lengthened in the strong cases (6.4.8), n dropped at the end of a pada (8.2.7), a elided in bha unless after a conjunct ending in m or v (6.4.134, 6.4.137)
+        ["a", "n"] => {
+            let conjunct = n >= 4 && matches!(stem[n - 3], "m" | "v") && is_consonant(stem[n - 4]);
+            let (weak, weak_alt) = if conjunct {
+                (stem.clone(), None)
+            } else {
+                // stoḥ ścunā ścuḥ (8.4.40)
+                let nasal = if stem[n - 3] == "j" { "J" } else { "n" };
+                (head(2, &[nasal]), Some(stem.clone()))
+            };
+            Stems {
+                strong: head(2, &["A", "n"]),
+                middle: head(1, &[]),
+                weak,
+                weak_alt,
+                nominative: vec![if neuter { head(1, &[]) } else { head(2, &["A"]) }],
+                vocative: if neuter { vec![stem.clone(), head(1, &[])] } else { vec![stem.clone()] },
+                plural: head(2, &["A", "n", "i"]),
+                locative: attach(&head(1, &[]), &["s", "u"]),
+            }
+        }
+        // in-stems: lengthened in the nominative singular (6.4.13)
+        ["i", "n"] => Stems {
+            strong: stem.clone(),
+            middle: head(1, &[]),
+            weak: stem.clone(),
+            weak_alt: None,
+            nominative: vec![if neuter { head(1, &[]) } else { head(2, &["I"]) }],
+            vocative: if neuter { vec![stem.clone(), head(1, &[])] } else { vec![stem.clone()] },
+            plural: head(2, &["I", "n", "i"]),
+            locative: attach(&head(1, &[]), &["s", "u"]),
+        },
+        // at-stems: num in the strong cases (7.1.70), and the lengthening of matup and vatup (6.4.14)
+        ["a", "t"] if n > 2 => {
+            let possessive = matches!(stem[n - 3], "m" | "v");
+            Stems {
+                strong: head(1, &["n", "t"]),
+                middle: head(1, &["d"]),
+                weak: stem.clone(),
+                weak_alt: None,
+                nominative: vec![if neuter { stem.clone() } else if possessive { head(2, &["A", "n"]) } else { head(1, &["n"]) }],
+                vocative: vec![if neuter { stem.clone() } else { head(1, &["n"]) }],
+                plural: head(1, &["n", "t", "i"]),
+                locative: attach(stem, &["s", "u"]),
+            }
+        }
+        // s-stems: the lengthening of as in the nominative singular (6.4.14), ru and its u before voiced consonants (8.2.66, 6.1.114)
+        [v, "s" | "S"] if matches!(*v, "a" | "i" | "u") => {
+            let sibilant = if *v == "a" { "s" } else { "S" };
+            Stems {
+                strong: head(1, &[sibilant]),
+                middle: if *v == "a" { head(2, &["o"]) } else { head(1, &["r"]) },
+                weak: head(1, &[sibilant]),
+                weak_alt: None,
+                nominative: vec![if *v == "a" && !neuter { head(2, &["A", "s"]) } else { stem.clone() }],
+                vocative: vec![stem.clone()],
+                plural: head(2, &[long(v), "M", sibilant, "i"]),
+                locative: head(1, &["H", sibilant, "u"]),
+            }
+        }
+        _ => {
+            let last = stem[n - 1];
+            let end = if KVIN.contains(&hk.as_str()) { head(1, &["k"]) } else { finish(stem.clone()) };
+            let mut middle = end.clone();
+            if let Some(last) = middle.last_mut() {
+                *last = voiced(last);
+            }
+            Stems {
+                strong: stem.clone(),
+                middle,
+                weak: stem.clone(),
+                weak_alt: None,
+                nominative: vec![end.clone()],
+                vocative: vec![end.clone()],
+                plural: head(1, &[nasal_of(last), last, "i"]),
+                locative: if KVIN.contains(&hk.as_str()) { attach(&end, &["s", "u"]) } else { attach(stem, &["s", "u"]) },
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vyakaran::varnas;
+
+    /// The 24 forms of a stem in HK, alternatives joined by "/".
+    fn paradigm(stem: &str, linga: Linga) -> String {
+        let mut cells = Vec::new();
+        for vibhakti in Vibhakti::ALL {
+            for vacana in Vacana::ALL {
+                let forms: Vec<String> = subanta(&varnas(stem), linga, vibhakti, vacana).iter()
+                    .map(|pada| pada.aksharas.iter().flat_map(|akshara| akshara.varnas.iter()).map(|varna| varna.hk).collect())
+                    .collect();
+                cells.push(forms.join("/"));
+            }
+        }
+        cells.join(" ")
+    }
+
+    #[test]
+    fn vowel_stems() {
+        assert_eq!(paradigm("rAma", Linga::Pum), "rAmaH rAmau rAmAH rAmam rAmau rAmAn rAmeNa rAmAbhyAm rAmaiH rAmAya rAmAbhyAm rAmebhyaH \
+            rAmAt rAmAbhyAm rAmebhyaH rAmasya rAmayoH rAmANAm rAme rAmayoH rAmeSu rAma rAmau rAmAH");
+        assert_eq!(paradigm("mati", Linga::Stri), "matiH matI matayaH matim matI matIH matyA matibhyAm matibhiH mataye/matyai matibhyAm matibhyaH \
+            mateH/matyAH matibhyAm matibhyaH mateH/matyAH matyoH matInAm matau/matyAm matyoH matiSu mate matI matayaH");
+        assert_eq!(paradigm("madhu", Linga::Napumsaka), "madhu madhunI madhUni madhu madhunI madhUni madhunA madhubhyAm madhubhiH madhune madhubhyAm madhubhyaH \
+            madhunaH madhubhyAm madhubhyaH madhunaH madhunoH madhUnAm madhuni madhunoH madhuSu madho/madhu madhunI madhUni");
+        assert_eq!(paradigm("nadI", Linga::Stri), "nadI nadyau nadyaH nadIm nadyau nadIH nadyA nadIbhyAm nadIbhiH nadyai nadIbhyAm nadIbhyaH \
+            nadyAH nadIbhyAm nadIbhyaH nadyAH nadyoH nadInAm nadyAm nadyoH nadISu nadi nadyau nadyaH");
+        assert_eq!(paradigm("pitR", Linga::Pum), "pitA pitarau pitaraH pitaram pitarau pitRRn pitrA pitRbhyAm pitRbhiH pitre pitRbhyAm pitRbhyaH \
+            pituH pitRbhyAm pitRbhyaH pituH pitroH pitRRNAm pitari pitroH pitRSu pitaH pitarau pitaraH");
+        assert!(paradigm("dAtR", Linga::Pum).starts_with("dAtA dAtArau dAtAraH"));
+        assert!(paradigm("dhI", Linga::Stri).contains("dhiyAm/dhInAm"));
+    }
+
+    #[test]
+    fn consonant_stems() {
+        assert_eq!(paradigm("rAjan", Linga::Pum), "rAjA rAjAnau rAjAnaH rAjAnam rAjAnau rAjJaH rAjJA rAjabhyAm rAjabhiH rAjJe rAjabhyAm rAjabhyaH \
+            rAjJaH rAjabhyAm rAjabhyaH rAjJaH rAjJoH rAjJAm rAjJi/rAjani rAjJoH rAjasu rAjan rAjAnau rAjAnaH");
+        assert_eq!(paradigm("manas", Linga::Napumsaka), "manaH manasI manAMsi manaH manasI manAMsi manasA manobhyAm manobhiH manase manobhyAm manobhyaH \
+            manasaH manobhyAm manobhyaH manasaH manasoH manasAm manasi manasoH manaHsu manaH manasI manAMsi");
+        assert!(paradigm("vAc", Linga::Stri).starts_with("vAk vAcau vAcaH vAcam vAcau vAcaH vAcA vAgbhyAm"));
+        assert!(paradigm("bhagavat", Linga::Pum).starts_with("bhagavAn bhagavantau"));
+        assert!(paradigm("karman", Linga::Napumsaka).starts_with("karma karmaNI karmANi"));
+        assert!(paradigm("Atman", Linga::Pum).contains("AtmanA"));
+    }
+
+    #[test]
+    fn at_stems() {
+        // Num before ī for the śatṛ of a root with śap, not for other at-stems
+        assert!(paradigm("gacchat", Linga::Napumsaka).starts_with("gacchat gacchantI gacchanti gacchat gacchantI gacchanti gacchatA"));
+        assert!(paradigm("gacchat", Linga::Stri).starts_with("gacchantI gacchantyau gacchantyaH"));
+        assert!(paradigm("gacchat", Linga::Pum).starts_with("gacchan gacchantau gacchantaH"));
+        assert!(paradigm("jagat", Linga::Napumsaka).starts_with("jagat jagatI jaganti"));
+        assert!(paradigm("bhagavat", Linga::Stri).starts_with("bhagavatI bhagavatyau"));
+    }
+
+    #[test]
+    fn pronouns() {
+        assert_eq!(paradigm("tad", Linga::Pum), "saH tau te tam tau tAn tena tAbhyAm taiH tasmai tAbhyAm tebhyaH \
+            tasmAt tAbhyAm tebhyaH tasya tayoH teSAm tasmin tayoH teSu   ");
+        assert!(paradigm("sarva", Linga::Pum).starts_with("sarvaH sarvau sarve"));
+        assert!(paradigm("sarva", Linga::Stri).contains("sarvasyai"));
+        assert!(paradigm("idam", Linga::Stri).starts_with("iyam ime imAH"));
+        assert!(paradigm("adas", Linga::Pum).starts_with("asau amU amI"));
+        assert_eq!(paradigm("asmad", Linga::Stri), paradigm("asmad", Linga::Pum));
+        assert!(paradigm("kim", Linga::Napumsaka).starts_with("kim ke kAni"));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("saptami".parse::<Vibhakti>(), Ok(Vibhakti::Saptami));
+        assert_eq!("Napumsakalinga".parse::<Linga>(), Ok(Linga::Napumsaka));
+        assert!("ablative".parse::<Vibhakti>().is_err());
+        assert_eq!(decline(&varnas("rAma"), Linga::Pum).len(), 24);
+    }
+}
@@ -0,0 +1,1343 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::shiksha::{lipi, Pada, ParseNameError, TransliterationScheme};
+use crate::vyakaran::dhatu::{dhatupatha, Dhatu, Gana, Idagama, Padi};
+use crate::vyakaran::prakriya::{attach, augment, finish, guna, is_consonant, is_jhal, is_short, is_vowel, last_vowel, long, pada, short, strengthen, vrddhi, word, Word};
+
+/// The ten tenses and moods (lakara) of the finite verb. Leṭ, found only
+/// in the Veda, is left out.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Lakara {
+    Lat,      // Present
+    Lit,      // Perfect
+    Lut,      // Periphrastic future
+    Lrt,      // Simple future
+    Lot,      // Imperative
+    Lan,      // Imperfect
+    VidhiLin, // Optative
+    AshirLin, // Benedictive
+    Lun,      // Aorist
+    Lrn,      // Conditional
+}
+
+impl Lakara {
+    pub const ALL: [Lakara; 10] = [
+        Lakara::Lat, Lakara::Lit, Lakara::Lut, Lakara::Lrt, Lakara::Lot,
+        Lakara::Lan, Lakara::VidhiLin, Lakara::AshirLin, Lakara::Lun, Lakara::Lrn,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lakara::Lat => "lat",
+            Lakara::Lit => "lit",
+            Lakara::Lut => "lut",
+            Lakara::Lrt => "lrt",
+            Lakara::Lot => "lot",
+            Lakara::Lan => "lan",
+            Lakara::VidhiLin => "vidhilin",
+            Lakara::AshirLin => "ashirlin",
+            Lakara::Lun => "lun",
+            Lakara::Lrn => "lrn",
+        }
+    }
+
+    /// Whether the endings are sārvadhātuka (3.4.113), taking the present stem.
+    fn sarvadhatuka(self) -> bool {
+        matches!(self, Lakara::Lat | Lakara::Lot | Lakara::Lan | Lakara::VidhiLin)
+    }
+}
+
+impl fmt::Display for Lakara {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a lakara in any letter case.
+impl FromStr for Lakara {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lakara::ALL.into_iter().find(|l| l.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("lakara", s))
+    }
+}
+
+/// Grammatical person, named from the one the verb speaks of outward.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Purusha {
+    Prathama, // Third person
+    Madhyama, // Second person
+    Uttama,   // First person
+}
+
+impl Purusha {
+    pub const ALL: [Purusha; 3] = [Purusha::Prathama, Purusha::Madhyama, Purusha::Uttama];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Purusha::Prathama => "prathama",
+            Purusha::Madhyama => "madhyama",
+            Purusha::Uttama => "uttama",
+        }
+    }
+}
+
+impl fmt::Display for Purusha {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a person in any letter case.
+impl FromStr for Purusha {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Purusha::ALL.into_iter().find(|p| p.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("purusha", s))
+    }
+}
+
+/// Grammatical number.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Vacana {
+    Eka,  // Singular
+    Dvi,  // Dual
+    Bahu, // Plural
+}
+
+impl Vacana {
+    pub const ALL: [Vacana; 3] = [Vacana::Eka, Vacana::Dvi, Vacana::Bahu];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Vacana::Eka => "ekavacana",
+            Vacana::Dvi => "dvivacana",
+            Vacana::Bahu => "bahuvacana",
+        }
+    }
+}
+
+impl fmt::Display for Vacana {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name of a number in any letter case.
+impl FromStr for Vacana {
+    type Err = ParseNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Vacana::ALL.into_iter().find(|v| v.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseNameError::new("vacana", s))
+    }
+}
+
+/// A finite verb form with what it expresses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Tinanta {
+    pub pada: Pada,
+    pub lakara: Lakara,
+    pub purusha: Purusha,
+    pub vacana: Vacana,
+    pub padi: Padi, // Parasmai or Atmane
+}
+
+/// Generates the finite forms of a root in one lakara, person, number and
+/// pada, with the vikarana of its class and the sandhi inside the word
+/// applied. Where grammar allows more than one form all are given, the
+/// commoner first. The result is empty if the root is not used in that pada;
+/// `Padi::Ubhaya` asks for the forms of both.
+pub fn tinanta(dhatu: &Dhatu, lakara: Lakara, padi: Padi, purusha: Purusha, vacana: Vacana) -> Vec<Pada> {
+    let index = purusha as usize * 3 + vacana as usize;
+    let mut words: Vec<Word> = Vec::new();
+    for atmane in [false, true] {
+        let asked = match padi {
+            Padi::Parasmai => !atmane,
+            Padi::Atmane => atmane,
+            Padi::Ubhaya => true,
+        };
+        if asked && takes(dhatu, lakara, atmane) {
+            for form in forms(dhatu, lakara, atmane, index).into_iter().map(finish) {
+                if !words.contains(&form) {
+                    words.push(form);
+                }
+            }
+        }
+    }
+    words.iter().map(|w| pada(w)).collect()
+}
+
+/// Generates every finite form of a root: all lakaras, persons and numbers
+/// in each pada the root takes.
+pub fn conjugate(dhatu: &Dhatu) -> Vec<Tinanta> {
+    let mut forms = Vec::new();
+    for padi in [Padi::Parasmai, Padi::Atmane] {
+        for lakara in Lakara::ALL {
+            for purusha in Purusha::ALL {
+                for vacana in Vacana::ALL {
+                    forms.extend(tinanta(dhatu, lakara, padi, purusha, vacana).into_iter()
+                        .map(|pada| Tinanta { pada, lakara, purusha, vacana, padi }));
+                }
+            }
+        }
+    }
+    forms
+}
+
+/// The suffix between the root and the ending of a form of liṭ, āśīrliṅ or
+/// luṅ, in HK: ām (3.1.35 to 3.1.39), yāsuṭ or sīyuṭ (3.4.102, 3.4.103) and
+/// the substitute of cli (3.1.43 to 3.1.66). None for the other lakaras and
+/// for a liṭ made by reduplication.
+pub(crate) fn ardhadhatuka(d: &Dhatu, form: &Tinanta) -> Option<&'static str> {
+    // asterbhūḥ, bruvo vaciḥ (2.4.52, 2.4.53)
+    let d = match lipi::render(&d.root, HK).as_str() {
+        "as" => lookup("bhU"),
+        "brU" => lookup("vac"),
+        _ => d,
+    };
+    let r = lipi::render(&d.root, HK);
+    let root = word(&r);
+    let atmane = form.padi == Padi::Atmane;
+    let index = form.purusha as usize * 3 + form.vacana as usize;
+    let made = |word: Word| pada(&finish(word)) == form.pada;
+    match form.lakara {
+        Lakara::Lit => amanta(d, &r, &root, atmane, index).into_iter().any(made).then_some("Am"),
+        Lakara::AshirLin => Some(if atmane { "sIyuT" } else { "yAsuT" }),
+        Lakara::Lun => lun_kinds(d, &r, &root, atmane, index).into_iter().find(|(_, w)| made(w.clone())).map(|(kind, _)| kind.pratyaya()),
+        _ => None,
+    }
+}
+
+const HK: TransliterationScheme = TransliterationScheme::HarvardKyoto;
+
+/// Whether a root takes the endings of a pada in a lakara. Mṛ is ātmanepadī
+/// only in the present system, liṅ and luṅ (1.3.61).
+fn takes(dhatu: &Dhatu, lakara: Lakara, atmane: bool) -> bool {
+    if lipi::render(&dhatu.root, HK) == "mR" {
+        return atmane == (lakara.sarvadhatuka() || matches!(lakara, Lakara::AshirLin | Lakara::Lun));
+    }
+    match dhatu.padi {
+        Padi::Parasmai => !atmane,
+        Padi::Atmane => atmane,
+        Padi::Ubhaya => true,
+    }
+}
+
+fn lookup(root: &str) -> &'static Dhatu {
+    dhatupatha().iter().find(|d| lipi::render(&d.root, HK) == root).unwrap_or_else(|| panic!("{root} is not in the Dhatupatha"))
+}
+
+/// The unfinished forms for one person and number, `index` counting third
+/// person singular to first person plural.
+fn forms(d: &Dhatu, lakara: Lakara, atmane: bool, index: usize) -> Vec<Word> {
+    let hk = lipi::render(&d.root, HK);
+    let r = hk.as_str();
+    if !lakara.sarvadhatuka() {
+        // asterbhūḥ, bruvo vaciḥ (2.4.52, 2.4.53)
+        match r {
+            "as" => return forms(lookup("bhU"), lakara, atmane, index),
+            "brU" => return forms(lookup("vac"), lakara, atmane, index),
+            _ => {}
+        }
+    }
+    let root = word(r);
+    match lakara {
+        Lakara::Lat | Lakara::Lot | Lakara::Lan | Lakara::VidhiLin => sarvadhatuka(d, r, &root, lakara, atmane, index),
+        Lakara::Lit => lit(d, r, &root, atmane, index),
+        Lakara::Lut => lut(d, r, &root, atmane, index),
+        Lakara::Lrt => lrt(d, r, &root, atmane, index, false),
+        Lakara::Lrn => lrt(d, r, &root, atmane, index, true),
+        Lakara::AshirLin => ashir(d, r, &root, atmane, index),
+        Lakara::Lun => lun(d, r, &root, atmane, index),
+    }
+}
+
+type Endings = [&'static str; 9];
+
+/// Endings after a stem in a, including the a, for laṭ, loṭ, laṅ and
+/// vidhiliṅ: parasmaipada, then ātmanepada.
+const THEMATIC: [[Endings; 2]; 4] = [
+    [["ati", "ataH", "anti", "asi", "athaH", "atha", "Ami", "AvaH", "AmaH"],
+     ["ate", "ete", "ante", "ase", "ethe", "adhve", "e", "Avahe", "Amahe"]],
+    [["atu", "atAm", "antu", "a", "atam", "ata", "Ani", "Ava", "Ama"],
+     ["atAm", "etAm", "antAm", "asva", "ethAm", "adhvam", "ai", "Avahai", "Amahai"]],
+    [["at", "atAm", "an", "aH", "atam", "ata", "am", "Ava", "Ama"],
+     ["ata", "etAm", "anta", "athAH", "ethAm", "adhvam", "e", "Avahi", "Amahi"]],
+    [["et", "etAm", "eyuH", "eH", "etam", "eta", "eyam", "eva", "ema"],
+     ["eta", "eyAtAm", "eran", "ethAH", "eyAthAm", "edhvam", "eya", "evahi", "emahi"]],
+];
+
+/// Endings after the stems of the classes that lose śap, for the same lakaras.
+const ATHEMATIC: [[Endings; 2]; 4] = [
+    [["ti", "taH", "anti", "si", "thaH", "tha", "mi", "vaH", "maH"],
+     ["te", "Ate", "ate", "se", "Athe", "dhve", "e", "vahe", "mahe"]],
+    [["tu", "tAm", "antu", "hi", "tam", "ta", "Ani", "Ava", "Ama"],
+     ["tAm", "AtAm", "atAm", "sva", "AthAm", "dhvam", "ai", "Avahai", "Amahai"]],
+    [["t", "tAm", "an", "s", "tam", "ta", "am", "va", "ma"],
+     ["ta", "AtAm", "ata", "thAH", "AthAm", "dhvam", "i", "vahi", "mahi"]],
+    [["yAt", "yAtAm", "yuH", "yAH", "yAtam", "yAta", "yAm", "yAva", "yAma"],
+     ["Ita", "IyAtAm", "Iran", "IthAH", "IyAthAm", "Idhvam", "Iya", "Ivahi", "Imahi"]],
+];
+
+fn sarva_index(lakara: Lakara) -> usize {
+    match lakara {
+        Lakara::Lat => 0,
+        Lakara::Lot => 1,
+        Lakara::Lan => 2,
+        _ => 3,
+    }
+}
+
+/// Roots of the tenth class ending in a, which keep their vowel short.
+const ADANTA: [&str; 2] = ["kath", "gaN"];
+
+/// Roots of the fourth class that take aṅ in luṅ (3.1.55).
+const PUSHADI: [&str; 6] = ["kup", "tuS", "muh", "sidh", "naz", "zam"];
+
+/// The present stem: a stem that takes the endings with their a, or the
+/// classes whose stem has strong and weak forms.
+enum Present {
+    Thematic(Word),
+    Athematic,
+}
+
+fn present(d: &Dhatu, r: &str, root: &Word) -> Present {
+    let stem = |hk: &str| Present::Thematic(word(hk));
+    match d.gana {
+        Gana::Bhvadi => match r {
+            "zru" => Present::Athematic,
+            "sthA" => stem("tiSTh"),
+            "pA" => stem("pib"),
+            "gam" => stem("gacch"),
+            "dRz" => stem("pazy"),
+            "sad" => stem("sId"),
+            "kram" => stem("krAm"),
+            _ => Present::Thematic(strengthen(root, guna)),
+        },
+        Gana::Divadi => match r {
+            "jan" => stem("jAy"),
+            "zam" => stem("zAmy"),
+            "div" => stem("dIvy"),
+            "vyadh" => stem("vidhy"),
+            _ => Present::Thematic([&root[..], &["y"]].concat()),
+        },
+        Gana::Tudadi => match r {
+            "iS" => stem("icch"),
+            "prach" => stem("pRcch"),
+            "muc" => stem("muJc"),
+            "sic" => stem("siJc"),
+            "mR" => stem("mriy"),
+            _ => Present::Thematic(root.clone()),
+        },
+        Gana::Curadi => Present::Thematic([&curadi(r, root)[..], &["a", "y"]].concat()),
+        _ => Present::Athematic,
+    }
+}
+
+/// The root of the tenth class before ṇic: vṛddhi of a final vowel or a
+/// light a (7.2.115, 7.2.116), guṇa of a light penultimate vowel (7.3.86).
+fn curadi(r: &str, root: &Word) -> Word {
+    if ADANTA.contains(&r) {
+        return root.clone();
+    }
+    match last_vowel(root) {
+        Some(i) if i + 1 == root.len() || (i + 2 == root.len() && root[i] == "a") => strengthen(root, vrddhi),
+        _ => strengthen(root, guna),
+    }
+}
+
+/// Whether an ending is pit and so takes the strong stem (1.2.4): the
+/// singular of laṭ and laṅ, and the singular and first person of loṭ.
+fn is_strong(lakara: Lakara, atmane: bool, index: usize) -> bool {
+    match (lakara, atmane) {
+        (Lakara::Lat | Lakara::Lan, false) => matches!(index, 0 | 3 | 6),
+        (Lakara::Lot, false) => matches!(index, 0 | 6 | 7 | 8),
+        (Lakara::Lot, true) => matches!(index, 6..=8),
+        _ => false,
+    }
+}
+
+/// Whether the stem is reduplicated, so that its third person plural
+/// parasmaipada drops the n (7.1.4) and laṅ takes jus (3.4.109).
+fn abhyasta(d: &Dhatu, r: &str) -> bool {
+    d.gana == Gana::Juhotyadi || matches!(r, "jAgR" | "zAs")
+}
+
+/// Whether the third person plural of laṅ parasmaipada takes jus: after a
+/// reduplicated stem and after vid (3.4.109).
+fn jus(d: &Dhatu, r: &str) -> bool {
+    abhyasta(d, r) || r == "vid"
+}
+
+/// The stems of the athematic classes before an ending beginning with `next`.
+fn athematic(d: &Dhatu, r: &str, root: &Word, strong: bool, next: &str) -> Vec<Word> {
+    let vowel = is_vowel(next);
+    let w = |hk: &str| vec![word(hk)];
+    let suffixed = |stem: &Word, suffix: &str| [&stem[..], &word(suffix)[..]].concat();
+    match (d.gana, r) {
+        (_, "zru") => if strong { w("zRno") } else if matches!(next, "m" | "v") { vec![word("zRnu"), word("zRn")] } else { w("zRnu") },
+        (Gana::Adadi, "as") => if strong { w("as") } else { w("s") },
+        (Gana::Adadi, "han") => if strong { w("han") } else if vowel { w("ghn") } else if is_jhal(next) { w("ha") } else { w("han") },
+        (Gana::Adadi, "brU") => if strong && !vowel { w("bravI") } else if strong { w("bro") } else { w("brU") },
+        (Gana::Adadi, "zI") => w("ze"),
+        (Gana::Adadi, "zAs") => if !strong && !vowel { w("ziS") } else { w("zAs") },
+        (Gana::Adadi, "jAgR") => if strong { w("jAgar") } else { w("jAgR") },
+        (Gana::Adadi, "rud" | "svap") => {
+            let stem = if strong { strengthen(root, guna) } else { root.clone() };
+            if !vowel && next != "y" { vec![attach(&stem, &["i"])] } else { vec![stem] }
+        }
+        (Gana::Adadi, _) if strong && !vowel && matches!(root.last(), Some(&("u" | "U"))) => vec![strengthen(root, vrddhi)],
+        (Gana::Adadi, _) if strong => vec![strengthen(root, guna)],
+        (Gana::Adadi, _) => vec![root.clone()],
+        (Gana::Juhotyadi, _) => {
+            let (strong_stem, weak, before_vowel) = match r {
+                "hu" => ("juho", "juhu", "juhu"),
+                "dA" => ("dadA", "dad", "dad"),
+                "dhA" => ("dadhA", if matches!(next, "t" | "th" | "s") { "dhat" } else if next == "dh" { "dhad" } else { "dadh" }, "dadh"),
+                "bhR" => ("bibhar", "bibhR", "bibhR"),
+                "hA" => ("jahA", if next == "y" { "jah" } else { "jahi" }, "jah"),
+                "bhI" => ("bibhe", "bibhI", "bibhI"),
+                "mA" => ("mimA", "mimI", "mim"),
+                "hrI" => ("jihre", "jihrI", "jihrI"),
+                _ => {
+                    let stem = [abhyasa(root), root.clone()].concat();
+                    return if strong { vec![strengthen(&stem, guna)] } else { vec![stem] };
+                }
+            };
+            if strong { w(strong_stem) } else if vowel { w(before_vowel) } else { w(weak) }
+        }
+        (Gana::Svadi, _) if strong => vec![suffixed(root, "no")],
+        (Gana::Svadi, _) if matches!(next, "m" | "v") && root.last().is_some_and(|v| is_vowel(v)) => vec![suffixed(root, "nu"), suffixed(root, "n")],
+        (Gana::Svadi, _) => vec![suffixed(root, "nu")],
+        (Gana::Rudhadi, _) => {
+            // śnam goes after the last vowel, taking the place of a nasal already there
+            let mut base = root.clone();
+            let n = base.len();
+            if n >= 2 && matches!(base[n - 2], "G" | "J" | "N" | "n" | "m" | "M") {
+                base.remove(n - 2);
+            }
+            let n = base.len();
+            let infix = if strong { vec!["n", "a"] } else { vec![crate::vyakaran::prakriya::nasal_of(base[n - 1])] };
+            vec![[&base[..n - 1], &infix[..], &base[n - 1..]].concat()]
+        }
+        (Gana::Tanadi, "kR") => if strong { w("karo") } else if matches!(next, "m" | "v" | "y") { w("kur") } else { w("kuru") },
+        (Gana::Tanadi, _) if strong => vec![suffixed(&strengthen(root, guna), "o")],
+        (Gana::Tanadi, _) if matches!(next, "m" | "v") => vec![suffixed(root, "u"), root.clone()],
+        (Gana::Tanadi, _) => vec![suffixed(root, "u")],
+        (Gana::Kryadi, _) => {
+            let base = kryadi(r, root);
+            if strong { vec![suffixed(&base, "nA")] } else if vowel { vec![suffixed(&base, "n")] } else { vec![suffixed(&base, "nI")] }
+        }
+        _ => vec![root.clone()],
+    }
+}
+
+/// The root of the ninth class before śnā: pū and lū shorten (7.3.80), jñā
+/// becomes jā (7.3.79), grah takes samprasāraṇa and bandh loses its nasal.
+fn kryadi(r: &str, root: &Word) -> Word {
+    match r {
+        "pU" => word("pu"),
+        "lU" => word("lu"),
+        "jJA" => word("jA"),
+        "grah" => word("gRh"),
+        "bandh" => word("badh"),
+        _ => root.clone(),
+    }
+}
+
+fn sarvadhatuka(d: &Dhatu, r: &str, root: &Word, lakara: Lakara, atmane: bool, index: usize) -> Vec<Word> {
+    let table = sarva_index(lakara);
+    let lan = lakara == Lakara::Lan;
+    let tat = lakara == Lakara::Lot && !atmane && matches!(index, 0 | 3); // tātaṅ (7.1.35)
+
+    if let Present::Thematic(stem) = present(d, r, root) {
+        let stem = if lan { augment(&stem) } else { stem };
+        let mut forms = vec![attach(&stem, &word(THEMATIC[table][atmane as usize][index]))];
+        if tat {
+            forms.push(attach(&stem, &word("atAt")));
+        }
+        return forms;
+    }
+
+    let mut ending = word(ATHEMATIC[table][atmane as usize][index]);
+    let strong = is_strong(lakara, atmane, index) || (lan && !atmane && index == 2 && abhyasta(d, r));
+    if !atmane && index == 2 && abhyasta(d, r) {
+        ending = match lakara {
+            Lakara::Lat => word("ati"),
+            Lakara::Lot => word("atu"),
+            _ => ending,
+        };
+    }
+    if lan && !atmane && index == 2 && jus(d, r) {
+        ending = word("uH");
+    }
+    if r == "zI" && index == 2 && matches!(lakara, Lakara::Lat | Lakara::Lot | Lakara::Lan) {
+        ending.insert(0, "r"); // śīṅo ruṭ (7.1.6); liṅ has ran for jha (3.4.105)
+    }
+
+    // Forms that the general rules do not reach
+    if lan && !atmane && matches!(index, 0 | 3) {
+        let person = if index == 0 { "t" } else { "s" };
+        match r {
+            "as" => return vec![word(&format!("AsI{person}"))],
+            "ad" => return vec![word(&format!("Ada{person}"))],
+            "rud" | "svap" => {
+                let stem = augment(&strengthen(root, guna));
+                return vec![[&stem[..], &word(&format!("I{person}"))[..]].concat(), [&stem[..], &word(&format!("a{person}"))[..]].concat()];
+            }
+            _ => {}
+        }
+    }
+    if lan && r == "as" {
+        return vec![attach(&word("As"), &ending)];
+    }
+    if r == "as" && lakara == Lakara::Lat && index == 3 {
+        return vec![word("asi")];
+    }
+    if lakara == Lakara::Lot && !atmane && index == 3 {
+        let special = match r {
+            "as" => Some("edhi"),
+            "han" => Some("jahi"),
+            "dA" => Some("dehi"),
+            "dhA" => Some("dhehi"),
+            "zAs" => Some("zAdhi"),
+            "hu" => Some("juhudhi"),
+            _ => None,
+        };
+        let mut forms = match special {
+            Some(form) => vec![word(form)],
+            None => athematic(d, r, root, false, "h").into_iter().map(|stem| imperative(d, r, root, stem)).collect(),
+        };
+        forms.extend(athematic(d, r, root, false, "t").into_iter().map(|stem| attach(&stem, &word("tAt"))));
+        return forms;
+    }
+
+    let mut forms = Vec::new();
+    for stem in athematic(d, r, root, strong, ending[0]) {
+        let stem = if lan { augment(&stem) } else { stem };
+        if lan && !atmane && matches!(index, 0 | 3) && stem.last().is_some_and(|v| is_consonant(v)) {
+            // halṅyābbhyaḥ (6.1.68); a final s becomes t, and optionally so before si (8.2.73, 8.2.74);
+            // a final d optionally becomes ru before si (8.2.75)
+            let n = stem.len();
+            if stem[n - 1] == "s" {
+                let t = [&stem[..n - 1], &["t"][..]].concat();
+                if index == 3 {
+                    forms.push(stem);
+                }
+                forms.push(t);
+            } else if stem[n - 1] == "d" && index == 3 {
+                let ru = [&stem[..n - 1], &["s"][..]].concat();
+                forms.push(stem);
+                forms.push(ru);
+            } else {
+                forms.push(stem);
+            }
+        } else if lan && !atmane && index == 2 && abhyasta(d, r) && stem.last() == Some(&"A") {
+            forms.push(attach(&stem[..stem.len() - 1], &ending)); // usi ca (6.1.96)
+        } else {
+            forms.push(attach(&stem, &ending));
+        }
+    }
+    if tat {
+        forms.extend(athematic(d, r, root, false, "t").into_iter().map(|stem| attach(&stem, &word("tAt"))));
+    }
+    if r == "vid" && lakara == Lakara::Lat && !atmane {
+        // vido laṭo vā (3.4.83): the endings of liṭ, without reduplication
+        let stem = if is_strong(lakara, atmane, index) { strengthen(root, guna) } else { root.clone() };
+        forms.push(attach(&stem, &word(LIT[0][index])));
+    }
+    forms
+}
+
+/// The second person singular of loṭ parasmaipada: hi is dropped after the
+/// u of the fifth and eighth classes (6.4.106), becomes āna after a
+/// consonant in the ninth (3.1.83) and dhi after other consonants (6.4.101).
+fn imperative(d: &Dhatu, r: &str, root: &Word, stem: Word) -> Word {
+    let n = stem.len();
+    if matches!(d.gana, Gana::Svadi | Gana::Tanadi) || r == "zru" {
+        let conjunct = n >= 3 && is_consonant(stem[n - 2]) && is_consonant(stem[n - 3]);
+        if stem[n - 1] == "u" && !conjunct {
+            return stem;
+        }
+    }
+    if d.gana == Gana::Kryadi && root.last().is_some_and(|v| is_consonant(v)) {
+        return attach(&kryadi(r, root), &word("Ana"));
+    }
+    if is_jhal(stem[n - 1]) {
+        attach(&stem, &word("dhi"))
+    } else {
+        attach(&stem, &word("hi"))
+    }
+}
+
+/// A root ending in a diphthong takes ā before ārdhadhātuka endings (6.1.45).
+fn eca(root: &Word) -> Word {
+    match root.split_last() {
+        Some((&("e" | "ai" | "o" | "au"), rest)) => [rest, &["A"]].concat(),
+        _ => root.clone(),
+    }
+}
+
+/// The stem before an ārdhadhātuka suffix that is not kit: guṇa of the
+/// root (7.3.84, 7.3.86), or the stem with ṇic for the tenth class.
+fn base(d: &Dhatu, r: &str, root: &Word) -> Word {
+    if d.gana == Gana::Curadi {
+        return [&curadi(r, root)[..], &["a", "y"]].concat();
+    }
+    strengthen(&eca(root), guna)
+}
+
+/// The stem before a suffix beginning with a consonant other than y, with no
+/// iṭ: dṛś, sṛj and spṛś take am (6.1.58) and naś takes num (7.1.60).
+fn anit_base(d: &Dhatu, r: &str, root: &Word) -> Word {
+    match r {
+        "dRz" => word("draz"),
+        "sRj" => word("sraj"),
+        "spRz" => word("spraz"),
+        "naz" => word("nanz"),
+        _ => base(d, r, root),
+    }
+}
+
+/// The iṭ augments a root takes before a valādi ārdhadhātuka suffix, None
+/// for none: a seṭ root takes iṭ (7.2.35), an aniṭ root does not (7.2.10)
+/// and a veṭ root may. The future of roots in ṛ, han and gam takes iṭ
+/// (7.2.70, 7.2.58); grah lengthens it (7.2.37), and vṛ and tṝ may (7.2.38).
+fn its(d: &Dhatu, r: &str, lakara: Lakara, atmane: bool) -> Vec<Option<&'static str>> {
+    let future = matches!(lakara, Lakara::Lut | Lakara::Lrt | Lakara::Lrn);
+    let set = match r {
+        "grah" => vec![Some("I")],
+        "vR" | "tRR" if future => vec![Some("i"), Some("I")],
+        _ => vec![Some("i")],
+    };
+    if d.gana == Gana::Curadi || (matches!(lakara, Lakara::Lrt | Lakara::Lrn) && (r.ends_with('R') || r == "han" || (r == "gam" && !atmane))) {
+        return set;
+    }
+    match d.idagama {
+        Idagama::Set => set,
+        Idagama::Anit => vec![None],
+        Idagama::Vet => [set, vec![None]].concat(),
+    }
+}
+
+/// Endings of luṭ, the agent noun in tṛc with the present of as.
+const LUT: [Endings; 2] = [
+    ["tA", "tArau", "tAraH", "tAsi", "tAsthaH", "tAstha", "tAsmi", "tAsvaH", "tAsmaH"],
+    ["tA", "tArau", "tAraH", "tAse", "tAsAthe", "tAdhve", "tAhe", "tAsvahe", "tAsmahe"],
+];
+
+fn lut(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<Word> {
+    let ending = word(LUT[atmane as usize][index]);
+    its(d, r, Lakara::Lut, atmane).into_iter().map(|it| {
+        let stem = match it {
+            Some(i) => attach(&base(d, r, root), &[i]),
+            None => anit_base(d, r, root),
+        };
+        attach(&stem, &ending)
+    }).collect()
+}
+
+/// Lṛṭ, or lṛṅ with the augment: the stem in sya with the endings of laṭ or laṅ.
+fn lrt(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize, conditional: bool) -> Vec<Word> {
+    let endings = if conditional { THEMATIC[2] } else { THEMATIC[0] };
+    its(d, r, Lakara::Lrt, atmane).into_iter().map(|it| {
+        let stem = match it {
+            Some(i) => attach(&base(d, r, root), &[i]),
+            None => sa(anit_base(d, r, root)),
+        };
+        let stem = attach(&stem, &["s", "y"]);
+        let stem = if conditional { augment(&stem) } else { stem };
+        attach(&stem, &word(endings[atmane as usize][index]))
+    }).collect()
+}
+
+/// A root's final s becomes t before an ārdhadhātuka s (7.4.49).
+fn sa(mut stem: Word) -> Word {
+    if stem.last() == Some(&"s") {
+        let n = stem.len();
+        stem[n - 1] = "t";
+    }
+    stem
+}
+
+const ASHIR: [Endings; 2] = [
+    ["yAt", "yAstAm", "yAsuH", "yAH", "yAstam", "yAsta", "yAsam", "yAsva", "yAsma"],
+    ["sISTa", "sIyAstAm", "sIran", "sISThAH", "sIyAsthAm", "sIdhvam", "sIya", "sIvahi", "sImahi"],
+];
+
+/// Āśīrliṅ. Parasmaipada yāsuṭ is kit (3.4.104), so the root is weak:
+/// samprasāraṇa, final i and u lengthened (7.4.25), ṛ to ri (7.4.28), the
+/// ā of ghu, mā, sthā, gā, pā and hā to e (6.4.67), and ṇic dropped.
+fn ashir(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<Word> {
+    let ending = word(ASHIR[atmane as usize][index]);
+    if !atmane {
+        return kit(d, r, root).iter().map(|stem| attach(stem, &ending)).collect();
+    }
+    its(d, r, Lakara::AshirLin, atmane).into_iter().map(|it| match it {
+        Some(i) => attach(&attach(&base(d, r, root), &[i]), &ending),
+        None => {
+            let stem = sa(atmane_stem(r, root));
+            attach(&stem, &murdhanya(&stem, ending.clone()))
+        }
+    }).collect()
+}
+
+/// The dh of an ending becomes ḍh after a stem in a vowel other than a (8.3.78).
+fn murdhanya(stem: &Word, mut ending: Word) -> Word {
+    if stem.last().is_some_and(|v| is_vowel(v) && !matches!(*v, "a" | "A")) {
+        if let Some(dh) = ending.iter().position(|v| *v == "dh") {
+            ending[dh] = "Dh";
+        }
+    }
+    ending
+}
+
+/// The root before a kit or ṅit ārdhadhātuka suffix, in the alternatives
+/// grammar allows.
+fn kit(d: &Dhatu, r: &str, root: &Word) -> Vec<Word> {
+    if d.gana == Gana::Curadi {
+        return vec![curadi(r, root)];
+    }
+    if let Some(weak) = samprasarana(r) {
+        return vec![word(weak)];
+    }
+    let root = eca(root);
+    let n = root.len();
+    let conjunct = n >= 3 && is_consonant(root[0]) && is_consonant(root[1]);
+    match r {
+        "jAgR" => return vec![word("jAgar")],
+        "zAs" => return vec![word("ziS")],
+        "bandh" => return vec![word("badh")],
+        "prach" => return vec![word("pRch")],
+        "bhaJj" => return vec![word("bhaj")],
+        "han" => return vec![word("vadh")],
+        "sthA" | "dA" | "dhA" | "pA" | "gai" | "mA" | "hA" => return vec![[&root[..n - 1], &["e"]].concat()],
+        _ => {}
+    }
+    match root[n - 1] {
+        "R" if conjunct => vec![strengthen(&root, guna)],
+        "R" => vec![[&root[..n - 1], &["r", "i"]].concat()],
+        "RR" => vec![[&root[..n - 1], &["I", "r"]].concat()],
+        "i" | "u" => vec![[&root[..n - 1], &[long(root[n - 1])]].concat()],
+        "A" if conjunct => vec![root.clone(), [&root[..n - 1], &["e"]].concat()],
+        _ => vec![root],
+    }
+}
+
+/// The weak form of the roots that take samprasāraṇa before kit suffixes (6.1.15, 6.1.16).
+fn samprasarana(r: &str) -> Option<&'static str> {
+    match r {
+        "yaj" => Some("ij"),
+        "vac" => Some("uc"),
+        "vad" => Some("ud"),
+        "vas" => Some("uS"),
+        "vah" => Some("uh"),
+        "svap" => Some("sup"),
+        "grah" => Some("gRh"),
+        "vyadh" => Some("vidh"),
+        _ => None,
+    }
+}
+
+/// The root before the s of āśīrliṅ and luṅ ātmanepada with no iṭ, kit
+/// after ṛ and after a consonant preceded by i, u or ṛ (1.2.11, 1.2.12);
+/// other roots in a vowel take guṇa.
+fn atmane_stem(r: &str, root: &Word) -> Word {
+    let root = eca(root);
+    match root.last() {
+        Some(&("R" | "RR")) => root,
+        Some(&("i" | "I" | "u" | "U")) => strengthen(&root, guna),
+        _ if r == "naz" => word("nanz"),
+        _ => root,
+    }
+}
+
+const LIT: [Endings; 2] = [
+    ["a", "atuH", "uH", "tha", "athuH", "a", "a", "va", "ma"],
+    ["e", "Ate", "ire", "se", "Athe", "dhve", "e", "vahe", "mahe"],
+];
+
+/// Liṭ of as, used after ām.
+const AS_LIT: Endings = ["Asa", "AsatuH", "AsuH", "Asitha", "AsathuH", "Asa", "Asa", "Asiva", "Asima"];
+
+/// Roots that take no iṭ in liṭ (7.2.13).
+const KRADI: [&str; 8] = ["kR", "sR", "bhR", "vR", "stu", "dru", "sru", "zru"];
+
+/// Liṭ: ām with the liṭ of kṛ, bhū and as for the tenth class, ās and roots
+/// beginning with a heavy vowel other than a (3.1.35 to 3.1.39), otherwise
+/// the reduplicated root.
+fn lit(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<Word> {
+    if r == "i" {
+        let forms: [&[&str]; 9] = [&["iyAya"], &["IyatuH"], &["IyuH"], &["iyayitha", "iyetha"], &["IyathuH"], &["Iya"], &["iyAya", "iyaya"], &["Iyiva"], &["Iyima"]];
+        return forms[index].iter().map(|form| word(form)).collect();
+    }
+    let first = root[0];
+    let heavy = is_vowel(first) && !matches!(first, "a" | "A") && (!is_short(first) || root.iter().skip(1).take_while(|v| is_consonant(v)).count() > 1);
+    let periphrastic = d.gana == Gana::Curadi || r == "As" || heavy;
+    let mut forms = Vec::new();
+    if periphrastic || matches!(r, "vid" | "jAgR" | "bhI" | "hrI" | "bhR" | "hu") {
+        forms.extend(amanta(d, r, root, atmane, index));
+    }
+    if !periphrastic {
+        forms.splice(0..0, reduplicated(d, r, root, atmane, index));
+    }
+    forms
+}
+
+/// The root with ām and the liṭ of kṛ, bhū and as. Vid takes ām without
+/// guṇa, the ām being treated as kit (3.1.38).
+fn amanta(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<Word> {
+    let stem = match d.gana {
+        _ if r == "vid" => root.clone(),
+        Gana::Juhotyadi => athematic(d, r, root, true, "A").remove(0),
+        _ => base(d, r, root),
+    };
+    let am = attach(&stem, &["A", "m"]);
+    let kr = lookup("kR");
+    let bhu = lookup("bhU");
+    let auxiliaries = [lit(kr, "kR", &word("kR"), atmane, index), lit(bhu, "bhU", &word("bhU"), false, index), vec![word(AS_LIT[index])]];
+    auxiliaries.concat().into_iter().map(|aux| {
+        let mut am = am.clone();
+        if is_consonant(aux[0]) {
+            let n = am.len();
+            am[n - 1] = "M";
+        }
+        [am, aux].concat()
+    }).collect()
+}
+
+/// The syllable of reduplication (6.1.8): the first consonant, or the stop
+/// after an initial sibilant (7.4.61), unaspirated (8.4.54) and with velars
+/// and h turned palatal (7.4.62), and the root's vowel made short (7.4.59),
+/// with ṛ as a (7.4.66).
+fn abhyasa(root: &Word) -> Word {
+    let Some(v) = root.iter().position(|hk| is_vowel(hk)) else { return Vec::new() };
+    let consonants = &root[..v];
+    let consonant = match consonants {
+        [] => None,
+        [s, stop, ..] if matches!(*s, "s" | "S" | "z") && is_jhal(stop) && !matches!(*stop, "s" | "S" | "z" | "h") => Some(*stop),
+        [c, ..] => Some(*c),
+    };
+    let vowel = match short(root[v]) {
+        "R" | "lR" => "a",
+        vowel => vowel,
+    };
+    let mut abhyasa: Word = consonant.map(|c| match c {
+        "k" | "kh" => "c",
+        "g" | "gh" | "h" => "j",
+        c => match c {
+            "ch" => "c",
+            "jh" => "j",
+            "Th" => "T",
+            "Dh" => "D",
+            "th" => "t",
+            "dh" => "d",
+            "ph" => "p",
+            "bh" => "b",
+            c => c,
+        },
+    }).into_iter().collect();
+    abhyasa.push(vowel);
+    abhyasa
+}
+
+enum Grade {
+    Vrddhi,
+    Guna,
+    Weak,
+}
+
+fn reduplicated(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<Word> {
+    let root = eca(root);
+    let ending = word(LIT[atmane as usize][index]);
+    let n = root.len();
+    let grades = match (atmane, index) {
+        (false, 0) => vec![Grade::Vrddhi],
+        (false, 6) => vec![Grade::Vrddhi, Grade::Guna], // ṇal uttamo vā (7.1.91)
+        (false, 3) => vec![Grade::Guna],
+        _ => vec![Grade::Weak],
+    };
+    let its: Vec<bool> = if !is_consonant(ending[0]) || KRADI.contains(&r) {
+        vec![false]
+    } else if !atmane && index == 3 && r != "ad" {
+        // thal: the aniṭ roots ending in a vowel or with a take iṭ optionally, those in ṛ never (7.2.61 to 7.2.63)
+        match d.idagama {
+            Idagama::Set => vec![true],
+            Idagama::Vet => vec![false, true],
+            Idagama::Anit if root[n - 1] == "R" => vec![false],
+            Idagama::Anit if is_vowel(root[n - 1]) || root.contains(&"a") || matches!(r, "dRz" | "sRj") => vec![false, true],
+            Idagama::Anit => vec![true],
+        }
+    } else {
+        vec![true]
+    };
+    let source = samprasarana(r).map(word).unwrap_or_else(|| root.clone());
+    let abhyasa = if r == "bhU" { word("ba") } else { abhyasa(&source) };
+
+    let mut forms = Vec::new();
+    for grade in &grades {
+        for &it in &its {
+            for mut body in body(r, &root, grade, it) {
+                // ekahalmadhye 'nādeśādeḥ: e for a between single consonants, with no reduplication (6.4.120 to 6.4.122)
+                let weak = matches!(grade, Grade::Weak) || (index == 3 && it && !atmane);
+                let plain = n == 3 && root[1] == "a" && is_consonant(root[0]) && is_consonant(root[2]) && root[2] != "ch"
+                    && root[0] != "v" && abhyasa.first() == Some(&root[0]) && samprasarana(r).is_none() && !matches!(r, "gam" | "han" | "jan");
+                let stem = if weak && (plain || r == "bhaj") {
+                    vec![root[0], "e", root[2]]
+                } else if weak && r == "tRR" {
+                    word("ter")
+                } else {
+                    match r {
+                        "ji" => body[0] = "g",
+                        "han" => body[0] = "gh",
+                        _ => {}
+                    }
+                    join(d, &abhyasa, body)
+                };
+                let mut stem = stem;
+                let mut ending = ending.clone();
+                if matches!(stem.last(), Some(&"A")) && (matches!(grade, Grade::Vrddhi) || (!atmane && index == 6)) {
+                    stem.pop();
+                    ending = word("au"); // āta au ṇalaḥ (7.1.34)
+                } else if matches!(stem.last(), Some(&"A")) && (it || is_vowel(ending[0])) {
+                    stem.pop(); // āto lopa iṭi ca (6.4.64)
+                }
+                if (it || is_vowel(ending[0])) && r != "bhU" {
+                    let n = stem.len();
+                    let conjunct = n >= 3 && is_consonant(stem[n - 2]) && is_consonant(stem[n - 3]);
+                    match stem[n - 1] {
+                        // iyaṅ and uvaṅ (6.4.77), yaṇ after a single consonant (6.4.82)
+                        "u" | "U" => stem.splice(n - 1.., ["u", "v"]).for_each(drop),
+                        "i" | "I" if conjunct => stem.splice(n - 1.., ["i", "y"]).for_each(drop),
+                        "i" | "I" => stem[n - 1] = "y",
+                        _ => {}
+                    }
+                }
+                let stem = if it { attach(&stem, &["i"]) } else { stem };
+                let ending = if it { ending } else { murdhanya(&stem, ending) };
+                forms.push(attach(&stem, &ending));
+            }
+        }
+    }
+    if r == "ci" {
+        let k: Vec<Word> = forms.iter().map(|form| [&form[..2], &["k"], &form[3..]].concat()).collect();
+        forms.extend(k);
+    }
+    forms
+}
+
+/// The root after the reduplication, in the grade the ending asks for, and
+/// its alternatives.
+fn body(r: &str, root: &Word, grade: &Grade, it: bool) -> Vec<Word> {
+    let n = root.len();
+    let conjunct = n >= 3 && is_consonant(root[0]) && is_consonant(root[1]);
+    match r {
+        "bhU" => return vec![word("bhUv")], // bhuvo vug (6.4.88)
+        "jAgR" if matches!(grade, Grade::Vrddhi) => return vec![word("jAgAr")],
+        "jAgR" => return vec![word("jAgar")],
+        "dRz" | "sRj" | "spRz" if matches!(grade, Grade::Guna) && !it => return vec![anit_am(r)],
+        _ => {}
+    }
+    match grade {
+        Grade::Vrddhi => match last_vowel(root) {
+            Some(v) if v + 1 == n => vec![strengthen(root, vrddhi)],
+            Some(v) if v + 2 == n && root[v] == "a" && root[v + 1] != "ch" => vec![strengthen(root, vrddhi)],
+            _ => vec![strengthen(root, guna)],
+        },
+        Grade::Guna => vec![strengthen(root, guna)],
+        Grade::Weak => {
+            if let Some(weak) = samprasarana(r) {
+                return vec![word(weak)];
+            }
+            match r {
+                "gam" => return vec![word("gm")], // gamahanajanakhanaghasāṃ lopaḥ (6.4.98)
+                "han" => return vec![word("hn")],
+                "jan" => return vec![word("jJ")],
+                _ => {}
+            }
+            match root[n - 1] {
+                "R" if conjunct => vec![strengthen(root, guna)], // ṛcchatyṝtām (7.4.11)
+                "RR" => vec![strengthen(root, guna)],
+                _ => vec![root.clone()],
+            }
+        }
+    }
+}
+
+fn anit_am(r: &str) -> Word {
+    match r {
+        "dRz" => word("draz"),
+        "sRj" => word("sraj"),
+        _ => word("spraz"),
+    }
+}
+
+/// Joins the reduplication to the root. A vowel of the reduplication merges
+/// with a like vowel and otherwise takes iyaṅ or uvaṅ (6.4.78); the s of a
+/// root taught with ṣ becomes ṣ after i or u (8.3.59).
+fn join(d: &Dhatu, abhyasa: &Word, mut body: Word) -> Word {
+    if abhyasa.len() == 1 {
+        let a = abhyasa[0];
+        return match (a, body[0]) {
+            ("a", "a" | "A") | ("i", "i" | "I") | ("u", "u" | "U") => [&[long(a)][..], &body[1..]].concat(),
+            ("i", b) if is_vowel(b) => [&["i", "y"][..], &body[..]].concat(),
+            ("u", b) if is_vowel(b) => [&["u", "v"][..], &body[..]].concat(),
+            _ => [abhyasa.clone(), body].concat(),
+        };
+    }
+    let taught = lipi::render(&d.upadesha, HK);
+    let taught = taught.trim_start_matches("Du").trim_start_matches("Ji").trim_start_matches("Tu");
+    if taught.starts_with('S') && body[0] == "s" && abhyasa.last() != Some(&"a") {
+        body[0] = "S";
+        body[1] = match body[1] {
+            "t" => "T",
+            "th" => "Th",
+            hk => hk,
+        };
+    }
+    [abhyasa.clone(), body].concat()
+}
+
+/// The kinds of luṅ, by the substitute of cli (3.1.43 to 3.1.66).
+#[derive(PartialEq)]
+enum Aorist {
+    Root, // sic elided (2.4.77)
+    An,   // aṅ
+    Can,  // caṅ, with reduplication
+    S,    // sic
+    Is,   // sic with iṭ
+    Sis,  // sic with iṭ and saK
+    Ksa,  // ksa
+}
+
+impl Aorist {
+    /// The suffix that takes the place of cli, in HK.
+    fn pratyaya(&self) -> &'static str {
+        match self {
+            Aorist::Root | Aorist::S | Aorist::Is | Aorist::Sis => "sic",
+            Aorist::An => "aG",
+            Aorist::Can => "caG",
+            Aorist::Ksa => "ksa",
+        }
+    }
+}
+
+const S_P: Endings = ["sIt", "stAm", "suH", "sIH", "stam", "sta", "sam", "sva", "sma"];
+const S_A: Endings = ["sta", "sAtAm", "sata", "sthAH", "sAthAm", "dhvam", "si", "svahi", "smahi"];
+const IS_P: Endings = ["It", "iSTAm", "iSuH", "IH", "iSTam", "iSTa", "iSam", "iSva", "iSma"];
+const IS_A: Endings = ["iSTa", "iSAtAm", "iSata", "iSThAH", "iSAthAm", "idhvam", "iSi", "iSvahi", "iSmahi"];
+const SIS_P: Endings = ["sIt", "siSTAm", "siSuH", "sIH", "siSTam", "siSTa", "siSam", "siSva", "siSma"];
+const KSA_A: Endings = ["ata", "AtAm", "anta", "athAH", "AthAm", "adhvam", "i", "Avahi", "Amahi"];
+
+fn aorists(d: &Dhatu, r: &str, root: &Word, atmane: bool) -> Vec<Aorist> {
+    if d.gana == Gana::Curadi {
+        return vec![Aorist::Can];
+    }
+    if !atmane && matches!(r, "bhU" | "sthA" | "dA" | "dhA" | "pA") {
+        return vec![Aorist::Root];
+    }
+    if !atmane && matches!(r, "su" | "stu") {
+        return vec![Aorist::Is]; // stusudhūñbhyaḥ parasmaipadeṣu (7.2.72)
+    }
+    let marked = |it: &str| d.anubandhas.iter().any(|a| a.iter().map(|v| v.hk).collect::<String>() == it);
+    if !atmane && (marked("lR") || PUSHADI.contains(&r) || matches!(r, "vac" | "zAs" | "sic" | "sR")) {
+        return vec![Aorist::An];
+    }
+    let n = root.len();
+    let shal = matches!(root[n - 1], "z" | "S" | "h");
+    if shal && n >= 2 && matches!(root[n - 2], "i" | "u" | "R") && d.idagama == Idagama::Anit && r != "dRz" {
+        return vec![Aorist::Ksa]; // śala igupadhād aniṭaḥ ksaḥ (3.1.45)
+    }
+    let mut kinds = Vec::new();
+    if !atmane && marked("ir") {
+        kinds.push(Aorist::An); // irito vā (3.1.57)
+    }
+    if !atmane && eca(root).last() == Some(&"A") {
+        kinds.push(Aorist::Sis); // yamaramanamātāṃ sak ca (7.2.73)
+        return kinds;
+    }
+    for it in its(d, r, Lakara::Lun, atmane) {
+        kinds.push(if it.is_some() { Aorist::Is } else { Aorist::S });
+    }
+    kinds
+}
+
+fn lun(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<Word> {
+    lun_kinds(d, r, root, atmane, index).into_iter().map(|(_, form)| form).collect()
+}
+
+/// The forms of luṅ, each with the kind of aorist it is.
+fn lun_kinds(d: &Dhatu, r: &str, root: &Word, atmane: bool, index: usize) -> Vec<(Aorist, Word)> {
+    let lan = THEMATIC[2][atmane as usize][index];
+    match r {
+        "i" => return root_aorist(&word("gA"), index).into_iter().map(|form| (Aorist::Root, form)).collect(), // iṇo gā luṅi (2.4.45)
+        "ad" => return vec![(Aorist::An, attach(&augment(&word("ghas")), &word(lan)))], // luṅsanor ghasḷ (2.4.37)
+        "han" if !atmane => return vec![(Aorist::Is, attach(&augment(&word("vadh")), &word(IS_P[index])))], // luṅi ca (2.4.43)
+        _ => {}
+    }
+    aorists(d, r, root, atmane).into_iter().map(|kind| {
+        let form = match kind {
+            Aorist::Root => root_aorist(root, index).remove(0),
+            Aorist::An => attach(&augment(&an_stem(r, root)), &word(lan)),
+            Aorist::Can => attach(&augment(&can_stem(r, root)), &word(lan)),
+            Aorist::S if atmane => {
+                let mut stem = atmane_stem(r, root);
+                if matches!(r, "sthA" | "dA" | "dhA") {
+                    let n = stem.len();
+                    stem[n - 1] = "i"; // sthāghvor ic ca (1.2.17)
+                }
+                let stem = augment(&sa(stem));
+                let mut ending = word(S_A[index]);
+                let last = stem[stem.len() - 1];
+                if ending[0] == "s" && matches!(ending[1], "t" | "th") && (is_short(last) || is_jhal(last)) {
+                    ending.remove(0); // hrasvād aṅgāt, jhalo jhali (8.2.27, 8.2.26)
+                }
+                attach(&stem, &murdhanya(&stem, ending))
+            }
+            Aorist::S => {
+                let stem = augment(&sa(aorist_vrddhi(r, root)));
+                let mut ending = word(S_P[index]);
+                if ending[1] == "t" && is_jhal(stem[stem.len() - 1]) {
+                    ending.remove(0);
+                }
+                attach(&stem, &ending)
+            }
+            Aorist::Is => {
+                let (stem, ending) = if atmane {
+                    (base(d, r, root), IS_A[index])
+                } else {
+                    (is_base(r, root), IS_P[index])
+                };
+                let mut ending = word(ending);
+                if r == "grah" && ending[0] == "i" {
+                    ending[0] = "I";
+                }
+                attach(&augment(&stem), &ending)
+            }
+            Aorist::Sis => attach(&augment(&eca(root)), &word(SIS_P[index])),
+            Aorist::Ksa => {
+                let mut stem = attach(root, &["s", "a"]);
+                stem.pop();
+                let stem = augment(&stem);
+                attach(&stem, &word(if atmane { KSA_A[index] } else { lan }))
+            }
+        };
+        (kind, form)
+    }).collect()
+}
+
+/// Luṅ with sic elided: bhū keeps its ū with vuk before a vowel (6.4.88),
+/// roots in ā drop it before us (6.1.96).
+fn root_aorist(root: &Word, index: usize) -> Vec<Word> {
+    const ROOT_P: Endings = ["t", "tAm", "uH", "s", "tam", "ta", "am", "va", "ma"];
+    let stem = augment(root);
+    let ending = word(ROOT_P[index]);
+    if stem.last() == Some(&"U") && is_vowel(ending[0]) {
+        let ending = if index == 2 { word("an") } else { ending };
+        return vec![[stem, word("v"), ending].concat()];
+    }
+    if index == 2 {
+        return vec![attach(&stem[..stem.len() - 1], &ending)];
+    }
+    vec![attach(&stem, &ending)]
+}
+
+/// The root before aṅ: pat, vac and śās are replaced (7.4.19, 7.4.20,
+/// 6.4.34), and dṛś and roots in ṛ take guṇa (7.4.16).
+fn an_stem(r: &str, root: &Word) -> Word {
+    match r {
+        "pat" => word("papt"),
+        "vac" => word("voc"),
+        "zAs" => word("ziS"),
+        _ if r == "dRz" || root.last() == Some(&"R") => strengthen(root, guna),
+        _ => root.clone(),
+    }
+}
+
+/// The reduplicated stem before caṅ: the penultimate vowel shortened
+/// (7.4.1), and before a light root the reduplication treated as before san
+/// (7.4.93, 7.4.79) and lengthened when light (7.4.94). Roots in a lose
+/// their a before ṇic, which blocks both.
+fn can_stem(r: &str, root: &Word) -> Word {
+    let adanta = ADANTA.contains(&r);
+    let mut body = root.clone();
+    let Some(v) = last_vowel(&body) else { return body };
+    if !adanta && v + 1 < body.len() {
+        body[v] = short(body[v]);
+    }
+    let mut abhyasa = abhyasa(&body);
+    let light = !adanta && is_short(body[v]) && body.len() - v == 2;
+    if light {
+        let a = abhyasa.len() - 1;
+        if abhyasa[a] == "a" {
+            abhyasa[a] = "i";
+        }
+        if body[..v].len() == 1 {
+            abhyasa[a] = long(abhyasa[a]);
+        }
+    }
+    [abhyasa, body].concat()
+}
+
+/// Vṛddhi of the root before sic in parasmaipada (7.2.1, 7.2.3), with am
+/// for dṛś, sṛj and spṛś.
+fn aorist_vrddhi(r: &str, root: &Word) -> Word {
+    let mut root = match r {
+        "dRz" | "sRj" | "spRz" => anit_am(r),
+        _ => root.clone(),
+    };
+    if let Some(v) = last_vowel(&root) {
+        let grade = vrddhi(root[v]);
+        root.splice(v..=v, grade);
+    }
+    root
+}
+
+/// The root before sic with iṭ in parasmaipada: vṛddhi of a final vowel
+/// (7.2.1) but not of one before a consonant (7.2.4), except a before r or
+/// l and in vad (7.2.2, 7.2.3).
+fn is_base(r: &str, root: &Word) -> Word {
+    let n = root.len();
+    match r {
+        "jAgR" => return word("jAgar"),
+        "vad" => return word("vAd"),
+        _ => {}
+    }
+    if is_vowel(root[n - 1]) || (n >= 2 && root[n - 2] == "a" && matches!(root[n - 1], "r" | "l")) {
+        strengthen(root, vrddhi)
+    } else {
+        strengthen(root, guna)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vyakaran::dhatu::find;
+    use crate::vyakaran::varnas;
+
+    /// The nine forms of a lakara in HK, alternatives joined by "/".
+    fn paradigm(root: &str, lakara: Lakara, padi: Padi) -> String {
+        let dhatu = &find(&varnas(root))[0];
+        let mut cells = Vec::new();
+        for purusha in Purusha::ALL {
+            for vacana in Vacana::ALL {
+                let forms: Vec<String> = tinanta(dhatu, lakara, padi, purusha, vacana).iter()
+                    .map(|pada| pada.aksharas.iter().flat_map(|akshara| akshara.varnas.iter()).map(|varna| varna.hk).collect())
+                    .collect();
+                cells.push(forms.join("/"));
+            }
+        }
+        cells.join(" ")
+    }
+
+    fn check(root: &str, padi: Padi, expected: [&str; 10]) {
+        for (lakara, expected) in Lakara::ALL.into_iter().zip(expected) {
+            assert_eq!(paradigm(root, lakara, padi), expected, "{root} {lakara}");
+        }
+    }
+
+    #[test]
+    fn bhu() {
+        check("bhU", Padi::Parasmai, [
+            "bhavati bhavataH bhavanti bhavasi bhavathaH bhavatha bhavAmi bhavAvaH bhavAmaH",
+            "babhUva babhUvatuH babhUvuH babhUvitha babhUvathuH babhUva babhUva babhUviva babhUvima",
+            "bhavitA bhavitArau bhavitAraH bhavitAsi bhavitAsthaH bhavitAstha bhavitAsmi bhavitAsvaH bhavitAsmaH",
+            "bhaviSyati bhaviSyataH bhaviSyanti bhaviSyasi bhaviSyathaH bhaviSyatha bhaviSyAmi bhaviSyAvaH bhaviSyAmaH",
+            "bhavatu/bhavatAt bhavatAm bhavantu bhava/bhavatAt bhavatam bhavata bhavAni bhavAva bhavAma",
+            "abhavat abhavatAm abhavan abhavaH abhavatam abhavata abhavam abhavAva abhavAma",
+            "bhavet bhavetAm bhaveyuH bhaveH bhavetam bhaveta bhaveyam bhaveva bhavema",
+            "bhUyAt bhUyAstAm bhUyAsuH bhUyAH bhUyAstam bhUyAsta bhUyAsam bhUyAsva bhUyAsma",
+            "abhUt abhUtAm abhUvan abhUH abhUtam abhUta abhUvam abhUva abhUma",
+            "abhaviSyat abhaviSyatAm abhaviSyan abhaviSyaH abhaviSyatam abhaviSyata abhaviSyam abhaviSyAva abhaviSyAma",
+        ]);
+        assert_eq!(paradigm("bhU", Lakara::Lat, Padi::Atmane), "        ");
+    }
+
+    #[test]
+    fn as_() {
+        check("as", Padi::Parasmai, [
+            "asti staH santi asi sthaH stha asmi svaH smaH",
+            "babhUva babhUvatuH babhUvuH babhUvitha babhUvathuH babhUva babhUva babhUviva babhUvima",
+            "bhavitA bhavitArau bhavitAraH bhavitAsi bhavitAsthaH bhavitAstha bhavitAsmi bhavitAsvaH bhavitAsmaH",
+            "bhaviSyati bhaviSyataH bhaviSyanti bhaviSyasi bhaviSyathaH bhaviSyatha bhaviSyAmi bhaviSyAvaH bhaviSyAmaH",
+            "astu/stAt stAm santu edhi/stAt stam sta asAni asAva asAma",
+            "AsIt AstAm Asan AsIH Astam Asta Asam Asva Asma",
+            "syAt syAtAm syuH syAH syAtam syAta syAm syAva syAma",
+            "bhUyAt bhUyAstAm bhUyAsuH bhUyAH bhUyAstam bhUyAsta bhUyAsam bhUyAsva bhUyAsma",
+            "abhUt abhUtAm abhUvan abhUH abhUtam abhUta abhUvam abhUva abhUma",
+            "abhaviSyat abhaviSyatAm abhaviSyan abhaviSyaH abhaviSyatam abhaviSyata abhaviSyam abhaviSyAva abhaviSyAma",
+        ]);
+    }
+
+    #[test]
+    fn kr() {
+        check("kR", Padi::Parasmai, [
+            "karoti kurutaH kurvanti karoSi kuruthaH kurutha karomi kurvaH kurmaH",
+            "cakAra cakratuH cakruH cakartha cakrathuH cakra cakAra/cakara cakRva cakRma",
+            "kartA kartArau kartAraH kartAsi kartAsthaH kartAstha kartAsmi kartAsvaH kartAsmaH",
+            "kariSyati kariSyataH kariSyanti kariSyasi kariSyathaH kariSyatha kariSyAmi kariSyAvaH kariSyAmaH",
+            "karotu/kurutAt kurutAm kurvantu kuru/kurutAt kurutam kuruta karavANi karavAva karavAma",
+            "akarot akurutAm akurvan akaroH akurutam akuruta akaravam akurva akurma",
+            "kuryAt kuryAtAm kuryuH kuryAH kuryAtam kuryAta kuryAm kuryAva kuryAma",
+            "kriyAt kriyAstAm kriyAsuH kriyAH kriyAstam kriyAsta kriyAsam kriyAsva kriyAsma",
+            "akArSIt akArSTAm akArSuH akArSIH akArSTam akArSTa akArSam akArSva akArSma",
+            "akariSyat akariSyatAm akariSyan akariSyaH akariSyatam akariSyata akariSyam akariSyAva akariSyAma",
+        ]);
+        check("kR", Padi::Atmane, [
+            "kurute kurvAte kurvate kuruSe kurvAthe kurudhve kurve kurvahe kurmahe",
+            "cakre cakrAte cakrire cakRSe cakrAthe cakRDhve cakre cakRvahe cakRmahe",
+            "kartA kartArau kartAraH kartAse kartAsAthe kartAdhve kartAhe kartAsvahe kartAsmahe",
+            "kariSyate kariSyete kariSyante kariSyase kariSyethe kariSyadhve kariSye kariSyAvahe kariSyAmahe",
+            "kurutAm kurvAtAm kurvatAm kuruSva kurvAthAm kurudhvam karavai karavAvahai karavAmahai",
+            "akuruta akurvAtAm akurvata akuruthAH akurvAthAm akurudhvam akurvi akurvahi akurmahi",
+            "kurvIta kurvIyAtAm kurvIran kurvIthAH kurvIyAthAm kurvIdhvam kurvIya kurvIvahi kurvImahi",
+            "kRSISTa kRSIyAstAm kRSIran kRSISThAH kRSIyAsthAm kRSIDhvam kRSIya kRSIvahi kRSImahi",
+            "akRta akRSAtAm akRSata akRthAH akRSAthAm akRDhvam akRSi akRSvahi akRSmahi",
+            "akariSyata akariSyetAm akariSyanta akariSyathAH akariSyethAm akariSyadhvam akariSye akariSyAvahi akariSyAmahi",
+        ]);
+    }
+
+    #[test]
+    fn vid() {
+        check("vid", Padi::Parasmai, [
+            "vetti/veda vittaH/vidatuH vidanti/viduH vetsi/vettha vitthaH/vidathuH vittha/vida vedmi/veda vidvaH/vidva vidmaH/vidma",
+            "viveda/vidAMcakAra/vidAMbabhUva/vidAmAsa vividatuH/vidAMcakratuH/vidAMbabhUvatuH/vidAmAsatuH vividuH/vidAMcakruH/vidAMbabhUvuH/vidAmAsuH \
+             viveditha/vidAMcakartha/vidAMbabhUvitha/vidAmAsitha vividathuH/vidAMcakrathuH/vidAMbabhUvathuH/vidAmAsathuH vivida/vidAMcakra/vidAMbabhUva/vidAmAsa \
+             viveda/vidAMcakAra/vidAMcakara/vidAMbabhUva/vidAmAsa vividiva/vidAMcakRva/vidAMbabhUviva/vidAmAsiva vividima/vidAMcakRma/vidAMbabhUvima/vidAmAsima",
+            "veditA veditArau veditAraH veditAsi veditAsthaH veditAstha veditAsmi veditAsvaH veditAsmaH",
+            "vediSyati vediSyataH vediSyanti vediSyasi vediSyathaH vediSyatha vediSyAmi vediSyAvaH vediSyAmaH",
+            "vettu/vittAt vittAm vidantu viddhi/vittAt vittam vitta vedAni vedAva vedAma",
+            "avet avittAm aviduH avet/aveH avittam avitta avedam avidva avidma",
+            "vidyAt vidyAtAm vidyuH vidyAH vidyAtam vidyAta vidyAm vidyAva vidyAma",
+            "vidyAt vidyAstAm vidyAsuH vidyAH vidyAstam vidyAsta vidyAsam vidyAsva vidyAsma",
+            "avedIt avediSTAm avediSuH avedIH avediSTam avediSTa avediSam avediSva avediSma",
+            "avediSyat avediSyatAm avediSyan avediSyaH avediSyatam avediSyata avediSyam avediSyAva avediSyAma",
+        ]);
+    }
+
+    #[test]
+    fn shi() {
+        assert_eq!(paradigm("zI", Lakara::Lat, Padi::Atmane), "zete zayAte zerate zeSe zayAthe zedhve zaye zevahe zemahe");
+        assert_eq!(paradigm("zI", Lakara::Lan, Padi::Atmane), "azeta azayAtAm azerata azethAH azayAthAm azedhvam azayi azevahi azemahi");
+        assert_eq!(paradigm("zI", Lakara::VidhiLin, Padi::Atmane), "zayIta zayIyAtAm zayIran zayIthAH zayIyAthAm zayIdhvam zayIya zayIvahi zayImahi");
+    }
+
+    #[test]
+    fn special_forms() {
+        assert_eq!(paradigm("zru", Lakara::Lat, Padi::Parasmai), "zRNoti zRNutaH zRNvanti zRNoSi zRNuthaH zRNutha zRNomi zRNuvaH/zRNvaH zRNumaH/zRNmaH");
+        assert_eq!(paradigm("yuj", Lakara::Lot, Padi::Parasmai).split(' ').nth(3), Some("yuGgdhi/yuGktAt"));
+        assert_eq!(paradigm("prach", Lakara::Lit, Padi::Parasmai).split(' ').next(), Some("papraccha"));
+        assert_eq!(paradigm("krI", Lakara::Lit, Padi::Parasmai).split(' ').nth(7), Some("cikriyiva"));
+        assert_eq!(paradigm("zAs", Lakara::Lan, Padi::Parasmai).split(' ').nth(3), Some("azAH/azAt"));
+        assert_eq!(conjugate(find(&varnas("bhU"))[0]).len(), 92);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("lit".parse::<Lakara>(), Ok(Lakara::Lit));
+        assert_eq!("AshirLin".parse::<Lakara>(), Ok(Lakara::AshirLin));
+        assert_eq!(Purusha::Uttama.to_string(), "uttama");
+        assert!("let".parse::<Lakara>().is_err());
+    }
+}
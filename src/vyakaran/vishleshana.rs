@@ -0,0 +1,192 @@
+// Morphological analysis: the reverse of declension and conjugation. Every
+// form the generators make from the Dhatupatha and the stem list is indexed
+// once, so a word is analysed exactly as it would be generated.
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+use crate::shiksha::{Pada, TransliterationScheme, Varna};
+use crate::vyakaran::dhatu::{dhatupatha, Dhatu, Gana, Padi};
+use crate::vyakaran::subanta::{decline, pratipadikas, Pratipadika, Vibhakti};
+use crate::vyakaran::tinanta::{ardhadhatuka, conjugate, Lakara, Purusha, Tinanta, Vacana};
+use crate::vyakaran::varnas;
+
+/// One reading of a word.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Analysis {
+    Tinanta {
+        dhatu: &'static Dhatu,
+        lakara: Lakara,
+        purusha: Purusha,
+        vacana: Vacana,
+        padi: Padi,                 // Parasmai or Atmane
+        pratyayas: Vec<Vec<Varna>>, // Ṇic, the vikarana, ām and the tense suffix, as far as they stand in the form
+    },
+    Subanta {
+        pratipadika: &'static Pratipadika, // Its gender and derivational suffixes as well
+        vibhakti: Vibhakti,
+        vacana: Vacana,
+    },
+}
+
+/// Shows the analysis as its stem or root in IAST and its features; asmad
+/// and yuṣmad are shown without a gender, having none.
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Analysis::Tinanta { dhatu, lakara, purusha, vacana, padi, .. } => {
+                write!(f, "{} ({}) {} {} {} {}", dhatu.render(TransliterationScheme::Iast), dhatu.gana.number(), lakara, purusha, vacana, padi)
+            }
+            Analysis::Subanta { pratipadika, vibhakti, vacana } if !pratipadika.gendered() => {
+                write!(f, "{} {} {}", pratipadika.render(TransliterationScheme::Iast), vibhakti, vacana)
+            }
+            Analysis::Subanta { pratipadika, vibhakti, vacana } => {
+                write!(f, "{} {} {} {}", pratipadika.render(TransliterationScheme::Iast), pratipadika.linga, vibhakti, vacana)
+            }
+        }
+    }
+}
+
+/// Returns every analysis of a word as a form of a root of the Dhatupatha or
+/// a stem of the stem list, verbs first. A word the grammar cannot make has none.
+pub fn analyze(pada: &Pada) -> Vec<Analysis> {
+    index().get(&key(pada)).cloned().unwrap_or_default()
+}
+
+/// The word as the Harvard-Kyoto spellings of its Varnas, accents left out.
+fn key(pada: &Pada) -> String {
+    pada.aksharas.iter().flat_map(|akshara| akshara.varnas.iter()).map(|varna| varna.hk).collect()
+}
+
+fn index() -> &'static HashMap<String, Vec<Analysis>> {
+    static INDEX: OnceLock<HashMap<String, Vec<Analysis>>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index: HashMap<String, Vec<Analysis>> = HashMap::new();
+        for dhatu in dhatupatha() {
+            for form in conjugate(dhatu) {
+                let pratyayas = pratyayas(dhatu, &form).iter().map(|pratyaya| varnas(pratyaya)).collect();
+                index.entry(key(&form.pada)).or_default().push(Analysis::Tinanta {
+                    dhatu,
+                    lakara: form.lakara,
+                    purusha: form.purusha,
+                    vacana: form.vacana,
+                    padi: form.padi,
+                    pratyayas,
+                });
+            }
+        }
+        for pratipadika in pratipadikas() {
+            for form in decline(&pratipadika.stem, pratipadika.linga) {
+                index.entry(key(&form.pada)).or_default().push(Analysis::Subanta {
+                    pratipadika,
+                    vibhakti: form.vibhakti,
+                    vacana: form.vacana,
+                });
+            }
+        }
+        index
+    })
+}
+
+/// The suffixes between a root and its ending: ṇic of the tenth class
+/// (3.1.25), the vikarana of the class in the sārvadhātuka lakaras (3.1.68
+/// to 3.1.81), the tense suffixes tāsi and sya (3.1.33), and ām of liṭ,
+/// yāsuṭ or sīyuṭ of āśīrliṅ and the substitute of cli in luṅ.
+fn pratyayas(dhatu: &Dhatu, form: &Tinanta) -> Vec<&'static str> {
+    let mut pratyayas = Vec::new();
+    if dhatu.gana == Gana::Curadi {
+        pratyayas.push("Nic");
+    }
+    match form.lakara {
+        Lakara::Lat | Lakara::Lot | Lakara::Lan | Lakara::VidhiLin => pratyayas.extend(match dhatu.gana {
+            Gana::Bhvadi | Gana::Curadi => Some("zap"),
+            Gana::Adadi | Gana::Juhotyadi => None, // śap is elided (2.4.72, 2.4.75)
+            Gana::Divadi => Some("zyan"),
+            Gana::Svadi => Some("znu"),
+            Gana::Tudadi => Some("za"),
+            Gana::Rudhadi => Some("znam"),
+            Gana::Tanadi => Some("u"),
+            Gana::Kryadi => Some("znA"),
+        }),
+        Lakara::Lut => pratyayas.push("tAsi"),
+        Lakara::Lrt | Lakara::Lrn => pratyayas.push("sya"),
+        Lakara::Lit | Lakara::AshirLin | Lakara::Lun => pratyayas.extend(ardhadhatuka(dhatu, form)),
+    }
+    pratyayas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::niruktam::{Lexer, Token};
+    use crate::vyakaran::{subanta, tinanta, Linga};
+
+    fn pada(hk: &str) -> Pada {
+        let aksharas = Lexer::with_scheme(hk, TransliterationScheme::HarvardKyoto).tokenize().into_iter().filter_map(|token| match token {
+            Token::Akshara(akshara, _) => Some(akshara),
+            Token::Unknown(..) => None,
+        }).collect();
+        Pada::new(aksharas)
+    }
+
+    fn shown(hk: &str) -> Vec<String> {
+        analyze(&pada(hk)).iter().map(|analysis| analysis.to_string()).collect()
+    }
+
+    #[test]
+    fn verbs() {
+        assert_eq!(shown("bhavati"), ["bhū (1) lat prathama ekavacana parasmaipada"]);
+        assert_eq!(shown("kurvanti"), ["kṛ (8) lat prathama bahuvacana parasmaipada"]);
+        let Analysis::Tinanta { pratyayas, .. } = &analyze(&pada("corayiSyati"))[0] else { panic!("not a verb") };
+        assert_eq!(pratyayas, &[varnas("Nic"), varnas("sya")]);
+    }
+
+    #[test]
+    fn ardhadhatuka_pratyayas() {
+        let pratyayas = |hk: &str| match analyze(&pada(hk)).remove(0) {
+            Analysis::Tinanta { pratyayas, .. } => pratyayas,
+            Analysis::Subanta { .. } => panic!("{hk} is not a verb"),
+        };
+        assert_eq!(pratyayas("vidAMcakAra"), [varnas("Am")]);
+        assert!(pratyayas("viveda").is_empty());
+        assert_eq!(pratyayas("corayAMcakAra"), [varnas("Nic"), varnas("Am")]);
+        assert_eq!(pratyayas("bhUyAt"), [varnas("yAsuT")]);
+        assert_eq!(pratyayas("akArSIt"), [varnas("sic")]);
+        assert_eq!(pratyayas("agamat"), [varnas("aG")]);
+    }
+
+    #[test]
+    fn no_analysis_for_wrong_forms() {
+        for wrong in ["zerIran", "avidan", "vedAMcakAra", "gacchatI"] {
+            assert!(analyze(&pada(wrong)).is_empty(), "{wrong}");
+        }
+        assert_eq!(shown("zayIran"), ["śī (2) vidhilin prathama bahuvacana atmanepada"]);
+        assert_eq!(shown("aviduH"), ["vid (2) lan prathama bahuvacana parasmaipada"]);
+        assert_eq!(shown("vidAMcakAra").len(), 2);
+    }
+
+    #[test]
+    fn nouns() {
+        assert_eq!(shown("rAmeNa"), ["rāma pumlinga trtiya ekavacana"]);
+        assert_eq!(shown("rAmau"), [
+            "rāma pumlinga prathama dvivacana",
+            "rāma pumlinga dvitiya dvivacana",
+            "rāma pumlinga sambodhana dvivacana",
+        ]);
+        assert!(shown("tasya").contains(&"tad napumsakalinga sasthi ekavacana".to_string()));
+        assert_eq!(shown("te").iter().filter(|shown| shown.starts_with("yuṣmad")).collect::<Vec<_>>(), ["yuṣmad caturthi ekavacana", "yuṣmad sasthi ekavacana"]);
+        let Analysis::Subanta { pratipadika, .. } = &analyze(&pada("bhagavatyAH"))[0] else { panic!("not a noun") };
+        assert_eq!((pratipadika.linga, pratipadika.prakriti.clone()), (Linga::Stri, Some(varnas("bhaga"))));
+        assert!(analyze(&pada("xyz")).is_empty());
+    }
+
+    #[test]
+    fn agrees_with_generation() {
+        for analysis in analyze(&pada("asti")).into_iter().chain(analyze(&pada("manasA"))) {
+            let forms = match &analysis {
+                Analysis::Tinanta { dhatu, lakara, purusha, vacana, padi, .. } => tinanta(dhatu, *lakara, *padi, *purusha, *vacana),
+                Analysis::Subanta { pratipadika, vibhakti, vacana } => subanta(&pratipadika.stem, pratipadika.linga, *vibhakti, *vacana),
+            };
+            assert!(forms.iter().any(|form| key(form) == "asti" || key(form) == "manasA"), "{analysis}");
+        }
+    }
+}